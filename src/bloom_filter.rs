@@ -0,0 +1,249 @@
+use crate::protos::orc_proto;
+
+
+/// A bloom filter over the values of a row group, compatible with `BloomFilterUtf8` of the Java
+/// implementation: values are hashed to 64 bits (Murmur3 for bytes, Thomas Wang's hash for integers)
+/// and the two halves of the hash are combined to derive the bit of each hash function.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct BloomFilter {
+    num_hash_functions: u32,
+    bits: Vec<u64>,
+}
+
+/// Value of `ColumnEncoding.bloomEncoding` for bloom filters hashing strings as UTF-8 and timestamps
+/// as UTC milliseconds.
+pub(crate) const BLOOM_ENCODING_UTF8_UTC: u32 = 1;
+
+const MURMUR3_SEED: u64 = 104729;
+
+impl BloomFilter {
+    /// Creates a bloom filter sized for `expected_entries` values with a false positive
+    /// probability of `fpp`.
+    pub fn new(expected_entries: u64, fpp: f64) -> Self {
+        let expected_entries = expected_entries.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let optimal_bits = (-expected_entries * fpp.ln() / (ln2 * ln2)) as u64;
+        // Rounded past the next multiple of 64, as in the Java implementation
+        let num_bits = optimal_bits + (64 - optimal_bits % 64);
+        let num_hash_functions = ((num_bits as f64 / expected_entries * ln2).round() as u32).max(1);
+        BloomFilter {
+            num_hash_functions,
+            bits: vec![0; (num_bits / 64) as usize],
+        }
+    }
+
+    /// Reads a bloom filter from a `BLOOM_FILTER_UTF8` stream. Returns `None` if it is malformed.
+    pub fn from_proto(bloom_filter: &orc_proto::BloomFilter) -> Option<Self> {
+        let bitset = bloom_filter.get_utf8bitset();
+        let words = bitset.chunks_exact(8);
+        if bitset.is_empty() || !words.remainder().is_empty() || bitset.len() * 8 > i32::MAX as usize
+                || bloom_filter.get_numHashFunctions() == 0 {
+            return None;
+        }
+        Some(BloomFilter {
+            num_hash_functions: bloom_filter.get_numHashFunctions(),
+            bits: words.map(|x| u64::from_le_bytes([x[0], x[1], x[2], x[3], x[4], x[5], x[6], x[7]])).collect(),
+        })
+    }
+
+    pub fn add_bytes(&mut self, x: &[u8]) {
+        self.add_hash(murmur3_hash64(x));
+    }
+
+    pub fn add_long(&mut self, x: i64) {
+        self.add_hash(long_hash(x));
+    }
+
+    /// Adds a decimal with the given unscaled value and scale. As in the Java implementation, the
+    /// decimal is hashed as a string, without trailing zeros in the fractional part.
+    pub fn add_decimal(&mut self, unscaled: i128, scale: u32) {
+        self.add_bytes(format_decimal(unscaled, scale).as_bytes());
+    }
+
+    pub fn add_double(&mut self, x: f64) {
+        self.add_long(double_bits(x));
+    }
+
+    fn add_hash(&mut self, hash: u64) {
+        let num_bits = self.bits.len() as i32 * 64;
+        for pos in bit_positions(hash, self.num_hash_functions, num_bits) {
+            self.bits[pos / 64] |= 1 << (pos % 64);
+        }
+    }
+
+    /// Whether the bloom filter may contain `x`. `false` means that it definitely does not.
+    pub fn might_contain_bytes(&self, x: &[u8]) -> bool {
+        self.test_hash(murmur3_hash64(x))
+    }
+
+    pub fn might_contain_long(&self, x: i64) -> bool {
+        self.test_hash(long_hash(x))
+    }
+
+    pub fn might_contain_decimal(&self, unscaled: i128, scale: u32) -> bool {
+        self.might_contain_bytes(format_decimal(unscaled, scale).as_bytes())
+    }
+
+    pub fn might_contain_double(&self, x: f64) -> bool {
+        // 0.0 and -0.0 are equal but hashed differently
+        if x == 0.0 {
+            self.might_contain_long(double_bits(0.0)) || self.might_contain_long(double_bits(-0.0))
+        } else {
+            self.might_contain_long(double_bits(x))
+        }
+    }
+
+    fn test_hash(&self, hash: u64) -> bool {
+        let num_bits = self.bits.len() as i32 * 64;
+        bit_positions(hash, self.num_hash_functions, num_bits).all(|pos| self.bits[pos / 64] & (1 << (pos % 64)) != 0)
+    }
+
+    pub fn to_proto(&self) -> orc_proto::BloomFilter {
+        let mut bloom_filter = orc_proto::BloomFilter::new();
+        bloom_filter.set_numHashFunctions(self.num_hash_functions);
+        bloom_filter.set_utf8bitset(self.bits.iter().flat_map(|x| x.to_le_bytes().to_vec()).collect());
+        bloom_filter
+    }
+}
+
+/// The bits of a double as returned by Java's `Double.doubleToLongBits`, which collapses all NaNs
+/// to a single value.
+fn double_bits(x: f64) -> i64 {
+    let bits = if x.is_nan() { 0x7ff8000000000000 } else { x.to_bits() };
+    bits as i64
+}
+
+fn format_decimal(unscaled: i128, scale: u32) -> String {
+    let digits = unscaled.unsigned_abs().to_string();
+    let sign = if unscaled < 0 { "-" } else { "" };
+    let scale = scale as usize;
+    if scale == 0 {
+        return format!("{}{}", sign, digits);
+    }
+    let digits = format!("{:0>1$}", digits, scale + 1);
+    let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+    let frac_part = frac_part.trim_end_matches('0');
+    if frac_part.is_empty() {
+        format!("{}{}", sign, int_part)
+    } else {
+        format!("{}{}.{}", sign, int_part, frac_part)
+    }
+}
+
+/// The bits set for a value with the given hash.
+fn bit_positions(hash: u64, num_hash_functions: u32, num_bits: i32) -> impl Iterator<Item = usize> {
+    let hash1 = hash as i32;
+    let hash2 = (hash >> 32) as i32;
+    (1..=num_hash_functions as i32).map(move |i| {
+        let combined = hash1.wrapping_add(i.wrapping_mul(hash2));
+        // Flip all bits of negative hashes to make them positive
+        let combined = if combined < 0 { !combined } else { combined };
+        (combined % num_bits) as usize
+    })
+}
+
+/// Thomas Wang's 64-bit integer hash.
+fn long_hash(key: i64) -> u64 {
+    let mut key = (!key).wrapping_add(key << 21);
+    key ^= key >> 24;
+    key = key.wrapping_add(key << 3).wrapping_add(key << 8);
+    key ^= key >> 14;
+    key = key.wrapping_add(key << 2).wrapping_add(key << 4);
+    key ^= key >> 28;
+    key = key.wrapping_add(key << 31);
+    key as u64
+}
+
+/// The 64-bit variant of Murmur3 of the Java implementation (`Murmur3.hash64`).
+fn murmur3_hash64(data: &[u8]) -> u64 {
+    const C1: u64 = 0x87c37b91114253d5;
+    const C2: u64 = 0x4cf5ad432745937f;
+    const M: u64 = 5;
+    const N1: u64 = 0x52dce729;
+
+    let mut hash = MURMUR3_SEED;
+    let mut blocks = data.chunks_exact(8);
+    for block in &mut blocks {
+        let mut k = u64::from_le_bytes([block[0], block[1], block[2], block[3], block[4], block[5], block[6], block[7]]);
+        k = k.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2);
+        hash ^= k;
+        hash = hash.rotate_left(27).wrapping_mul(M).wrapping_add(N1);
+    }
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0u64;
+        for (i, &b) in tail.iter().enumerate() {
+            k ^= (b as u64) << (8 * i);
+        }
+        k = k.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2);
+        hash ^= k;
+    }
+
+    hash ^= data.len() as u64;
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^= hash >> 33;
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashes() {
+        // Values of `BloomFilter.getLongHash` and `Murmur3.hash64` of the Java implementation
+        assert_eq!(long_hash(0), 0);
+        assert_eq!(long_hash(1), 0x5bca7c69b794f8ce);
+        assert_eq!(long_hash(-1), 0x5bca868437950d03);
+        assert_eq!(murmur3_hash64(b""), 0x74a18dc8f20adb48);
+        assert_eq!(murmur3_hash64(b"hello"), 0x3928100018224141);
+        assert_eq!(murmur3_hash64(b"hello, world!"), 0xaeb2bded11d1b710);
+    }
+
+    #[test]
+    fn test_format_decimal() {
+        assert_eq!(format_decimal(12345, 2), "123.45");
+        assert_eq!(format_decimal(12300, 3), "12.3");
+        assert_eq!(format_decimal(-5, 3), "-0.005");
+        assert_eq!(format_decimal(1000, 2), "10");
+        assert_eq!(format_decimal(0, 4), "0");
+        assert_eq!(format_decimal(-42, 0), "-42");
+        assert_eq!(format_decimal(i128::MIN, 38), "-1.70141183460469231731687303715884105728");
+    }
+
+    #[test]
+    fn test_bloom_filter() {
+        let bloom_filter = BloomFilter::new(10000, 0.05);
+        assert_eq!(bloom_filter.bits.len(), 975);
+        assert_eq!(bloom_filter.num_hash_functions, 4);
+
+        let mut bloom_filter = BloomFilter::new(300, 0.01);
+        for i in 0..100 {
+            bloom_filter.add_long(i * 7);
+            bloom_filter.add_bytes(format!("value-{}", i).as_bytes());
+        }
+        bloom_filter.add_double(f64::NAN);
+        bloom_filter.add_double(-0.0);
+        bloom_filter.add_decimal(1250, 2);
+        for i in 0..100 {
+            assert!(bloom_filter.might_contain_long(i * 7));
+            assert!(bloom_filter.might_contain_bytes(format!("value-{}", i).as_bytes()));
+        }
+        assert!(bloom_filter.might_contain_double(-f64::NAN));
+        assert!(bloom_filter.might_contain_double(0.0));
+        assert!(bloom_filter.might_contain_decimal(125, 1));
+        let false_positives = (1000..2000).filter(|&i| bloom_filter.might_contain_long(i * 7 + 1)).count();
+        assert!(false_positives < 50, "{} false positives", false_positives);
+
+        let proto = bloom_filter.to_proto();
+        assert_eq!(BloomFilter::from_proto(&proto), Some(bloom_filter.clone()));
+        assert_eq!(proto.get_numHashFunctions(), bloom_filter.num_hash_functions);
+        assert_eq!(proto.get_utf8bitset().len(), bloom_filter.bits.len() * 8);
+        let bitset = proto.get_utf8bitset();
+        assert_eq!(u64::from_le_bytes([bitset[0], bitset[1], bitset[2], bitset[3], bitset[4], bitset[5], bitset[6], bitset[7]]),
+            bloom_filter.bits[0]);
+    }
+}
@@ -1,3 +1,4 @@
+use std::io::{self, Write};
 use std::ops::{Deref, DerefMut};
 
 /// Simple wrapper around a Vec<u8>, which we use for all our substantial memory allocations.
@@ -18,10 +19,6 @@ impl Buffer {
         }
     }
 
-    pub fn capacity(&self) -> usize {
-        self.data.capacity()
-    }
-
     pub fn resize(&mut self, new_len: usize) {
         self.data.resize(new_len, 0);
     }
@@ -53,3 +50,14 @@ impl DerefMut for Buffer {
         &mut self.data
     }
 }
+
+impl Write for Buffer {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.write_bytes(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt;
+use std::io;
+
+/// Errors of the writer. Errors concerning a column carry its id, along with its path in the
+/// schema: dot-separated field names, with `_elem` for list elements, `_key` and `_value` for map
+/// keys and values, and the index of union variants (the root column has the empty path).
+#[derive(Debug)]
+pub enum OrcError {
+    Io(io::Error),
+    /// A column was accessed as a different type than the one in the schema.
+    SchemaMismatch { column_id: u32, path: String, expected: &'static str, actual: &'static str },
+    /// The number of values written to a column in a batch does not match the number of rows.
+    RowCountMismatch { column_id: u32, path: String, expected: u64, actual: u64 },
+    InvalidConfig(String),
+    Compression(String),
+    /// A value can't be represented in the type of its column.
+    ValueOutOfRange { column_id: u32, path: String, message: String },
+}
+
+impl fmt::Display for OrcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrcError::Io(e) => write!(f, "{}", e),
+            OrcError::SchemaMismatch { column_id, path, expected, actual } =>
+                write!(f, "column {} ({:?}) was accessed as {} but has type {}", column_id, path, expected, actual),
+            OrcError::RowCountMismatch { column_id, path, expected, actual } =>
+                write!(f, "in column {} ({:?}), the number of values written ({}) does not match the expected number ({})",
+                    column_id, path, actual, expected),
+            OrcError::InvalidConfig(msg) => write!(f, "invalid configuration: {}", msg),
+            OrcError::Compression(msg) => write!(f, "compression failed: {}", msg),
+            OrcError::ValueOutOfRange { column_id, path, message } =>
+                write!(f, "value out of range in column {} ({:?}): {}", column_id, path, message),
+        }
+    }
+}
+
+impl std::error::Error for OrcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OrcError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+// Internally the writer passes errors around as `io::Error`, which an `OrcError` converts to and
+// back from without losing its variant.
+impl From<io::Error> for OrcError {
+    fn from(e: io::Error) -> Self {
+        if e.get_ref().map_or(false, |inner| inner.is::<OrcError>()) {
+            *e.into_inner().unwrap().downcast::<OrcError>().unwrap()
+        } else {
+            OrcError::Io(e)
+        }
+    }
+}
+
+impl From<OrcError> for io::Error {
+    fn from(e: OrcError) -> Self {
+        match e {
+            OrcError::Io(e) => e,
+            OrcError::InvalidConfig(_) => io::Error::new(io::ErrorKind::InvalidInput, e),
+            _ => io::Error::new(io::ErrorKind::Other, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_io_error_conversion() {
+        let e = io::Error::from(OrcError::RowCountMismatch { column_id: 2, path: "a.b".to_owned(), expected: 5, actual: 4 });
+        assert_eq!(e.kind(), io::ErrorKind::Other);
+        match OrcError::from(e) {
+            OrcError::RowCountMismatch { column_id: 2, path, expected: 5, actual: 4 } => assert_eq!(path, "a.b"),
+            e => panic!("unexpected error {:?}", e),
+        }
+        let e = OrcError::from(io::Error::new(io::ErrorKind::UnexpectedEof, "eof"));
+        assert!(matches!(e, OrcError::Io(ref e) if e.kind() == io::ErrorKind::UnexpectedEof));
+    }
+}
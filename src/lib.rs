@@ -1,5 +1,7 @@
 
+mod bloom_filter;
 mod buffer;
+pub mod error;
 pub mod protos;
 pub mod reader;
 pub mod schema;
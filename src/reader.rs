@@ -1,22 +1,10 @@
-// use std::io::{Read, Seek};
-
-
-
-
-// pub struct Reader<T: Read + Seek> {
-//     inner: T,
-// }
-
-// impl<T: Read + Seek> Reader<T> {
-
-// }
-
-// pub struct LongReader {}
-
-// impl LongReader {
-//     pub fn skip(&mut self, values: u64) {}
-
-//     pub fn read(&mut self) -> Option<i64> {
-//         unimplemented!()
-//     }
-// }
\ No newline at end of file
+pub use metadata::{OrcFile, StripeInformation};
+pub use row_reader::{RowReader, RowReaderOptions};
+pub use search_argument::{SearchArgument, TruthValue};
+
+pub mod compression;
+pub mod data;
+mod decoder;
+pub mod metadata;
+mod row_reader;
+pub mod search_argument;
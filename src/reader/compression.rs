@@ -1,6 +1,312 @@
+use std::io::{Read, Result, Error, ErrorKind};
+use std::ops::Range;
+use std::sync::Arc;
 
-trait CompressionImpl {
-    fn decompressor(&self) -> Option<Box<dyn Decompressor>>;
+use crate::protos::orc_proto;
+use crate::buffer::Buffer;
+use common::Decompressor;
+use snappy::SnappyDecompressor;
+use self::zstd::ZstdDecompressor;
+use zlib::ZlibDecompressor;
+use lz4::Lz4Decompressor;
+use lzo::LzoDecompressor;
+
+mod common;
+mod snappy;
+mod zstd;
+mod zlib;
+mod lz4;
+mod lzo;
+
+const MAX_BLOCK_SIZE: u64 = 0x7fffff;
+
+/// The compression settings of a file, as given in its postscript.
+#[derive(Copy, Clone, Debug)]
+pub struct Compression {
+    kind: orc_proto::CompressionKind,
+    block_size: usize,
+}
+
+impl Compression {
+    pub(crate) fn new(kind: orc_proto::CompressionKind, block_size: u64) -> Result<Self> {
+        // Block lengths are stored in 23 bits, and the LZ4 decompressor allocates a full block up front.
+        if kind != orc_proto::CompressionKind::NONE && block_size > MAX_BLOCK_SIZE {
+            return Err(Error::new(ErrorKind::InvalidData, format!("invalid compression block size {}", block_size)));
+        }
+        Ok(Compression {
+            kind,
+            block_size: block_size as usize,
+        })
+    }
+
+    pub fn kind(&self) -> orc_proto::CompressionKind {
+        self.kind
+    }
+
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn decompressor(&self) -> Option<Box<dyn Decompressor>> {
+        match self.kind {
+            orc_proto::CompressionKind::NONE => None,
+            orc_proto::CompressionKind::SNAPPY => Some(Box::new(SnappyDecompressor::new())),
+            orc_proto::CompressionKind::ZSTD => Some(Box::new(ZstdDecompressor::new())),
+            orc_proto::CompressionKind::ZLIB => Some(Box::new(ZlibDecompressor::new())),
+            orc_proto::CompressionKind::LZ4 => Some(Box::new(Lz4Decompressor::new(self.block_size))),
+            orc_proto::CompressionKind::LZO => Some(Box::new(LzoDecompressor::new())),
+        }
+    }
+}
+
+/// Sequence of positions taken from a row index entry, consumed in order by the decoders
+/// of a column as they seek.
+pub(crate) struct PositionProvider<'a> {
+    positions: &'a [u64],
+    index: usize,
+}
+
+impl<'a> PositionProvider<'a> {
+    pub fn new(positions: &'a [u64]) -> Self {
+        PositionProvider { positions, index: 0 }
+    }
+
+    pub fn next(&mut self) -> Result<u64> {
+        match self.positions.get(self.index) {
+            Some(&x) => {
+                self.index += 1;
+                Ok(x)
+            }
+            None => Err(Error::new(ErrorKind::InvalidData, "row index entry has too few positions")),
+        }
+    }
+}
+
+/// Reads the uncompressed contents of a stream, inverting the block framing done by
+/// `writer::compression::CompressionStream`.
+pub(crate) struct DecompressionStream {
+    decompressor: Option<Box<dyn Decompressor>>,
+    // The stream is the range `input_start..input_end` of `input`, which may be shared with other
+    // streams; offsets below are within `input`
+    input: Arc<Vec<u8>>,
+    input_start: usize,
+    input_end: usize,
+    // Offset in `input` of the header of the next compression block
+    next_block: usize,
+    // The current block: either a range of `input` (for original or uncompressed data), or
+    // else the contents of `decompressed`.
+    block_range: Option<(usize, usize)>,
+    decompressed: Buffer,
+    // Offset within the current block
+    offset: usize,
+}
+
+impl DecompressionStream {
+    pub fn new(input: Vec<u8>, compression: &Compression) -> Self {
+        let len = input.len();
+        Self::new_shared(Arc::new(input), 0..len, compression)
+    }
+
+    /// A stream over the range `range` of `input`, without copying it.
+    pub fn new_shared(input: Arc<Vec<u8>>, range: Range<usize>, compression: &Compression) -> Self {
+        let decompressor = compression.decompressor();
+        let (next_block, block_range) = if decompressor.is_some() {
+            (range.start, Some((range.start, range.start)))
+        } else {
+            (range.end, Some((range.start, range.end)))
+        };
+        DecompressionStream {
+            decompressor,
+            input,
+            input_start: range.start,
+            input_end: range.end,
+            next_block,
+            block_range,
+            decompressed: Buffer::with_capacity(compression.block_size()),
+            offset: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn block(&self) -> &[u8] {
+        match self.block_range {
+            Some((start, end)) => &self.input[start..end],
+            None => &self.decompressed,
+        }
+    }
+
+    /// Loads the next compression block, returning false if the end of the stream was reached.
+    fn read_block(&mut self) -> Result<bool> {
+        if self.next_block >= self.input_end {
+            return Ok(false);
+        }
+        if self.next_block + 3 > self.input_end {
+            return Err(Error::new(ErrorKind::InvalidData, "truncated compression block header"));
+        }
+        let header = &self.input[self.next_block..self.next_block + 3];
+        let header = header[0] as usize | (header[1] as usize) << 8 | (header[2] as usize) << 16;
+        let is_original = header & 1 == 1;
+        let length = header >> 1;
+        let start = self.next_block + 3;
+        let end = start + length;
+        if end > self.input_end {
+            return Err(Error::new(ErrorKind::InvalidData, "compression block extends past end of stream"));
+        }
+        if is_original {
+            self.block_range = Some((start, end));
+        } else if let Some(decompressor) = &mut self.decompressor {
+            self.decompressed.resize(0);
+            decompressor.decompress(&self.input[start..end], &mut self.decompressed)?;
+            self.block_range = None;
+        } else {
+            unreachable!();
+        }
+        self.next_block = end;
+        self.offset = 0;
+        Ok(true)
+    }
+
+    /// Seeks to a position recorded by `CompressionStreamPosition::record`: the offset of the start of
+    /// the compression block (only present for compressed streams) followed by the offset within it.
+    pub fn seek(&mut self, positions: &mut PositionProvider) -> Result<()> {
+        if self.decompressor.is_some() {
+            let block_start = positions.next()? as usize;
+            let offset = positions.next()? as usize;
+            self.next_block = self.input_start.saturating_add(block_start);
+            self.block_range = Some((0, 0));
+            self.read_block()?;
+            if offset > self.block().len() {
+                return Err(Error::new(ErrorKind::InvalidData, "seek offset is past the end of the compression block"));
+            }
+            self.offset = offset;
+        } else {
+            let offset = positions.next()? as usize;
+            if offset > self.input_end - self.input_start {
+                return Err(Error::new(ErrorKind::InvalidData, "seek offset is past the end of the stream"));
+            }
+            self.offset = offset;
+        }
+        Ok(())
+    }
+
+    /// Skips over the next `num_bytes` bytes of the stream.
+    pub fn skip(&mut self, mut num_bytes: u64) -> Result<()> {
+        while num_bytes > 0 {
+            while self.offset >= self.block().len() {
+                if !self.read_block()? {
+                    return Err(Error::new(ErrorKind::UnexpectedEof, "unexpected end of stream"));
+                }
+            }
+            let len = num_bytes.min((self.block().len() - self.offset) as u64);
+            self.offset += len as usize;
+            num_bytes -= len;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn read_u8(&mut self) -> Result<u8> {
+        loop {
+            if let Some(&b) = self.block().get(self.offset) {
+                self.offset += 1;
+                return Ok(b);
+            }
+            if !self.read_block()? {
+                return Err(Error::new(ErrorKind::UnexpectedEof, "unexpected end of stream"));
+            }
+        }
+    }
+}
+
+impl Read for DecompressionStream {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        while self.offset >= self.block().len() {
+            if !self.read_block()? {
+                return Ok(0);
+            }
+        }
+        let block = self.block();
+        let len = buf.len().min(block.len() - self.offset);
+        buf[..len].copy_from_slice(&block[self.offset..self.offset + len]);
+        self.offset += len;
+        Ok(len)
+    }
 }
 
-pub struct Compression(Box<dyn CompressionImpl>);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use std::sync::Arc;
+    use crate::writer::compression::{self as writer_compression, CompressionPool, CompressionStream, NoCompression, SnappyCompression, ZstdCompression, ZlibCompression, Lz4Compression, LzoCompression};
+
+    fn compressions() -> Vec<writer_compression::Compression> {
+        vec![
+            NoCompression::new().build(),
+            SnappyCompression::new().with_block_size(1000).unwrap().build(),
+            ZstdCompression::new().with_block_size(1000).unwrap().build(),
+            ZlibCompression::new().with_block_size(1000).unwrap().build(),
+            Lz4Compression::new().with_block_size(1000).unwrap().build(),
+            LzoCompression::new().with_block_size(1000).unwrap().build(),
+            ZstdCompression::new().with_block_size(1000).unwrap().build().with_pool(Arc::new(CompressionPool::new(3))),
+        ]
+    }
+
+    #[test]
+    fn test_decompression_stream() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for compression in compressions() {
+            // A mix of compressible and (random, hence incompressible) data, so that both compressed
+            // and original blocks occur.
+            let mut data: Vec<u8> = Vec::new();
+            let mut positions: Vec<Vec<u64>> = Vec::new();
+            let mut offsets: Vec<usize> = Vec::new();
+            let mut stream = CompressionStream::new(&compression);
+            for i in 0..20000 {
+                if i % 777 == 0 {
+                    let mut position = Vec::new();
+                    stream.position().record(&stream, &mut position);
+                    positions.push(position);
+                    offsets.push(data.len());
+                }
+                let b = if (i / 3000) % 2 == 0 { (i % 7) as u8 } else { rng.gen() };
+                data.push(b);
+                stream.write_u8(b);
+            }
+            let mut out: Vec<u8> = Vec::new();
+            stream.finish(&mut out).unwrap();
+
+            let reader_compression = Compression::new(compression.kind(), compression.block_size() as u64).unwrap();
+            let mut decompressed: Vec<u8> = Vec::new();
+            DecompressionStream::new(out.clone(), &reader_compression).read_to_end(&mut decompressed).unwrap();
+            assert_eq!(decompressed, data);
+
+            // Within bytes shared with other streams
+            let range = 10..10 + out.len();
+            let shared = Arc::new([vec![0xff; 10], out, vec![0xff; 10]].concat());
+            decompressed.clear();
+            DecompressionStream::new_shared(shared.clone(), range.clone(), &reader_compression).read_to_end(&mut decompressed).unwrap();
+            assert_eq!(decompressed, data);
+
+            let mut stream = DecompressionStream::new_shared(shared, range, &reader_compression);
+            for (position, &offset) in positions.iter().zip(offsets.iter()).rev() {
+                stream.seek(&mut PositionProvider::new(position)).unwrap();
+                for &b in &data[offset..(offset + 100).min(data.len())] {
+                    assert_eq!(stream.read_u8().unwrap(), b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_stream() {
+        for compression in compressions() {
+            let reader_compression = Compression::new(compression.kind(), compression.block_size() as u64).unwrap();
+            let mut stream = DecompressionStream::new(Vec::new(), &reader_compression);
+            let mut out: Vec<u8> = Vec::new();
+            assert_eq!(stream.read_to_end(&mut out).unwrap(), 0);
+            assert!(stream.read_u8().is_err());
+        }
+    }
+}
@@ -0,0 +1,7 @@
+use std::io::Result;
+use crate::buffer::Buffer;
+
+pub trait Decompressor {
+    /// Decompress a single compression block, appending the result to `output`.
+    fn decompress(&mut self, input: &[u8], output: &mut Buffer) -> Result<()>;
+}
@@ -0,0 +1,45 @@
+use std::io::{Result, Error, ErrorKind};
+
+use super::common::Decompressor;
+use crate::buffer::Buffer;
+
+pub struct Lz4Decompressor {
+    block_size: usize,
+}
+
+impl Lz4Decompressor {
+    pub fn new(block_size: usize) -> Self {
+        Lz4Decompressor { block_size }
+    }
+}
+
+impl Decompressor for Lz4Decompressor {
+    fn decompress(&mut self, input: &[u8], output: &mut Buffer) -> Result<()> {
+        // LZ4 blocks don't record their decompressed size, but in ORC it is at most the block size.
+        let current_len = output.len();
+        output.resize(current_len + self.block_size);
+        let additional_len = lz4_flex::block::decompress_into(input, &mut output[current_len..])
+            .map_err(|e| Error::new(ErrorKind::InvalidData, format!("invalid LZ4 block: {}", e)))?;
+        output.resize(current_len + additional_len);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decompress() {
+        // A block in the LZ4 block format: one literal followed by a match of length 8 at
+        // offset 1, then the final 5 literals.
+        let input = [0x14, b'a', 0x01, 0x00, 0x50, b'a', b'a', b'a', b'a', b'b'];
+        let mut output = Buffer::new();
+        output.write_bytes(b"xyz");
+        Lz4Decompressor::new(100).decompress(&input, &mut output).unwrap();
+        assert_eq!(&output[..], &b"xyzaaaaaaaaaaaaab"[..]);
+
+        assert!(Lz4Decompressor::new(10).decompress(&input, &mut Buffer::new()).is_err());
+        assert!(Lz4Decompressor::new(100).decompress(&input[..6], &mut Buffer::new()).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use std::io::{Result, Error, ErrorKind};
+
+use super::common::Decompressor;
+use crate::buffer::Buffer;
+
+/// Decompressor for the LZO1X format, as used by the Java implementation (through aircompressor)
+/// for raw blocks without any header.
+pub struct LzoDecompressor {}
+
+impl LzoDecompressor {
+    pub fn new() -> Self {
+        LzoDecompressor {}
+    }
+}
+
+fn invalid_block() -> Error {
+    Error::new(ErrorKind::InvalidData, "invalid LZO block")
+}
+
+/// Reads the bytes of an instruction, failing on truncated input.
+struct Input<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Input<'a> {
+    fn next(&mut self) -> Result<u8> {
+        let b = *self.data.get(self.pos).ok_or_else(invalid_block)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn next_le16(&mut self) -> Result<usize> {
+        Ok(self.next()? as usize | (self.next()? as usize) << 8)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos + len).ok_or_else(invalid_block)?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Reads the extension of a length whose bits in the instruction were all zero: each zero byte
+    /// adds 255, and the first non-zero byte is added as is.
+    fn length_extension(&mut self) -> Result<usize> {
+        let mut len = 0;
+        loop {
+            match self.next()? {
+                0 => len += 255,
+                b => return Ok(len + b as usize),
+            }
+        }
+    }
+}
+
+/// Appends `len` bytes starting `distance` bytes before the end of `output`. The ranges may overlap.
+fn copy_match(output: &mut Buffer, start: usize, distance: usize, len: usize) -> Result<()> {
+    if distance == 0 || distance > output.len() - start {
+        return Err(invalid_block());
+    }
+    for pos in (output.len() - distance..).take(len) {
+        let b = output[pos];
+        output.write_u8(b);
+    }
+    Ok(())
+}
+
+impl Decompressor for LzoDecompressor {
+    fn decompress(&mut self, input: &[u8], output: &mut Buffer) -> Result<()> {
+        let start = output.len();
+        let mut input = Input { data: input, pos: 0 };
+        // The number of literals copied after the last instruction: 0, 1-3, or 4 for longer runs.
+        // It determines the meaning of instructions below 16.
+        let mut state = 0;
+        if input.data.first().map_or(false, |&b| b > 17) {
+            let len = (input.next()? - 17) as usize;
+            output.write_bytes(input.take(len)?);
+            state = len.min(4);
+        }
+        loop {
+            let t = input.next()? as usize;
+            let (distance, len, next) = if t < 16 {
+                if state == 0 {
+                    // Run of at least 4 literals
+                    let len = if t == 0 { 15 + input.length_extension()? } else { t } + 3;
+                    output.write_bytes(input.take(len)?);
+                    state = 4;
+                    continue;
+                } else if state < 4 {
+                    (1 + (t >> 2) + ((input.next()? as usize) << 2), 2, t & 3)
+                } else {
+                    (2049 + (t >> 2) + ((input.next()? as usize) << 2), 3, t & 3)
+                }
+            } else if t >= 64 {
+                (1 + ((t >> 2) & 7) + ((input.next()? as usize) << 3), (t >> 5) + 1, t & 3)
+            } else if t >= 32 {
+                let len = if t & 31 == 0 { 31 + input.length_extension()? } else { t & 31 } + 2;
+                let x = input.next_le16()?;
+                (1 + (x >> 2), len, x & 3)
+            } else {
+                let len = if t & 7 == 0 { 7 + input.length_extension()? } else { t & 7 } + 2;
+                let x = input.next_le16()?;
+                let distance = ((t & 8) << 11) + (x >> 2);
+                if distance == 0 {
+                    // End of stream marker
+                    if len != 3 || input.pos != input.data.len() {
+                        return Err(invalid_block());
+                    }
+                    return Ok(());
+                }
+                (16384 + distance, len, x & 3)
+            };
+            copy_match(output, start, distance, len)?;
+            output.write_bytes(input.take(next)?);
+            state = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use crate::writer::compression::{LzoCompression, CompressionStream};
+    use crate::reader::compression::{Compression, DecompressionStream};
+    use std::io::Read;
+
+    fn decompress(input: &[u8]) -> Result<Vec<u8>> {
+        let mut output = Buffer::new();
+        LzoDecompressor::new().decompress(input, &mut output)?;
+        Ok(output.to_vec())
+    }
+
+    #[test]
+    fn test_decompress() {
+        // 3 initial literals, a match of length 9 at distance 3, and the end of stream marker
+        assert_eq!(decompress(&[20, b'a', b'b', b'c', 39, 8, 0, 0x11, 0, 0]).unwrap(), b"abcabcabcabc");
+        // A 2-byte match following a short literal run, then a run of 4 literals
+        assert_eq!(decompress(&[20, b'a', b'b', b'c', 8, 0, 1, b'w', b'x', b'y', b'z', 0x11, 0, 0]).unwrap(),
+            b"abcabwxyz");
+        // A short match with 2 trailing literals
+        assert_eq!(decompress(&[21, b'a', b'b', b'c', b'd', 78, 0, b'x', b'y', 0x11, 0, 0]).unwrap(), b"abcdabcxy");
+        // A literal run with an extended length
+        let mut input = vec![0, 2];
+        input.extend(0..20);
+        input.extend(&[0x11, 0, 0]);
+        assert_eq!(decompress(&input).unwrap(), (0..20).collect::<Vec<u8>>());
+
+        // Truncated input, a match before the start of the block, and a missing end marker
+        assert!(decompress(&[20, b'a', b'b']).is_err());
+        assert!(decompress(&[20, b'a', b'b', b'c', 39, 16, 0, 0x11, 0, 0]).is_err());
+        assert!(decompress(&[20, b'a', b'b', b'c']).is_err());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        // Repeats at distances of each of the match encodings
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut data: Vec<u8> = (0..50000).map(|_| rng.gen()).collect();
+        for &(distance, len) in &[(1, 300), (5, 3), (100, 40), (3000, 5), (20000, 1000), (45000, 2)] {
+            for _ in 0..len {
+                data.push(data[data.len() - distance]);
+            }
+            data.push(rng.gen());
+        }
+
+        let compression = LzoCompression::new().with_block_size(100000).unwrap().build();
+        let mut stream = CompressionStream::new(&compression);
+        stream.write_bytes(&data);
+        let mut out: Vec<u8> = Vec::new();
+        stream.finish(&mut out).unwrap();
+        assert!(out.len() < data.len() - 1000);
+
+        let reader_compression = Compression::new(compression.kind(), compression.block_size() as u64).unwrap();
+        let mut decompressed: Vec<u8> = Vec::new();
+        DecompressionStream::new(out, &reader_compression).read_to_end(&mut decompressed).unwrap();
+        assert_eq!(decompressed, data);
+    }
+}
@@ -0,0 +1,27 @@
+use snap;
+use std::io::Result;
+
+use super::common::Decompressor;
+use crate::buffer::Buffer;
+
+pub struct SnappyDecompressor {
+    decoder: snap::Decoder,
+}
+
+impl SnappyDecompressor {
+    pub fn new() -> Self {
+        SnappyDecompressor {
+            decoder: snap::Decoder::new(),
+        }
+    }
+}
+
+impl Decompressor for SnappyDecompressor {
+    fn decompress(&mut self, input: &[u8], output: &mut Buffer) -> Result<()> {
+        let current_len = output.len();
+        let additional_len = snap::decompress_len(input)?;
+        output.resize(current_len + additional_len);
+        self.decoder.decompress(input, &mut output[current_len..])?;
+        Ok(())
+    }
+}
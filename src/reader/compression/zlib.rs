@@ -0,0 +1,21 @@
+use std::io::Result;
+use flate2::read::DeflateDecoder;
+
+use super::common::Decompressor;
+use crate::buffer::Buffer;
+
+pub struct ZlibDecompressor {}
+
+impl ZlibDecompressor {
+    pub fn new() -> Self {
+        ZlibDecompressor {}
+    }
+}
+
+impl Decompressor for ZlibDecompressor {
+    fn decompress(&mut self, input: &[u8], output: &mut Buffer) -> Result<()> {
+        // ORC uses raw deflate, i.e. without the zlib header and checksum.
+        std::io::copy(&mut DeflateDecoder::new(input), output)?;
+        Ok(())
+    }
+}
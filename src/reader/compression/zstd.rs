@@ -0,0 +1,21 @@
+use zstd;
+use std::io::Result;
+
+use super::common::Decompressor;
+use crate::buffer::Buffer;
+
+pub struct ZstdDecompressor {}
+
+impl ZstdDecompressor {
+    pub fn new() -> Self {
+        ZstdDecompressor {}
+    }
+}
+
+impl Decompressor for ZstdDecompressor {
+    fn decompress(&mut self, input: &[u8], output: &mut Buffer) -> Result<()> {
+        // The frame content size is optional in the zstd format, so we decode in streaming mode
+        // rather than relying on it to size the output.
+        zstd::stream::copy_decode(input, output)
+    }
+}
@@ -0,0 +1,244 @@
+use std::io::Result;
+
+use crate::schema::Schema;
+use common::{BaseData, StripeStreams};
+
+pub use common::GenericData;
+pub use boolean::BooleanData;
+pub use byte::ByteData;
+pub use long::LongData;
+pub use float::FloatData;
+pub use double::DoubleData;
+pub use timestamp::TimestampData;
+pub use decimal::DecimalData;
+pub use string::StringData;
+pub use binary::BinaryData;
+pub use struct_::StructData;
+pub use list::ListData;
+pub use map::MapData;
+pub use union::UnionData;
+
+pub(crate) mod common;
+mod boolean;
+mod byte;
+mod long;
+mod float;
+mod double;
+mod timestamp;
+mod decimal;
+mod string;
+mod binary;
+mod struct_;
+mod list;
+mod map;
+mod union;
+
+/// The most recently read batch of a column, mirroring `writer::data::Data`.
+pub enum Data {
+    Boolean(BooleanData),
+    Byte(ByteData),
+    Long(LongData),
+    Float(FloatData),
+    Double(DoubleData),
+    Timestamp(TimestampData),
+    Decimal(DecimalData),
+    String(StringData),
+    Binary(BinaryData),
+    List(ListData),
+    Struct(StructData),
+    Map(MapData),
+    Union(UnionData),
+}
+
+impl Data {
+    pub(crate) fn new(schema: &Schema, column_id: &mut u32) -> Self {
+        match schema {
+            Schema::Boolean => Data::Boolean(BooleanData::new(column_id)),
+            Schema::Byte => Data::Byte(ByteData::new(column_id)),
+            Schema::Short | Schema::Int | Schema::Long | Schema::Date => Data::Long(LongData::new(column_id)),
+            Schema::Float => Data::Float(FloatData::new(column_id)),
+            Schema::Double => Data::Double(DoubleData::new(column_id)),
+            Schema::Timestamp => Data::Timestamp(TimestampData::new(column_id)),
+            Schema::Decimal(_, _) => Data::Decimal(DecimalData::new(schema, column_id)),
+            Schema::String | Schema::VarChar(_) | Schema::Char(_) => Data::String(StringData::new(column_id)),
+            Schema::Binary => Data::Binary(BinaryData::new(column_id)),
+            Schema::Struct(_) => Data::Struct(StructData::new(schema, column_id)),
+            Schema::List(_) => Data::List(ListData::new(schema, column_id)),
+            Schema::Map(_, _) => Data::Map(MapData::new(schema, column_id)),
+            Schema::Union(_) => Data::Union(UnionData::new(schema, column_id)),
+        }
+    }
+
+    /// Like `new`, but structs only have the fields whose column is included (`included` is indexed
+    /// by column id).
+    pub(crate) fn new_projected(schema: &Schema, column_id: &mut u32, included: &[bool]) -> Self {
+        match schema {
+            Schema::Struct(_) => Data::Struct(StructData::new_projected(schema, column_id, included)),
+            _ => Data::new(schema, column_id),
+        }
+    }
+
+    pub fn unwrap_boolean(&self) -> &BooleanData {
+        if let Data::Boolean(x) = self { x } else { panic!("unwrap_boolean called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_byte(&self) -> &ByteData {
+        if let Data::Byte(x) = self { x } else { panic!("unwrap_byte called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_long(&self) -> &LongData {
+        if let Data::Long(x) = self { x } else { panic!("unwrap_long called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_float(&self) -> &FloatData {
+        if let Data::Float(x) = self { x } else { panic!("unwrap_float called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_double(&self) -> &DoubleData {
+        if let Data::Double(x) = self { x } else { panic!("unwrap_double called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_timestamp(&self) -> &TimestampData {
+        if let Data::Timestamp(x) = self { x } else { panic!("unwrap_timestamp called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_decimal(&self) -> &DecimalData {
+        if let Data::Decimal(x) = self { x } else { panic!("unwrap_decimal called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_string(&self) -> &StringData {
+        if let Data::String(x) = self { x } else { panic!("unwrap_string called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_binary(&self) -> &BinaryData {
+        if let Data::Binary(x) = self { x } else { panic!("unwrap_binary called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_list(&self) -> &ListData {
+        if let Data::List(x) = self { x } else { panic!("unwrap_list called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_struct(&self) -> &StructData {
+        if let Data::Struct(x) = self { x } else { panic!("unwrap_struct called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_map(&self) -> &MapData {
+        if let Data::Map(x) = self { x } else { panic!("unwrap_map called on incorrect type of data"); }
+    }
+
+    pub fn unwrap_union(&self) -> &UnionData {
+        if let Data::Union(x) = self { x } else { panic!("unwrap_union called on incorrect type of data"); }
+    }
+}
+
+impl GenericData for Data {
+    fn column_id(&self) -> u32 {
+        match self {
+            Data::Boolean(x) => x.column_id(),
+            Data::Byte(x) => x.column_id(),
+            Data::Long(x) => x.column_id(),
+            Data::Float(x) => x.column_id(),
+            Data::Double(x) => x.column_id(),
+            Data::Timestamp(x) => x.column_id(),
+            Data::Decimal(x) => x.column_id(),
+            Data::String(x) => x.column_id(),
+            Data::Binary(x) => x.column_id(),
+            Data::List(x) => x.column_id(),
+            Data::Struct(x) => x.column_id(),
+            Data::Map(x) => x.column_id(),
+            Data::Union(x) => x.column_id(),
+        }
+    }
+
+    fn present(&self) -> &[bool] {
+        match self {
+            Data::Boolean(x) => x.present(),
+            Data::Byte(x) => x.present(),
+            Data::Long(x) => x.present(),
+            Data::Float(x) => x.present(),
+            Data::Double(x) => x.present(),
+            Data::Timestamp(x) => x.present(),
+            Data::Decimal(x) => x.present(),
+            Data::String(x) => x.present(),
+            Data::Binary(x) => x.present(),
+            Data::List(x) => x.present(),
+            Data::Struct(x) => x.present(),
+            Data::Map(x) => x.present(),
+            Data::Union(x) => x.present(),
+        }
+    }
+}
+
+impl BaseData for Data {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        match self {
+            Data::Boolean(x) => x.load_stripe(streams),
+            Data::Byte(x) => x.load_stripe(streams),
+            Data::Long(x) => x.load_stripe(streams),
+            Data::Float(x) => x.load_stripe(streams),
+            Data::Double(x) => x.load_stripe(streams),
+            Data::Timestamp(x) => x.load_stripe(streams),
+            Data::Decimal(x) => x.load_stripe(streams),
+            Data::String(x) => x.load_stripe(streams),
+            Data::Binary(x) => x.load_stripe(streams),
+            Data::List(x) => x.load_stripe(streams),
+            Data::Struct(x) => x.load_stripe(streams),
+            Data::Map(x) => x.load_stripe(streams),
+            Data::Union(x) => x.load_stripe(streams),
+        }
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        match self {
+            Data::Boolean(x) => x.read_batch(num_rows),
+            Data::Byte(x) => x.read_batch(num_rows),
+            Data::Long(x) => x.read_batch(num_rows),
+            Data::Float(x) => x.read_batch(num_rows),
+            Data::Double(x) => x.read_batch(num_rows),
+            Data::Timestamp(x) => x.read_batch(num_rows),
+            Data::Decimal(x) => x.read_batch(num_rows),
+            Data::String(x) => x.read_batch(num_rows),
+            Data::Binary(x) => x.read_batch(num_rows),
+            Data::List(x) => x.read_batch(num_rows),
+            Data::Struct(x) => x.read_batch(num_rows),
+            Data::Map(x) => x.read_batch(num_rows),
+            Data::Union(x) => x.read_batch(num_rows),
+        }
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        match self {
+            Data::Boolean(x) => x.skip(num_rows),
+            Data::Byte(x) => x.skip(num_rows),
+            Data::Long(x) => x.skip(num_rows),
+            Data::Float(x) => x.skip(num_rows),
+            Data::Double(x) => x.skip(num_rows),
+            Data::Timestamp(x) => x.skip(num_rows),
+            Data::Decimal(x) => x.skip(num_rows),
+            Data::String(x) => x.skip(num_rows),
+            Data::Binary(x) => x.skip(num_rows),
+            Data::List(x) => x.skip(num_rows),
+            Data::Struct(x) => x.skip(num_rows),
+            Data::Map(x) => x.skip(num_rows),
+            Data::Union(x) => x.skip(num_rows),
+        }
+    }
+
+    fn seek(&mut self, streams: &StripeStreams, row: usize) -> Result<()> {
+        match self {
+            Data::Boolean(x) => x.seek(streams, row),
+            Data::Byte(x) => x.seek(streams, row),
+            Data::Long(x) => x.seek(streams, row),
+            Data::Float(x) => x.seek(streams, row),
+            Data::Double(x) => x.seek(streams, row),
+            Data::Timestamp(x) => x.seek(streams, row),
+            Data::Decimal(x) => x.seek(streams, row),
+            Data::String(x) => x.seek(streams, row),
+            Data::Binary(x) => x.seek(streams, row),
+            Data::List(x) => x.seek(streams, row),
+            Data::Struct(x) => x.seek(streams, row),
+            Data::Map(x) => x.seek(streams, row),
+            Data::Union(x) => x.seek(streams, row),
+        }
+    }
+}
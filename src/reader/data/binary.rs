@@ -0,0 +1,93 @@
+use std::io::{Read, Result};
+
+use crate::protos::orc_proto;
+use crate::reader::compression::DecompressionStream;
+use crate::reader::decoder::IntRLEDecoder;
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present, read_lengths, sum_lengths,
+    stripe_not_loaded};
+
+
+pub struct BinaryData {
+    column_id: u32,
+    present: Present,
+    data: Option<(DecompressionStream, IntRLEDecoder)>,
+    bytes: Vec<u8>,
+    // Value `i` is `bytes[offsets[i]..offsets[i + 1]]`
+    offsets: Vec<usize>,
+}
+
+impl BinaryData {
+    pub(crate) fn new(column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        BinaryData {
+            column_id: cid,
+            present: Present::new(cid),
+            data: None,
+            bytes: Vec::new(),
+            offsets: vec![0],
+        }
+    }
+
+    /// Number of non-null values in the batch.
+    pub fn num_values(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// The `i`-th non-null value of the batch.
+    pub fn value(&self, i: usize) -> &[u8] {
+        &self.bytes[self.offsets[i]..self.offsets[i + 1]]
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &[u8]> {
+        (0..self.num_values()).map(move |i| self.value(i))
+    }
+}
+
+impl GenericData for BinaryData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for BinaryData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        self.data = Some((
+            streams.required_stream(self.column_id, orc_proto::Stream_Kind::DATA)?,
+            streams.int_rle(self.column_id, orc_proto::Stream_Kind::LENGTH, false)?,
+        ));
+        Ok(())
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        let column_id = self.column_id;
+        let (data, lengths) = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        self.offsets.truncate(1);
+        let total_len = read_lengths(lengths, num_values, &mut self.offsets)?;
+        self.bytes.clear();
+        self.bytes.resize(total_len, 0);
+        data.read_exact(&mut self.bytes)
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        let column_id = self.column_id;
+        let (data, lengths) = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let total_len = sum_lengths(lengths, num_values)?;
+        data.skip(total_len as u64)
+    }
+
+    fn seek(&mut self, streams: &StripeStreams, row: usize) -> Result<()> {
+        let column_id = self.column_id;
+        let present = &mut self.present;
+        let (data, lengths) = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let num_rows = streams.seek_row_group(column_id, row, |positions| {
+            present.seek(positions)?;
+            data.seek(positions)?;
+            lengths.seek(positions)
+        })?;
+        self.skip(num_rows)
+    }
+}
@@ -0,0 +1,78 @@
+use std::io::Result;
+
+use crate::protos::orc_proto;
+use crate::reader::decoder::ByteRLEDecoder;
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present, stripe_not_loaded};
+
+
+pub struct ByteData {
+    column_id: u32,
+    present: Present,
+    data: Option<ByteRLEDecoder>,
+    // The undecoded bytes of the batch
+    buffer: Vec<u8>,
+    values: Vec<i8>,
+}
+
+impl ByteData {
+    pub(crate) fn new(column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        ByteData {
+            column_id: cid,
+            present: Present::new(cid),
+            data: None,
+            buffer: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// The values of the non-null rows of the batch.
+    pub fn values(&self) -> &[i8] {
+        &self.values
+    }
+}
+
+impl GenericData for ByteData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for ByteData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        self.data = Some(ByteRLEDecoder::new(streams.required_stream(self.column_id, orc_proto::Stream_Kind::DATA)?));
+        Ok(())
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        let column_id = self.column_id;
+        let data = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        self.buffer.clear();
+        self.buffer.resize(num_values, 0);
+        data.read_batch(&mut self.buffer)?;
+        self.values.clear();
+        self.values.extend(self.buffer.iter().map(|&x| x as i8));
+        Ok(())
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        let column_id = self.column_id;
+        let data = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        data.skip(num_values as u64)
+    }
+
+    fn seek(&mut self, streams: &StripeStreams, row: usize) -> Result<()> {
+        let column_id = self.column_id;
+        let present = &mut self.present;
+        let data = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let num_rows = streams.seek_row_group(column_id, row, |positions| {
+            present.seek(positions)?;
+            data.seek(positions)
+        })?;
+        self.skip(num_rows)
+    }
+}
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::io::{Result, Error, ErrorKind};
+
+use protobuf::Message;
+
+use crate::bloom_filter::{BloomFilter, BLOOM_ENCODING_UTF8_UTC};
+use crate::protos::orc_proto;
+use crate::reader::compression::{Compression, DecompressionStream, PositionProvider};
+use crate::reader::decoder::{BooleanRLEDecoder, IntRLEDecoder};
+
+
+/// Common interface of the data of every column type, as of the most recent batch.
+pub trait GenericData {
+    fn column_id(&self) -> u32;
+
+    /// Whether each row of the batch is present (non-null). Values of the column are stored densely,
+    /// i.e. they only have an entry for each present row.
+    fn present(&self) -> &[bool];
+
+    fn num_rows(&self) -> usize {
+        self.present().len()
+    }
+
+    fn is_null(&self, row: usize) -> bool {
+        !self.present()[row]
+    }
+}
+
+pub(crate) trait BaseData {
+    /// Prepares the decoders of this column (and its children) for reading a new stripe.
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()>;
+
+    /// Decodes the next `num_rows` rows of the stripe, replacing the previous batch.
+    fn read_batch(&mut self, num_rows: usize) -> Result<()>;
+
+    /// Skips the next `num_rows` rows of the stripe.
+    fn skip(&mut self, num_rows: usize) -> Result<()>;
+
+    /// Moves to row `row` of this column in the stripe, which must have just been loaded. Primitive
+    /// columns seek to the enclosing row group using the row index and skip the rest. Compound
+    /// columns decode their own streams up to `row`, to find the row their children must move to.
+    /// Writers place the row index entries of every column at the same rows of the stripe, so the
+    /// children only seek if they have a row for each row of their parent: those of lists, maps,
+    /// unions and structs with nulls skip from the start of the stripe instead.
+    fn seek(&mut self, streams: &StripeStreams, row: usize) -> Result<()>;
+}
+
+pub(crate) fn invalid_data(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+pub(crate) fn stripe_not_loaded(column_id: u32) -> Error {
+    Error::new(ErrorKind::Other, format!("no stripe has been loaded for column {}", column_id))
+}
+
+/// The streams of a stripe, split by column and stream kind. Streams can be opened any number of
+/// times, so that decoders can be repositioned within the stripe.
+pub(crate) struct StripeStreams {
+    // The streams read, shared by the decompression streams opened over them
+    bytes: Arc<Vec<u8>>,
+    ranges: HashMap<(u32, orc_proto::Stream_Kind), (usize, usize)>,
+    encodings: Vec<orc_proto::ColumnEncoding>,
+    compression: Compression,
+    row_index_stride: usize,
+}
+
+impl StripeStreams {
+    /// Reads the streams of a stripe for which `include` returns true. The streams are laid out in
+    /// the order listed in the stripe footer, over the first `streams_len` bytes of the stripe;
+    /// `read_range(start, len)` reads the bytes at `start` relative to the start of the stripe.
+    /// Adjacent included streams are read at once, and the others are skipped over.
+    pub fn read<I, F>(footer: &orc_proto::StripeFooter, streams_len: u64, compression: &Compression,
+            row_index_stride: u32, include: I, mut read_range: F) -> Result<Self>
+        where I: Fn(u32, orc_proto::Stream_Kind) -> bool,
+              F: FnMut(u64, u64) -> Result<Vec<u8>>
+    {
+        let mut bytes = Vec::new();
+        let mut ranges = HashMap::new();
+        // Range of the stripe to read next, covering included streams not read yet
+        let mut pending = 0..0;
+        let mut offset = 0u64;
+        for stream in footer.get_streams() {
+            let end = offset.checked_add(stream.get_length())
+                .filter(|&end| end <= streams_len)
+                .ok_or_else(|| invalid_data(format!("stream {:?} of column {} exceeds the stripe",
+                    stream.get_kind(), stream.get_column())))?;
+            if include(stream.get_column(), stream.get_kind()) {
+                if pending.end != offset {
+                    if !pending.is_empty() {
+                        bytes.extend(read_range(pending.start, pending.end - pending.start)?);
+                    }
+                    pending = offset..offset;
+                }
+                let start = bytes.len() + (offset - pending.start) as usize;
+                ranges.insert((stream.get_column(), stream.get_kind()), (start, start + stream.get_length() as usize));
+                pending.end = end;
+            }
+            offset = end;
+        }
+        if !pending.is_empty() {
+            bytes.extend(read_range(pending.start, pending.end - pending.start)?);
+        }
+        Ok(StripeStreams {
+            bytes: Arc::new(bytes),
+            ranges,
+            encodings: footer.get_columns().to_vec(),
+            compression: *compression,
+            row_index_stride: row_index_stride as usize,
+        })
+    }
+
+    pub fn stream(&self, column_id: u32, kind: orc_proto::Stream_Kind) -> Option<DecompressionStream> {
+        self.ranges.get(&(column_id, kind))
+            .map(|&(start, end)| DecompressionStream::new_shared(self.bytes.clone(), start..end, &self.compression))
+    }
+
+    pub fn required_stream(&self, column_id: u32, kind: orc_proto::Stream_Kind) -> Result<DecompressionStream> {
+        self.stream(column_id, kind)
+            .ok_or_else(|| invalid_data(format!("missing {:?} stream for column {}", kind, column_id)))
+    }
+
+    pub fn encoding(&self, column_id: u32) -> Result<&orc_proto::ColumnEncoding> {
+        self.encodings.get(column_id as usize)
+            .ok_or_else(|| invalid_data(format!("missing column encoding for column {}", column_id)))
+    }
+
+    pub fn row_index(&self, column_id: u32) -> Result<Option<orc_proto::RowIndex>> {
+        match self.stream(column_id, orc_proto::Stream_Kind::ROW_INDEX) {
+            Some(mut stream) => Ok(Some(orc_proto::RowIndex::parse_from_reader(&mut stream)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The bloom filter of each row group of the column, from its `BLOOM_FILTER_UTF8` stream.
+    /// Malformed bloom filters are `None`.
+    pub fn bloom_filters(&self, column_id: u32) -> Result<Option<Vec<Option<BloomFilter>>>> {
+        // Older writers hash timestamps in local time, which is not supported
+        if self.encoding(column_id)?.get_bloomEncoding() < BLOOM_ENCODING_UTF8_UTC {
+            return Ok(None);
+        }
+        match self.stream(column_id, orc_proto::Stream_Kind::BLOOM_FILTER_UTF8) {
+            Some(mut stream) => {
+                let index = orc_proto::BloomFilterIndex::parse_from_reader(&mut stream)?;
+                Ok(Some(index.get_bloomFilter().iter().map(BloomFilter::from_proto).collect()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Finds the last row group of the column starting at or before `row`, seeks its decoders to
+    /// the start of it using `seek_streams`, and returns the number of rows remaining until `row`.
+    /// Row groups are counted in rows of the stripe, so the column must have a row (possibly null)
+    /// for each of them.
+    pub fn seek_row_group<F>(&self, column_id: u32, row: usize, seek_streams: F) -> Result<usize>
+        where F: FnOnce(&mut PositionProvider) -> Result<()>
+    {
+        if self.row_index_stride == 0 || row < self.row_index_stride {
+            return Ok(row);
+        }
+        let index = match self.row_index(column_id)? {
+            Some(index) => index,
+            None => return Ok(row),
+        };
+        // A column ending exactly at a row group boundary has no entry for the (empty) last group.
+        let group = (row / self.row_index_stride).min(index.get_entry().len().saturating_sub(1));
+        if group == 0 {
+            return Ok(row);
+        }
+        seek_streams(&mut PositionProvider::new(index.get_entry()[group].get_positions()))?;
+        Ok(row - group * self.row_index_stride)
+    }
+
+    /// Makes column `column_id` use the row index of column `other`.
+    #[cfg(test)]
+    pub fn replace_row_index(&mut self, column_id: u32, other: u32) {
+        let range = self.ranges[&(other, orc_proto::Stream_Kind::ROW_INDEX)];
+        self.ranges.insert((column_id, orc_proto::Stream_Kind::ROW_INDEX), range);
+    }
+
+    pub fn int_rle(&self, column_id: u32, kind: orc_proto::Stream_Kind, signed: bool) -> Result<IntRLEDecoder> {
+        let encoding = self.encoding(column_id)?.get_kind();
+        Ok(IntRLEDecoder::new(self.required_stream(column_id, kind)?, signed, encoding))
+    }
+}
+
+/// The PRESENT stream of a column. Stripes without nulls omit the stream.
+pub(crate) struct Present {
+    column_id: u32,
+    decoder: Option<BooleanRLEDecoder>,
+    values: Vec<bool>,
+}
+
+impl Present {
+    pub fn new(column_id: u32) -> Self {
+        Present { column_id, decoder: None, values: Vec::new() }
+    }
+
+    pub fn load_stripe(&mut self, streams: &StripeStreams) {
+        self.decoder = streams.stream(self.column_id, orc_proto::Stream_Kind::PRESENT).map(BooleanRLEDecoder::new);
+    }
+
+    /// Reads the presence of the next `num_rows` rows and returns the number of non-null rows.
+    pub fn read_batch(&mut self, num_rows: usize) -> Result<usize> {
+        self.values.clear();
+        self.values.resize(num_rows, true);
+        match &mut self.decoder {
+            Some(decoder) => {
+                decoder.read_batch(&mut self.values)?;
+                Ok(self.values.iter().filter(|&&x| x).count())
+            }
+            None => Ok(num_rows),
+        }
+    }
+
+    /// Skips the next `num_rows` rows and returns the number of non-null rows among them.
+    pub fn skip(&mut self, num_rows: usize) -> Result<usize> {
+        match &mut self.decoder {
+            Some(decoder) => {
+                let mut num_present = 0;
+                for _ in 0..num_rows {
+                    num_present += decoder.next()? as usize;
+                }
+                Ok(num_present)
+            }
+            None => Ok(num_rows),
+        }
+    }
+
+    pub fn seek(&mut self, positions: &mut PositionProvider) -> Result<()> {
+        match &mut self.decoder {
+            Some(decoder) => decoder.seek(positions),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the column has a PRESENT stream in the stripe, i.e. may have nulls.
+    pub fn has_stream(&self) -> bool {
+        self.decoder.is_some()
+    }
+
+    pub fn values(&self) -> &[bool] {
+        &self.values
+    }
+}
+
+/// Reads `num_values` lengths and returns their sum.
+pub(crate) fn sum_lengths(decoder: &mut IntRLEDecoder, num_values: usize) -> Result<usize> {
+    let mut offsets = vec![0];
+    read_lengths(decoder, num_values, &mut offsets)
+}
+
+/// Reads `num_values` lengths and appends the corresponding end offsets to `offsets`, which must
+/// already contain the start offset. Returns the sum of the lengths.
+pub(crate) fn read_lengths(decoder: &mut IntRLEDecoder, num_values: usize, offsets: &mut Vec<usize>) -> Result<usize> {
+    let mut lengths = vec![0i64; num_values];
+    decoder.read_batch(&mut lengths)?;
+    let start = *offsets.last().unwrap();
+    let mut end = start;
+    for len in lengths {
+        if len < 0 {
+            return Err(invalid_data(format!("invalid length {}", len as u64)));
+        }
+        end += len as usize;
+        offsets.push(end);
+    }
+    Ok(end - start)
+}
@@ -0,0 +1,110 @@
+use std::convert::TryFrom;
+use std::io::Result;
+
+use crate::protos::orc_proto;
+use crate::schema::Schema;
+use crate::reader::compression::DecompressionStream;
+use crate::reader::decoder::{IntRLEDecoder, read_varint_u128, zigzag_decode_i128};
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present, invalid_data, stripe_not_loaded};
+
+
+pub struct DecimalData {
+    column_id: u32,
+    precision: u32,
+    scale: u32,
+    present: Present,
+    data: Option<(DecompressionStream, IntRLEDecoder)>,
+    values: Vec<i128>,
+}
+
+impl DecimalData {
+    pub(crate) fn new(schema: &Schema, column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        if let Schema::Decimal(precision, scale) = schema {
+            DecimalData {
+                column_id: cid,
+                precision: *precision,
+                scale: *scale,
+                present: Present::new(cid),
+                data: None,
+                values: Vec::new(),
+            }
+        } else { unreachable!() }
+    }
+
+    /// The unscaled values of the non-null rows of the batch, i.e. value `x` stands for
+    /// `x * 10^-scale`.
+    pub fn values(&self) -> &[i128] {
+        &self.values
+    }
+
+    pub fn precision(&self) -> u32 { self.precision }
+
+    pub fn scale(&self) -> u32 { self.scale }
+}
+
+/// Converts an unscaled value from the scale it was stored with to the column's scale.
+fn rescale(x: i128, scale: i64, column_scale: u32, column_id: u32) -> Result<i128> {
+    let diff = column_scale as i64 - scale;
+    let factor = u32::try_from(diff.unsigned_abs()).ok()
+        .and_then(|exp| 10i128.checked_pow(exp))
+        .ok_or_else(|| invalid_data(format!("invalid scale {} in column {}", scale, column_id)))?;
+    if diff >= 0 {
+        x.checked_mul(factor)
+            .ok_or_else(|| invalid_data(format!("decimal overflow in column {}", column_id)))
+    } else {
+        Ok(x / factor)
+    }
+}
+
+impl GenericData for DecimalData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for DecimalData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        self.data = Some((
+            streams.required_stream(self.column_id, orc_proto::Stream_Kind::DATA)?,
+            streams.int_rle(self.column_id, orc_proto::Stream_Kind::SECONDARY, true)?,
+        ));
+        Ok(())
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        let column_id = self.column_id;
+        let (data, scales) = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        self.values.clear();
+        for _ in 0..num_values {
+            let x = zigzag_decode_i128(read_varint_u128(data)?);
+            self.values.push(rescale(x, scales.next()?, self.scale, self.column_id)?);
+        }
+        Ok(())
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        let column_id = self.column_id;
+        let (data, scales) = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        for _ in 0..num_values {
+            read_varint_u128(data)?;
+        }
+        scales.skip(num_values as u64)
+    }
+
+    fn seek(&mut self, streams: &StripeStreams, row: usize) -> Result<()> {
+        let column_id = self.column_id;
+        let present = &mut self.present;
+        let (data, scales) = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let num_rows = streams.seek_row_group(column_id, row, |positions| {
+            present.seek(positions)?;
+            data.seek(positions)?;
+            scales.seek(positions)
+        })?;
+        self.skip(num_rows)
+    }
+}
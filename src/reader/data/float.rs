@@ -0,0 +1,75 @@
+use std::convert::TryInto;
+use std::io::{Read, Result};
+
+use crate::protos::orc_proto;
+use crate::reader::compression::DecompressionStream;
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present, stripe_not_loaded};
+
+
+pub struct FloatData {
+    column_id: u32,
+    present: Present,
+    data: Option<DecompressionStream>,
+    values: Vec<f32>,
+}
+
+impl FloatData {
+    pub(crate) fn new(column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        FloatData {
+            column_id: cid,
+            present: Present::new(cid),
+            data: None,
+            values: Vec::new(),
+        }
+    }
+
+    /// The values of the non-null rows of the batch.
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+impl GenericData for FloatData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for FloatData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        self.data = Some(streams.required_stream(self.column_id, orc_proto::Stream_Kind::DATA)?);
+        Ok(())
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        let column_id = self.column_id;
+        let data = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let mut bytes = vec![0u8; num_values * 4];
+        data.read_exact(&mut bytes)?;
+        self.values.clear();
+        self.values.extend(bytes.chunks_exact(4).map(|b| f32::from_le_bytes(b.try_into().unwrap())));
+        Ok(())
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        let column_id = self.column_id;
+        let data = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        data.skip(num_values as u64 * 4)
+    }
+
+    fn seek(&mut self, streams: &StripeStreams, row: usize) -> Result<()> {
+        let column_id = self.column_id;
+        let present = &mut self.present;
+        let data = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let num_rows = streams.seek_row_group(column_id, row, |positions| {
+            present.seek(positions)?;
+            data.seek(positions)
+        })?;
+        self.skip(num_rows)
+    }
+}
@@ -0,0 +1,80 @@
+use std::io::Result;
+
+use crate::protos::orc_proto;
+use crate::schema::Schema;
+use crate::reader::decoder::IntRLEDecoder;
+use crate::reader::data::Data;
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present, read_lengths, sum_lengths, stripe_not_loaded};
+
+
+pub struct ListData {
+    column_id: u32,
+    present: Present,
+    lengths: Option<IntRLEDecoder>,
+    // The elements of the `i`-th non-null list are rows `offsets[i]..offsets[i + 1]` of the child
+    offsets: Vec<usize>,
+    pub(crate) child: Box<Data>,
+}
+
+impl ListData {
+    pub(crate) fn new(schema: &Schema, column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        if let Schema::List(child_schema) = schema {
+            ListData {
+                column_id: cid,
+                present: Present::new(cid),
+                lengths: None,
+                offsets: vec![0],
+                child: Box::new(Data::new(child_schema, column_id)),
+            }
+        } else { unreachable!() }
+    }
+
+    /// Start offsets of the non-null lists of the batch in the child, followed by the end offset of
+    /// the last one.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    pub fn child(&self) -> &Data {
+        &self.child
+    }
+}
+
+impl GenericData for ListData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for ListData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        self.lengths = Some(streams.int_rle(self.column_id, orc_proto::Stream_Kind::LENGTH, false)?);
+        self.child.load_stripe(streams)
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        let column_id = self.column_id;
+        let lengths = self.lengths.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        self.offsets.truncate(1);
+        let num_child_values = read_lengths(lengths, num_values, &mut self.offsets)?;
+        self.child.read_batch(num_child_values)
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        let column_id = self.column_id;
+        let lengths = self.lengths.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let num_child_values = sum_lengths(lengths, num_values)?;
+        self.child.skip(num_child_values)?;
+        Ok(())
+    }
+
+    fn seek(&mut self, _streams: &StripeStreams, row: usize) -> Result<()> {
+        // The row index entries of the elements are at rows of the stripe rather than their own
+        self.skip(row)
+    }
+}
@@ -0,0 +1,73 @@
+use std::io::Result;
+
+use crate::protos::orc_proto;
+use crate::reader::decoder::IntRLEDecoder;
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present, stripe_not_loaded};
+
+
+/// Data of a Short, Int, Long or Date column.
+pub struct LongData {
+    column_id: u32,
+    present: Present,
+    data: Option<IntRLEDecoder>,
+    values: Vec<i64>,
+}
+
+impl LongData {
+    pub(crate) fn new(column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        LongData {
+            column_id: cid,
+            present: Present::new(cid),
+            data: None,
+            values: Vec::new(),
+        }
+    }
+
+    /// The values of the non-null rows of the batch.
+    pub fn values(&self) -> &[i64] {
+        &self.values
+    }
+}
+
+impl GenericData for LongData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for LongData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        self.data = Some(streams.int_rle(self.column_id, orc_proto::Stream_Kind::DATA, true)?);
+        Ok(())
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        let column_id = self.column_id;
+        let data = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        self.values.clear();
+        self.values.resize(num_values, 0);
+        data.read_batch(&mut self.values)
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        let column_id = self.column_id;
+        let data = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        data.skip(num_values as u64)
+    }
+
+    fn seek(&mut self, streams: &StripeStreams, row: usize) -> Result<()> {
+        let column_id = self.column_id;
+        let present = &mut self.present;
+        let data = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let num_rows = streams.seek_row_group(column_id, row, |positions| {
+            present.seek(positions)?;
+            data.seek(positions)
+        })?;
+        self.skip(num_rows)
+    }
+}
@@ -0,0 +1,87 @@
+use std::io::Result;
+
+use crate::protos::orc_proto;
+use crate::schema::Schema;
+use crate::reader::decoder::IntRLEDecoder;
+use crate::reader::data::Data;
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present, read_lengths, sum_lengths, stripe_not_loaded};
+
+
+pub struct MapData {
+    column_id: u32,
+    present: Present,
+    lengths: Option<IntRLEDecoder>,
+    // The entries of the `i`-th non-null map are rows `offsets[i]..offsets[i + 1]` of the children
+    offsets: Vec<usize>,
+    pub(crate) keys: Box<Data>,
+    pub(crate) values: Box<Data>,
+}
+
+impl MapData {
+    pub(crate) fn new(schema: &Schema, column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        if let Schema::Map(key_schema, value_schema) = schema {
+            let keys = Box::new(Data::new(key_schema, column_id));
+            let values = Box::new(Data::new(value_schema, column_id));
+            MapData {
+                column_id: cid,
+                present: Present::new(cid),
+                lengths: None,
+                offsets: vec![0],
+                keys,
+                values,
+            }
+        } else { unreachable!() }
+    }
+
+    /// Start offsets of the non-null maps of the batch in the children, followed by the end offset
+    /// of the last one.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    pub fn children(&self) -> (&Data, &Data) {
+        (&self.keys, &self.values)
+    }
+}
+
+impl GenericData for MapData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for MapData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        self.lengths = Some(streams.int_rle(self.column_id, orc_proto::Stream_Kind::LENGTH, false)?);
+        self.keys.load_stripe(streams)?;
+        self.values.load_stripe(streams)
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        let column_id = self.column_id;
+        let lengths = self.lengths.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        self.offsets.truncate(1);
+        let num_child_values = read_lengths(lengths, num_values, &mut self.offsets)?;
+        self.keys.read_batch(num_child_values)?;
+        self.values.read_batch(num_child_values)
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        let column_id = self.column_id;
+        let lengths = self.lengths.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let num_child_values = sum_lengths(lengths, num_values)?;
+        self.keys.skip(num_child_values)?;
+        self.values.skip(num_child_values)?;
+        Ok(())
+    }
+
+    fn seek(&mut self, _streams: &StripeStreams, row: usize) -> Result<()> {
+        // The row index entries of the keys and values are at rows of the stripe rather than their own
+        self.skip(row)
+    }
+}
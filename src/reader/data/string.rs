@@ -0,0 +1,165 @@
+use std::io::{Read, Result};
+
+use crate::protos::orc_proto;
+use crate::reader::compression::DecompressionStream;
+use crate::reader::decoder::IntRLEDecoder;
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present, read_lengths, sum_lengths, invalid_data,
+    stripe_not_loaded};
+
+
+/// Data of a String, Char or VarChar column.
+pub struct StringData {
+    column_id: u32,
+    present: Present,
+    data: Option<StringDecoder>,
+    string: String,
+    // Value `i` is `string[offsets[i]..offsets[i + 1]]`
+    offsets: Vec<usize>,
+}
+
+enum StringDecoder {
+    Direct {
+        data: DecompressionStream,
+        lengths: IntRLEDecoder,
+    },
+    Dictionary {
+        data: IntRLEDecoder,
+        dictionary: String,
+        offsets: Vec<usize>,
+    },
+}
+
+/// Validates that `bytes` is UTF-8 and that every offset falls on a character boundary.
+fn to_string(bytes: Vec<u8>, offsets: &[usize], column_id: u32) -> Result<String> {
+    let s = String::from_utf8(bytes)
+        .map_err(|e| invalid_data(format!("invalid UTF-8 in column {}: {}", column_id, e)))?;
+    if offsets.iter().any(|&i| !s.is_char_boundary(i)) {
+        return Err(invalid_data(format!("invalid UTF-8 in column {}: value split within a character", column_id)));
+    }
+    Ok(s)
+}
+
+impl StringData {
+    pub(crate) fn new(column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        StringData {
+            column_id: cid,
+            present: Present::new(cid),
+            data: None,
+            string: String::new(),
+            offsets: vec![0],
+        }
+    }
+
+    /// Number of non-null values in the batch.
+    pub fn num_values(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// The `i`-th non-null value of the batch.
+    pub fn value(&self, i: usize) -> &str {
+        &self.string[self.offsets[i]..self.offsets[i + 1]]
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        (0..self.num_values()).map(move |i| self.value(i))
+    }
+
+    fn load_dictionary(&self, streams: &StripeStreams, dictionary_size: usize) -> Result<(String, Vec<usize>)> {
+        let mut lengths = streams.int_rle(self.column_id, orc_proto::Stream_Kind::LENGTH, false)?;
+        let mut offsets = vec![0];
+        let total_len = read_lengths(&mut lengths, dictionary_size, &mut offsets)?;
+        let mut bytes = vec![0; total_len];
+        streams.required_stream(self.column_id, orc_proto::Stream_Kind::DICTIONARY_DATA)?.read_exact(&mut bytes)?;
+        Ok((to_string(bytes, &offsets, self.column_id)?, offsets))
+    }
+}
+
+impl GenericData for StringData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for StringData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        let encoding = streams.encoding(self.column_id)?;
+        self.data = Some(match encoding.get_kind() {
+            orc_proto::ColumnEncoding_Kind::DIRECT | orc_proto::ColumnEncoding_Kind::DIRECT_V2 => StringDecoder::Direct {
+                data: streams.required_stream(self.column_id, orc_proto::Stream_Kind::DATA)?,
+                lengths: streams.int_rle(self.column_id, orc_proto::Stream_Kind::LENGTH, false)?,
+            },
+            orc_proto::ColumnEncoding_Kind::DICTIONARY | orc_proto::ColumnEncoding_Kind::DICTIONARY_V2 => {
+                let dictionary_size = encoding.get_dictionarySize() as usize;
+                let (dictionary, offsets) = self.load_dictionary(streams, dictionary_size)?;
+                StringDecoder::Dictionary {
+                    data: streams.int_rle(self.column_id, orc_proto::Stream_Kind::DATA, false)?,
+                    dictionary,
+                    offsets,
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        let column_id = self.column_id;
+        self.offsets.truncate(1);
+        match self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))? {
+            StringDecoder::Direct { data, lengths } => {
+                let total_len = read_lengths(lengths, num_values, &mut self.offsets)?;
+                let mut bytes = std::mem::take(&mut self.string).into_bytes();
+                bytes.clear();
+                bytes.resize(total_len, 0);
+                data.read_exact(&mut bytes)?;
+                self.string = to_string(bytes, &self.offsets, column_id)?;
+            }
+            StringDecoder::Dictionary { data, dictionary, offsets } => {
+                let mut ids = vec![0; num_values];
+                data.read_batch(&mut ids)?;
+                self.string.clear();
+                for id in ids {
+                    let id = id as u64 as usize;
+                    if id >= offsets.len() - 1 {
+                        return Err(invalid_data(format!("dictionary id {} out of range in column {}", id, column_id)));
+                    }
+                    self.string.push_str(&dictionary[offsets[id]..offsets[id + 1]]);
+                    self.offsets.push(self.string.len());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        let column_id = self.column_id;
+        match self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))? {
+            StringDecoder::Direct { data, lengths } => {
+                let total_len = sum_lengths(lengths, num_values)?;
+                data.skip(total_len as u64)
+            }
+            StringDecoder::Dictionary { data, .. } => data.skip(num_values as u64),
+        }
+    }
+
+    fn seek(&mut self, streams: &StripeStreams, row: usize) -> Result<()> {
+        let column_id = self.column_id;
+        let present = &mut self.present;
+        let data = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let num_rows = streams.seek_row_group(column_id, row, |positions| {
+            present.seek(positions)?;
+            match data {
+                StringDecoder::Direct { data, lengths } => {
+                    data.seek(positions)?;
+                    lengths.seek(positions)
+                }
+                StringDecoder::Dictionary { data, .. } => data.seek(positions),
+            }
+        })?;
+        self.skip(num_rows)
+    }
+}
@@ -0,0 +1,111 @@
+use std::io::Result;
+
+use crate::schema::Schema;
+use crate::reader::data::Data;
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present};
+
+
+pub struct StructData {
+    column_id: u32,
+    present: Present,
+    field_names: Vec<String>,
+    pub(crate) children: Vec<Data>,
+}
+
+impl StructData {
+    pub(crate) fn new(schema: &Schema, column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        if let Schema::Struct(fields) = schema {
+            StructData {
+                column_id: cid,
+                present: Present::new(cid),
+                field_names: fields.iter().map(|f| f.0.clone()).collect(),
+                children: fields.iter().map(|f| Data::new(&f.1, column_id)).collect(),
+            }
+        } else { unreachable!() }
+    }
+
+    pub(crate) fn new_projected(schema: &Schema, column_id: &mut u32, included: &[bool]) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        if let Schema::Struct(fields) = schema {
+            let mut field_names = Vec::new();
+            let mut children = Vec::new();
+            for field in fields {
+                if included[*column_id as usize] {
+                    field_names.push(field.0.clone());
+                    children.push(Data::new_projected(&field.1, column_id, included));
+                } else {
+                    *column_id += field.1.num_columns();
+                }
+            }
+            StructData {
+                column_id: cid,
+                present: Present::new(cid),
+                field_names,
+                children,
+            }
+        } else { unreachable!() }
+    }
+
+    /// The names of the fields read, in the order of the schema.
+    pub fn field_names(&self) -> &[String] {
+        &self.field_names
+    }
+
+    /// The fields of the struct. Each child has one row for every non-null row of the struct.
+    pub fn children(&self) -> &[Data] {
+        &self.children
+    }
+
+    pub fn child(&self, i: usize) -> &Data {
+        &self.children[i]
+    }
+}
+
+impl GenericData for StructData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for StructData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        for child in &mut self.children {
+            child.load_stripe(streams)?;
+        }
+        Ok(())
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        for child in &mut self.children {
+            child.read_batch(num_values)?;
+        }
+        Ok(())
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        for child in &mut self.children {
+            child.skip(num_values)?;
+        }
+        Ok(())
+    }
+
+    fn seek(&mut self, streams: &StripeStreams, row: usize) -> Result<()> {
+        let num_values = self.present.skip(row)?;
+        // The rows of the children only match the rows of the stripe if the struct has no nulls
+        let has_nulls = self.present.has_stream();
+        for child in &mut self.children {
+            if has_nulls {
+                child.skip(num_values)?;
+            } else {
+                child.seek(streams, num_values)?;
+            }
+        }
+        Ok(())
+    }
+}
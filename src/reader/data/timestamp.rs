@@ -0,0 +1,125 @@
+use std::io::Result;
+
+use crate::protos::orc_proto;
+use crate::reader::decoder::IntRLEDecoder;
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present, invalid_data, stripe_not_loaded};
+
+
+pub struct TimestampData {
+    column_id: u32,
+    present: Present,
+    data: Option<(IntRLEDecoder, IntRLEDecoder)>,
+    seconds: Vec<i64>,
+    nanos: Vec<u32>,
+}
+
+impl TimestampData {
+    /// Number of seconds between UNIX epoch and the ORC timestamp origin (2015-01-01)
+    pub const EPOCH_SECONDS: i64 = crate::writer::data::TimestampData::EPOCH_SECONDS;
+
+    pub(crate) fn new(column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        TimestampData {
+            column_id: cid,
+            present: Present::new(cid),
+            data: None,
+            seconds: Vec::new(),
+            nanos: Vec::new(),
+        }
+    }
+
+    /// Seconds of the non-null rows of the batch, relative to the ORC timestamp origin (as passed to
+    /// `writer::data::TimestampData::write_nanos`).
+    pub fn seconds(&self) -> &[i64] {
+        &self.seconds
+    }
+
+    /// Nanoseconds of the non-null rows of the batch.
+    pub fn nanos(&self) -> &[u32] {
+        &self.nanos
+    }
+
+    /// Seconds of the `i`-th non-null value since UNIX epoch.
+    pub fn seconds_epoch(&self, i: usize) -> i64 {
+        self.seconds[i] - Self::EPOCH_SECONDS
+    }
+
+    fn decode_nanos(x: i64, column_id: u32) -> Result<u32> {
+        let x = x as u64;
+        let trailing_zeros = (x & 7) as u32;
+        let nanos = (x >> 3) as u32;
+        let scale = if trailing_zeros == 0 { Some(1) } else { 10u32.checked_pow(trailing_zeros + 1) };
+        scale.and_then(|scale| nanos.checked_mul(scale))
+            .filter(|&nanos| nanos < 1_000_000_000)
+            .ok_or_else(|| invalid_data(format!("invalid encoded nanoseconds {} in column {}", x, column_id)))
+    }
+}
+
+impl GenericData for TimestampData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for TimestampData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        self.data = Some((
+            streams.int_rle(self.column_id, orc_proto::Stream_Kind::DATA, true)?,
+            streams.int_rle(self.column_id, orc_proto::Stream_Kind::SECONDARY, false)?,
+        ));
+        Ok(())
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        let column_id = self.column_id;
+        let (seconds, nanos) = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        self.seconds.clear();
+        self.seconds.resize(num_values, 0);
+        seconds.read_batch(&mut self.seconds)?;
+        let mut encoded_nanos = vec![0; num_values];
+        nanos.read_batch(&mut encoded_nanos)?;
+        self.nanos.clear();
+        for x in encoded_nanos {
+            self.nanos.push(Self::decode_nanos(x, column_id)?);
+        }
+        Ok(())
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        let column_id = self.column_id;
+        let (seconds, nanos) = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        seconds.skip(num_values as u64)?;
+        nanos.skip(num_values as u64)
+    }
+
+    fn seek(&mut self, streams: &StripeStreams, row: usize) -> Result<()> {
+        let column_id = self.column_id;
+        let present = &mut self.present;
+        let (seconds, nanos) = self.data.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        let num_rows = streams.seek_row_group(column_id, row, |positions| {
+            present.seek(positions)?;
+            seconds.seek(positions)?;
+            nanos.seek(positions)
+        })?;
+        self.skip(num_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimestampData;
+
+    #[test]
+    fn test_decode_nanos() {
+        assert_eq!(TimestampData::decode_nanos(999_999_999 << 3, 1).unwrap(), 999_999_999);
+        // 1 with 8 trailing zeros
+        assert_eq!(TimestampData::decode_nanos(1 << 3 | 7, 1).unwrap(), 100_000_000);
+        assert!(TimestampData::decode_nanos(10 << 3 | 7, 1).is_err());
+        assert!(TimestampData::decode_nanos(1_000_000_000 << 3, 1).is_err());
+        assert!(TimestampData::decode_nanos(-1, 1).is_err());
+    }
+}
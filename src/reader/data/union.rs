@@ -0,0 +1,103 @@
+use std::io::Result;
+
+use crate::protos::orc_proto;
+use crate::schema::Schema;
+use crate::reader::decoder::ByteRLEDecoder;
+use crate::reader::data::Data;
+use crate::reader::data::common::{GenericData, BaseData, StripeStreams, Present, invalid_data, stripe_not_loaded};
+
+
+pub struct UnionData {
+    column_id: u32,
+    present: Present,
+    tags: Option<ByteRLEDecoder>,
+    values: Vec<u8>,
+    pub(crate) children: Vec<Data>,
+}
+
+impl UnionData {
+    pub(crate) fn new(schema: &Schema, column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        if let Schema::Union(child_schemas) = schema {
+            UnionData {
+                column_id: cid,
+                present: Present::new(cid),
+                tags: None,
+                values: Vec::new(),
+                children: child_schemas.iter().map(|s| Data::new(s, column_id)).collect(),
+            }
+        } else { unreachable!() }
+    }
+
+    /// The tags of the non-null rows of the batch. The value of a row with tag `t` is the next row of
+    /// child `t`.
+    pub fn tags(&self) -> &[u8] {
+        &self.values
+    }
+
+    /// Reads the next `num_values` tags, returning the number of rows of each child they refer to.
+    fn read_tags(&mut self, num_values: usize, tags: &mut Vec<u8>) -> Result<Vec<usize>> {
+        let column_id = self.column_id;
+        let decoder = self.tags.as_mut().ok_or_else(|| stripe_not_loaded(column_id))?;
+        tags.clear();
+        tags.resize(num_values, 0);
+        decoder.read_batch(tags)?;
+        let mut child_counts = vec![0; self.children.len()];
+        for &tag in tags.iter() {
+            *child_counts.get_mut(tag as usize)
+                .ok_or_else(|| invalid_data(format!("tag {} out of range in column {}", tag, column_id)))? += 1;
+        }
+        Ok(child_counts)
+    }
+
+    pub fn children(&self) -> &[Data] {
+        &self.children
+    }
+
+    pub fn child(&self, i: usize) -> &Data {
+        &self.children[i]
+    }
+}
+
+impl GenericData for UnionData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn present(&self) -> &[bool] { self.present.values() }
+}
+
+impl BaseData for UnionData {
+    fn load_stripe(&mut self, streams: &StripeStreams) -> Result<()> {
+        self.present.load_stripe(streams);
+        self.tags = Some(ByteRLEDecoder::new(streams.required_stream(self.column_id, orc_proto::Stream_Kind::DATA)?));
+        for child in &mut self.children {
+            child.load_stripe(streams)?;
+        }
+        Ok(())
+    }
+
+    fn read_batch(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.read_batch(num_rows)?;
+        let mut tags = std::mem::take(&mut self.values);
+        let child_counts = self.read_tags(num_values, &mut tags);
+        self.values = tags;
+        for (child, count) in self.children.iter_mut().zip(child_counts?) {
+            child.read_batch(count)?;
+        }
+        Ok(())
+    }
+
+    fn skip(&mut self, num_rows: usize) -> Result<()> {
+        let num_values = self.present.skip(num_rows)?;
+        let child_counts = self.read_tags(num_values, &mut Vec::new())?;
+        for (child, count) in self.children.iter_mut().zip(child_counts) {
+            child.skip(count)?;
+        }
+        Ok(())
+    }
+
+    fn seek(&mut self, _streams: &StripeStreams, row: usize) -> Result<()> {
+        // The row index entries of the children are at rows of the stripe rather than their own
+        self.skip(row)
+    }
+}
@@ -0,0 +1,11 @@
+pub(crate) use boolean_rle::BooleanRLEDecoder;
+pub(crate) use byte_rle::ByteRLEDecoder;
+pub(crate) use int_rle::IntRLEDecoder;
+pub(crate) use varint::{read_varint_u128, zigzag_decode_i128};
+
+mod boolean_rle;
+mod byte_rle;
+mod int_rle;
+mod int_rle_v1;
+mod int_rle_v2;
+mod varint;
@@ -0,0 +1,123 @@
+use std::io::{Result, Error, ErrorKind};
+use crate::reader::compression::{DecompressionStream, PositionProvider};
+
+use crate::reader::decoder::byte_rle::ByteRLEDecoder;
+
+
+/// Decoder for the bit-packed booleans produced by `writer::encoder::BooleanRLE`.
+pub struct BooleanRLEDecoder {
+    byte_rle: ByteRLEDecoder,
+    buf: u8,
+    // Number of bits of `buf` not yet consumed
+    bits_left: u8,
+}
+
+impl BooleanRLEDecoder {
+    pub fn new(source: DecompressionStream) -> Self {
+        BooleanRLEDecoder {
+            byte_rle: ByteRLEDecoder::new(source),
+            buf: 0,
+            bits_left: 0,
+        }
+    }
+
+    #[inline(always)]
+    pub fn next(&mut self) -> Result<bool> {
+        if self.bits_left == 0 {
+            self.buf = self.byte_rle.next()?;
+            self.bits_left = 8;
+        }
+        self.bits_left -= 1;
+        Ok((self.buf >> self.bits_left) & 1 == 1)
+    }
+
+    pub fn read_batch(&mut self, out: &mut [bool]) -> Result<()> {
+        for x in out.iter_mut() {
+            *x = self.next()?;
+        }
+        Ok(())
+    }
+
+    pub fn skip(&mut self, num_values: u64) -> Result<()> {
+        if num_values <= self.bits_left as u64 {
+            self.bits_left -= num_values as u8;
+            return Ok(());
+        }
+        let remaining = num_values - self.bits_left as u64;
+        self.bits_left = 0;
+        self.byte_rle.skip(remaining / 8)?;
+        let bits = (remaining % 8) as u8;
+        if bits > 0 {
+            self.buf = self.byte_rle.next()?;
+            self.bits_left = 8 - bits;
+        }
+        Ok(())
+    }
+
+    /// Seeks to a position recorded by `BooleanRLEPosition::record`.
+    pub fn seek(&mut self, positions: &mut PositionProvider) -> Result<()> {
+        self.byte_rle.seek(positions)?;
+        let bits = positions.next()?;
+        if bits > 7 {
+            return Err(Error::new(ErrorKind::InvalidData, format!("invalid bit offset {} in boolean stream position", bits)));
+        }
+        self.bits_left = 0;
+        if bits > 0 {
+            self.buf = self.byte_rle.next()?;
+            self.bits_left = 8 - bits as u8;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::compression::Compression;
+    use crate::protos::orc_proto;
+    use crate::writer::compression::ZstdCompression;
+    use crate::writer::encoder::BooleanRLE;
+
+    #[test]
+    fn test_boolean_rle_decoder() {
+        let compression = ZstdCompression::new().with_block_size(64).unwrap().build();
+        let values: Vec<bool> = (0..10100u32).map(|i| if (i / 1000) % 2 == 0 { i % 3 == 0 } else { i % 500 < 250 }).collect();
+        let mut rle = BooleanRLE::new(&compression);
+        let mut positions: Vec<Vec<u64>> = Vec::new();
+        for (i, &x) in values.iter().enumerate() {
+            if i % 333 == 0 {
+                let mut position = Vec::new();
+                rle.position().record(rle.sink(), &mut position);
+                positions.push(position);
+            }
+            rle.write(x);
+        }
+        let mut out: Vec<u8> = Vec::new();
+        rle.finish(&mut out).unwrap();
+
+        let reader_compression = Compression::new(orc_proto::CompressionKind::ZSTD, 64).unwrap();
+        let mut decoder = BooleanRLEDecoder::new(DecompressionStream::new(out, &reader_compression));
+        let mut decoded = vec![false; values.len()];
+        decoder.read_batch(&mut decoded).unwrap();
+        assert_eq!(decoded, values);
+
+        for (n, position) in positions.iter().enumerate().rev() {
+            decoder.seek(&mut PositionProvider::new(position)).unwrap();
+            assert_eq!(decoder.next().unwrap(), values[n * 333]);
+            for skip in &[0, 3, 8, 21] {
+                decoder.skip(*skip).unwrap();
+            }
+            assert_eq!(decoder.next().unwrap(), values[n * 333 + 33]);
+        }
+    }
+
+    #[test]
+    fn test_boolean_rle_decoder_padding() {
+        let compression = Compression::new(orc_proto::CompressionKind::NONE, 0).unwrap();
+        let input = vec![254, 0b10101000, 0b10000000];
+        let mut decoder = BooleanRLEDecoder::new(DecompressionStream::new(input, &compression));
+        let mut decoded = vec![false; 9];
+        decoder.read_batch(&mut decoded).unwrap();
+        assert_eq!(decoded, vec![true, false, true, false, true, false, false, false, true]);
+    }
+}
@@ -0,0 +1,142 @@
+use std::io::{Read, Result};
+use crate::reader::compression::{DecompressionStream, PositionProvider};
+
+
+/// Decoder for the byte run-length encoding produced by `writer::encoder::ByteRLE`.
+pub struct ByteRLEDecoder {
+    source: DecompressionStream,
+    buf: [u8; 128],
+    // Number of values in the current group
+    group_len: usize,
+    // Number of values of the current group that have been consumed
+    used: usize,
+    is_run: bool,
+}
+
+impl ByteRLEDecoder {
+    pub fn new(source: DecompressionStream) -> Self {
+        ByteRLEDecoder {
+            source,
+            buf: [0; 128],
+            group_len: 0,
+            used: 0,
+            is_run: false,
+        }
+    }
+
+    fn read_group(&mut self) -> Result<()> {
+        let header = self.source.read_u8()?;
+        if header < 0x80 {
+            self.is_run = true;
+            self.group_len = header as usize + 3;
+            self.buf[0] = self.source.read_u8()?;
+        } else {
+            self.is_run = false;
+            self.group_len = 0x100 - header as usize;
+            self.source.read_exact(&mut self.buf[..self.group_len])?;
+        }
+        self.used = 0;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn next(&mut self) -> Result<u8> {
+        if self.used == self.group_len {
+            self.read_group()?;
+        }
+        let x = if self.is_run { self.buf[0] } else { self.buf[self.used] };
+        self.used += 1;
+        Ok(x)
+    }
+
+    pub fn read_batch(&mut self, out: &mut [u8]) -> Result<()> {
+        let mut i = 0;
+        while i < out.len() {
+            if self.used == self.group_len {
+                self.read_group()?;
+            }
+            let n = (self.group_len - self.used).min(out.len() - i);
+            if self.is_run {
+                out[i..i + n].iter_mut().for_each(|x| *x = self.buf[0]);
+            } else {
+                out[i..i + n].copy_from_slice(&self.buf[self.used..self.used + n]);
+            }
+            self.used += n;
+            i += n;
+        }
+        Ok(())
+    }
+
+    pub fn skip(&mut self, mut num_values: u64) -> Result<()> {
+        while num_values > 0 {
+            if self.used == self.group_len {
+                self.read_group()?;
+            }
+            let n = ((self.group_len - self.used) as u64).min(num_values);
+            self.used += n as usize;
+            num_values -= n;
+        }
+        Ok(())
+    }
+
+    /// Seeks to a position recorded by `ByteRLEPosition::record`.
+    pub fn seek(&mut self, positions: &mut PositionProvider) -> Result<()> {
+        self.source.seek(positions)?;
+        self.group_len = 0;
+        self.used = 0;
+        let rle_offset = positions.next()?;
+        self.skip(rle_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::compression::Compression;
+    use crate::protos::orc_proto;
+    use crate::writer::compression::SnappyCompression;
+    use crate::writer::encoder::ByteRLE;
+
+    #[test]
+    fn test_byte_rle_decoder() {
+        let compression = SnappyCompression::new().with_block_size(100).unwrap().build();
+        let values: Vec<u8> = (0..5000u32).map(|i| if (i / 200) % 2 == 0 { (i / 7) as u8 } else { (i * i % 251) as u8 }).collect();
+        let mut rle = ByteRLE::new(&compression);
+        let mut positions: Vec<Vec<u64>> = Vec::new();
+        for (i, &x) in values.iter().enumerate() {
+            if i % 300 == 0 {
+                let mut position = Vec::new();
+                rle.position().record(rle.sink(), &mut position);
+                positions.push(position);
+            }
+            rle.write(x);
+        }
+        let mut out: Vec<u8> = Vec::new();
+        rle.finish(&mut out).unwrap();
+
+        let reader_compression = Compression::new(orc_proto::CompressionKind::SNAPPY, 100).unwrap();
+        let mut decoder = ByteRLEDecoder::new(DecompressionStream::new(out, &reader_compression));
+        let mut decoded = vec![0; values.len()];
+        decoder.read_batch(&mut decoded).unwrap();
+        assert_eq!(decoded, values);
+        assert!(decoder.next().is_err());
+
+        for (n, position) in positions.iter().enumerate().rev() {
+            decoder.seek(&mut PositionProvider::new(position)).unwrap();
+            assert_eq!(decoder.next().unwrap(), values[n * 300]);
+            decoder.skip(17).unwrap();
+            assert_eq!(decoder.next().unwrap(), values[n * 300 + 18]);
+        }
+    }
+
+    #[test]
+    fn test_byte_rle_decoder_literal_and_run() {
+        let compression = Compression::new(orc_proto::CompressionKind::NONE, 0).unwrap();
+        let input = vec![2, 10, 254, 20, 30, 127, 10, 255, 10];
+        let mut decoder = ByteRLEDecoder::new(DecompressionStream::new(input, &compression));
+        let mut decoded = vec![0; 138];
+        decoder.read_batch(&mut decoded).unwrap();
+        let expected = [vec![10; 5], vec![20, 30], vec![10; 131]].concat();
+        assert_eq!(decoded, expected);
+    }
+}
@@ -0,0 +1,163 @@
+use std::io::Result;
+use crate::protos::orc_proto;
+use crate::reader::compression::{DecompressionStream, PositionProvider};
+use super::int_rle_v1::IntRLEv1Decoder;
+use super::int_rle_v2::IntRLEv2Decoder;
+
+
+/// Decoder for an integer stream, using the run-length encoding version implied by the
+/// encoding of its column.
+pub enum IntRLEDecoder {
+    // Boxed, as its buffer makes it much larger than V2
+    V1(Box<IntRLEv1Decoder>),
+    V2(IntRLEv2Decoder),
+}
+
+impl IntRLEDecoder {
+    pub fn new(source: DecompressionStream, signed: bool, encoding: orc_proto::ColumnEncoding_Kind) -> Self {
+        match encoding {
+            orc_proto::ColumnEncoding_Kind::DIRECT
+            | orc_proto::ColumnEncoding_Kind::DICTIONARY => IntRLEDecoder::V1(Box::new(IntRLEv1Decoder::new(source, signed))),
+            orc_proto::ColumnEncoding_Kind::DIRECT_V2
+            | orc_proto::ColumnEncoding_Kind::DICTIONARY_V2 => IntRLEDecoder::V2(IntRLEv2Decoder::new(source, signed)),
+        }
+    }
+
+    #[inline(always)]
+    pub fn next(&mut self) -> Result<i64> {
+        match self {
+            IntRLEDecoder::V1(d) => d.next(),
+            IntRLEDecoder::V2(d) => d.next(),
+        }
+    }
+
+    pub fn read_batch(&mut self, out: &mut [i64]) -> Result<()> {
+        match self {
+            IntRLEDecoder::V1(d) => d.read_batch(out),
+            IntRLEDecoder::V2(d) => d.read_batch(out),
+        }
+    }
+
+    pub fn skip(&mut self, num_values: u64) -> Result<()> {
+        match self {
+            IntRLEDecoder::V1(d) => d.skip(num_values),
+            IntRLEDecoder::V2(d) => d.skip(num_values),
+        }
+    }
+
+    pub fn seek(&mut self, positions: &mut PositionProvider) -> Result<()> {
+        match self {
+            IntRLEDecoder::V1(d) => d.seek(positions),
+            IntRLEDecoder::V2(d) => d.seek(positions),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reader::compression::Compression;
+    use crate::writer::Version;
+    use crate::writer::compression::ZstdCompression;
+    use crate::writer::encoder::{SignedIntRLE, UnsignedIntRLE};
+
+    fn test_values() -> Vec<i64> {
+        let mut sum = 0;
+        (0..16000i64).map(|i| match (i / 1000) % 8 {
+            0 => i * 3,
+            1 => (i * i * 7919) % 100003 - 50000,
+            2 => 42,
+            // Small values with rare outliers, for PATCHED_BASE
+            3 => if i % 37 == 0 { 1 << 40 } else { i % 16 },
+            4 => (i / 5) % 7,
+            5 => {
+                sum += i % 13;
+                sum
+            }
+            6 => if i % 2 == 0 { i64::MIN } else { i64::MAX - i },
+            _ => -i * i,
+        }).collect()
+    }
+
+    #[test]
+    fn test_int_rle_roundtrip() {
+        let values = test_values();
+        for &(version, encoding) in &[
+            (Version::V0_11, orc_proto::ColumnEncoding_Kind::DIRECT),
+            (Version::V0_12, orc_proto::ColumnEncoding_Kind::DIRECT_V2),
+        ] {
+            let compression = ZstdCompression::new().with_block_size(256).unwrap().build();
+            let mut signed = SignedIntRLE::new(&compression, version);
+            let mut unsigned = UnsignedIntRLE::new(&compression, version);
+            let mut positions: Vec<(Vec<u64>, Vec<u64>)> = Vec::new();
+            for (i, &x) in values.iter().enumerate() {
+                if i % 347 == 0 {
+                    let mut signed_position = Vec::new();
+                    signed.position().record(signed.sink(), &mut signed_position);
+                    let mut unsigned_position = Vec::new();
+                    unsigned.position().record(unsigned.sink(), &mut unsigned_position);
+                    positions.push((signed_position, unsigned_position));
+                }
+                signed.write(x);
+                unsigned.write(x as u64);
+            }
+            let mut signed_out: Vec<u8> = Vec::new();
+            signed.finish(&mut signed_out).unwrap();
+            let mut unsigned_out: Vec<u8> = Vec::new();
+            unsigned.finish(&mut unsigned_out).unwrap();
+
+            let reader_compression = Compression::new(orc_proto::CompressionKind::ZSTD, 256).unwrap();
+            let mut signed = IntRLEDecoder::new(DecompressionStream::new(signed_out, &reader_compression), true, encoding);
+            let mut unsigned = IntRLEDecoder::new(DecompressionStream::new(unsigned_out, &reader_compression), false, encoding);
+            let mut decoded = vec![0; values.len()];
+            signed.read_batch(&mut decoded).unwrap();
+            assert_eq!(decoded, values);
+            unsigned.read_batch(&mut decoded).unwrap();
+            assert_eq!(decoded, values);
+
+            for (n, (signed_position, unsigned_position)) in positions.iter().enumerate().rev() {
+                let row = n * 347;
+                signed.seek(&mut PositionProvider::new(signed_position)).unwrap();
+                unsigned.seek(&mut PositionProvider::new(unsigned_position)).unwrap();
+                assert_eq!(signed.next().unwrap(), values[row]);
+                assert_eq!(unsigned.next().unwrap(), values[row]);
+                if row + 200 < values.len() {
+                    signed.skip(199).unwrap();
+                    assert_eq!(signed.next().unwrap(), values[row + 200]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_int_rle_v2_smaller() {
+        let compression = ZstdCompression::new().build();
+        let mut sizes = Vec::new();
+        for &version in &[Version::V0_11, Version::V0_12] {
+            let mut rle = UnsignedIntRLE::new(&compression, version);
+            for i in 0..100000 {
+                rle.write(i * 1000 + i % 7);
+            }
+            let mut out: Vec<u8> = Vec::new();
+            rle.finish(&mut out).unwrap();
+            sizes.push(out.len());
+        }
+        assert!(sizes[1] < sizes[0]);
+    }
+
+    #[test]
+    fn test_int_rle_decoder_version() {
+        let compression = Compression::new(orc_proto::CompressionKind::NONE, 0).unwrap();
+        // The same bytes decode differently depending on the encoding of the column
+        let input = vec![0x0a, 0x27, 0x10];
+        let mut v1 = IntRLEDecoder::new(DecompressionStream::new(input.clone(), &compression), false, orc_proto::ColumnEncoding_Kind::DIRECT);
+        let mut v2 = IntRLEDecoder::new(DecompressionStream::new(input, &compression), false, orc_proto::ColumnEncoding_Kind::DICTIONARY_V2);
+        let mut out = vec![0; 5];
+        v2.read_batch(&mut out).unwrap();
+        assert_eq!(out, vec![10000; 5]);
+        assert_eq!(v1.next().unwrap(), 16);
+        assert_eq!(v1.next().unwrap(), 16 + 0x27);
+        v1.skip(11).unwrap();
+        assert!(v1.next().is_err());
+    }
+}
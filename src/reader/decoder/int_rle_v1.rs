@@ -0,0 +1,206 @@
+use std::io::Result;
+use crate::reader::compression::{DecompressionStream, PositionProvider};
+use super::varint::{read_varint_u64, zigzag_decode_i64};
+
+
+/// Decoder for the integer run-length encoding (version 1) produced by
+/// `writer::encoder::{SignedIntRLEv1, UnsignedIntRLEv1}`. Unsigned values are returned
+/// reinterpreted as `i64`.
+pub struct IntRLEv1Decoder {
+    source: DecompressionStream,
+    signed: bool,
+    buf: [i64; 128],
+    // Number of values in the current group
+    group_len: usize,
+    // Number of values of the current group that have been consumed
+    used: usize,
+    is_run: bool,
+    // For runs, the first value and the (signed) difference between consecutive values
+    run_base: i64,
+    run_delta: i64,
+}
+
+impl IntRLEv1Decoder {
+    pub fn new(source: DecompressionStream, signed: bool) -> Self {
+        IntRLEv1Decoder {
+            source,
+            signed,
+            buf: [0; 128],
+            group_len: 0,
+            used: 0,
+            is_run: false,
+            run_base: 0,
+            run_delta: 0,
+        }
+    }
+
+    #[inline(always)]
+    fn read_value(&mut self) -> Result<i64> {
+        let x = read_varint_u64(&mut self.source)?;
+        Ok(if self.signed { zigzag_decode_i64(x) } else { x as i64 })
+    }
+
+    fn read_group(&mut self) -> Result<()> {
+        let header = self.source.read_u8()?;
+        if header < 0x80 {
+            self.is_run = true;
+            self.group_len = header as usize + 3;
+            self.run_delta = self.source.read_u8()? as i8 as i64;
+            self.run_base = self.read_value()?;
+        } else {
+            self.is_run = false;
+            self.group_len = 0x100 - header as usize;
+            for i in 0..self.group_len {
+                self.buf[i] = self.read_value()?;
+            }
+        }
+        self.used = 0;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn next(&mut self) -> Result<i64> {
+        if self.used == self.group_len {
+            self.read_group()?;
+        }
+        let x = if self.is_run {
+            self.run_base.wrapping_add((self.used as i64).wrapping_mul(self.run_delta))
+        } else {
+            self.buf[self.used]
+        };
+        self.used += 1;
+        Ok(x)
+    }
+
+    pub fn read_batch(&mut self, out: &mut [i64]) -> Result<()> {
+        let mut i = 0;
+        while i < out.len() {
+            if self.used == self.group_len {
+                self.read_group()?;
+            }
+            let n = (self.group_len - self.used).min(out.len() - i);
+            if self.is_run {
+                for (j, x) in out[i..i + n].iter_mut().enumerate() {
+                    *x = self.run_base.wrapping_add(((self.used + j) as i64).wrapping_mul(self.run_delta));
+                }
+            } else {
+                out[i..i + n].copy_from_slice(&self.buf[self.used..self.used + n]);
+            }
+            self.used += n;
+            i += n;
+        }
+        Ok(())
+    }
+
+    pub fn skip(&mut self, mut num_values: u64) -> Result<()> {
+        while num_values > 0 {
+            if self.used == self.group_len {
+                self.read_group()?;
+            }
+            let n = ((self.group_len - self.used) as u64).min(num_values);
+            self.used += n as usize;
+            num_values -= n;
+        }
+        Ok(())
+    }
+
+    /// Seeks to a position recorded by `IntRLEv1Position::record`.
+    pub fn seek(&mut self, positions: &mut PositionProvider) -> Result<()> {
+        self.source.seek(positions)?;
+        self.group_len = 0;
+        self.used = 0;
+        let rle_offset = positions.next()?;
+        self.skip(rle_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protos::orc_proto;
+    use crate::reader::compression::Compression;
+    use crate::writer::Version;
+    use crate::writer::compression::ZstdCompression;
+    use crate::writer::encoder::{SignedIntRLE, UnsignedIntRLE};
+
+    fn decode(input: Vec<u8>, signed: bool, len: usize) -> Vec<i64> {
+        let compression = Compression::new(orc_proto::CompressionKind::NONE, 0).unwrap();
+        let mut decoder = IntRLEv1Decoder::new(DecompressionStream::new(input, &compression), signed);
+        let mut out = vec![0; len];
+        decoder.read_batch(&mut out).unwrap();
+        assert!(decoder.next().is_err());
+        out
+    }
+
+    #[test]
+    fn test_signed_int_rle_v1_decoder() {
+        let cases = vec![
+            (vec![], vec![]),
+            (vec![10], vec![255, 20]),
+            (vec![0, -1, 1, -2, 2], vec![251, 0, 1, 2, 3, 4]),
+            (vec![10, 10, 10, 10], vec![1, 0, 20]),
+            (vec![10, 15, 20, 25], vec![1, 5, 20]),
+            (vec![10, 15, 20, 25, 0], vec![1, 5, 20, 255, 0]),
+            (vec![10, 9, 8, 7], vec![1, 255, 20]),
+        ];
+        for (expected_output, input) in cases {
+            assert_eq!(decode(input, true, expected_output.len()), expected_output);
+        }
+    }
+
+    #[test]
+    fn test_unsigned_int_rle_v1_decoder() {
+        assert_eq!(decode(vec![97, 0, 7], false, 100), vec![7; 100]);
+        assert_eq!(decode(vec![254, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 1], false, 2), vec![-1, 1]);
+    }
+
+    #[test]
+    fn test_int_rle_v1_decoder_seek() {
+        let compression = ZstdCompression::new().with_block_size(128).unwrap().build();
+        let values: Vec<i64> = (0..10000i64).map(|i| match (i / 500) % 3 {
+            0 => i * 3,
+            1 => (i * i * 7919) % 100003 - 50000,
+            _ => 42,
+        }).collect();
+
+        let mut signed = SignedIntRLE::new(&compression, Version::V0_11);
+        let mut unsigned = UnsignedIntRLE::new(&compression, Version::V0_11);
+        let mut positions: Vec<(Vec<u64>, Vec<u64>)> = Vec::new();
+        for (i, &x) in values.iter().enumerate() {
+            if i % 347 == 0 {
+                let mut signed_position = Vec::new();
+                signed.position().record(signed.sink(), &mut signed_position);
+                let mut unsigned_position = Vec::new();
+                unsigned.position().record(unsigned.sink(), &mut unsigned_position);
+                positions.push((signed_position, unsigned_position));
+            }
+            signed.write(x);
+            unsigned.write(x.abs() as u64);
+        }
+        let mut signed_out: Vec<u8> = Vec::new();
+        signed.finish(&mut signed_out).unwrap();
+        let mut unsigned_out: Vec<u8> = Vec::new();
+        unsigned.finish(&mut unsigned_out).unwrap();
+
+        let reader_compression = Compression::new(orc_proto::CompressionKind::ZSTD, 128).unwrap();
+        let mut signed = IntRLEv1Decoder::new(DecompressionStream::new(signed_out, &reader_compression), true);
+        let mut unsigned = IntRLEv1Decoder::new(DecompressionStream::new(unsigned_out, &reader_compression), false);
+        let mut decoded = vec![0; values.len()];
+        signed.read_batch(&mut decoded).unwrap();
+        assert_eq!(decoded, values);
+        unsigned.read_batch(&mut decoded).unwrap();
+        assert_eq!(decoded, values.iter().map(|x| x.abs()).collect::<Vec<_>>());
+
+        for (n, (signed_position, unsigned_position)) in positions.iter().enumerate().rev() {
+            let row = n * 347;
+            signed.seek(&mut PositionProvider::new(signed_position)).unwrap();
+            unsigned.seek(&mut PositionProvider::new(unsigned_position)).unwrap();
+            assert_eq!(signed.next().unwrap(), values[row]);
+            assert_eq!(unsigned.next().unwrap(), values[row].abs());
+            if row + 200 < values.len() {
+                signed.skip(199).unwrap();
+                assert_eq!(signed.next().unwrap(), values[row + 200]);
+            }
+        }
+    }
+}
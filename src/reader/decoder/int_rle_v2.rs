@@ -0,0 +1,382 @@
+use std::io::{Result, Error, ErrorKind};
+use crate::reader::compression::{DecompressionStream, PositionProvider};
+use super::varint::{read_varint_u64, zigzag_decode_i64};
+
+
+/// Decoder for the integer run-length encoding version 2, as written by the Java and C++ ORC
+/// writers for `DIRECT_V2` and `DICTIONARY_V2` columns. Unsigned values are returned
+/// reinterpreted as `i64`.
+pub struct IntRLEv2Decoder {
+    source: DecompressionStream,
+    signed: bool,
+    // Values of the current run. A run holds at most 512 values.
+    buf: Vec<i64>,
+    // Number of values of the current run that have been consumed
+    used: usize,
+    // Scratch space for patch lists of PATCHED_BASE runs
+    patches: Vec<i64>,
+}
+
+const SHORT_REPEAT: u8 = 0;
+const DIRECT: u8 = 1;
+const PATCHED_BASE: u8 = 2;
+const DELTA: u8 = 3;
+
+const MIN_REPEAT_SIZE: usize = 3;
+
+fn invalid_data(msg: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Maps the 5-bit width code stored in run headers to a bit width.
+pub(crate) fn decode_bit_width(code: u8) -> u32 {
+    match code {
+        0..=23 => code as u32 + 1,
+        24 => 26,
+        25 => 28,
+        26 => 30,
+        27 => 32,
+        28 => 40,
+        29 => 48,
+        30 => 56,
+        _ => 64,
+    }
+}
+
+/// Rounds a bit width up to the nearest width that has a 5-bit code.
+pub(crate) fn closest_fixed_bits(n: u32) -> u32 {
+    match n {
+        0 => 1,
+        1..=24 => n,
+        25..=26 => 26,
+        27..=28 => 28,
+        29..=30 => 30,
+        31..=32 => 32,
+        33..=40 => 40,
+        41..=48 => 48,
+        49..=56 => 56,
+        _ => 64,
+    }
+}
+
+impl IntRLEv2Decoder {
+    pub fn new(source: DecompressionStream, signed: bool) -> Self {
+        IntRLEv2Decoder {
+            source,
+            signed,
+            buf: Vec::with_capacity(512),
+            used: 0,
+            patches: Vec::new(),
+        }
+    }
+
+    #[inline(always)]
+    fn read_signed_varint(&mut self) -> Result<i64> {
+        Ok(zigzag_decode_i64(read_varint_u64(&mut self.source)?))
+    }
+
+    fn read_big_endian(&mut self, num_bytes: usize) -> Result<u64> {
+        let mut x: u64 = 0;
+        for _ in 0..num_bytes {
+            x = (x << 8) | self.source.read_u8()? as u64;
+        }
+        Ok(x)
+    }
+
+    /// Appends `len` big-endian bit-packed values of `width` bits to `out`.
+    fn read_packed(source: &mut DecompressionStream, out: &mut Vec<i64>, len: usize, width: u32) -> Result<()> {
+        let mut current: u64 = 0;
+        let mut bits_left: u32 = 0;
+        for _ in 0..len {
+            let mut x: u64 = 0;
+            let mut bits_to_read = width;
+            while bits_to_read > bits_left {
+                x = (x << bits_left) | (current & ((1 << bits_left) - 1));
+                bits_to_read -= bits_left;
+                current = source.read_u8()? as u64;
+                bits_left = 8;
+            }
+            if bits_to_read > 0 {
+                bits_left -= bits_to_read;
+                x = (x << bits_to_read) | ((current >> bits_left) & ((1 << bits_to_read) - 1));
+            }
+            out.push(x as i64);
+        }
+        Ok(())
+    }
+
+    fn read_run(&mut self) -> Result<()> {
+        self.buf.clear();
+        self.used = 0;
+        let header = self.source.read_u8()?;
+        match header >> 6 {
+            SHORT_REPEAT => self.read_short_repeat(header),
+            DIRECT => self.read_direct(header),
+            PATCHED_BASE => self.read_patched_base(header),
+            DELTA => self.read_delta(header),
+            _ => unreachable!(),
+        }
+    }
+
+    fn read_short_repeat(&mut self, header: u8) -> Result<()> {
+        let width = ((header >> 3) & 0x07) as usize + 1;
+        let len = (header & 0x07) as usize + MIN_REPEAT_SIZE;
+        let x = self.read_big_endian(width)?;
+        let x = if self.signed { zigzag_decode_i64(x) } else { x as i64 };
+        self.buf.resize(len, x);
+        Ok(())
+    }
+
+    fn read_direct(&mut self, header: u8) -> Result<()> {
+        let width = decode_bit_width((header >> 1) & 0x1f);
+        let len = (((header & 1) as usize) << 8 | self.source.read_u8()? as usize) + 1;
+        Self::read_packed(&mut self.source, &mut self.buf, len, width)?;
+        if self.signed {
+            for x in self.buf.iter_mut() {
+                *x = zigzag_decode_i64(*x as u64);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_patched_base(&mut self, header: u8) -> Result<()> {
+        let width = decode_bit_width((header >> 1) & 0x1f);
+        let len = (((header & 1) as usize) << 8 | self.source.read_u8()? as usize) + 1;
+        let third = self.source.read_u8()?;
+        let base_width = ((third >> 5) & 0x07) as usize + 1;
+        let patch_width = decode_bit_width(third & 0x1f);
+        let fourth = self.source.read_u8()?;
+        let patch_gap_width = ((fourth >> 5) & 0x07) as u32 + 1;
+        let patch_list_len = (fourth & 0x1f) as usize;
+
+        // The base is stored in sign-magnitude form, with the sign in the most significant bit.
+        let base = self.read_big_endian(base_width)?;
+        let sign_mask = 1u64 << (base_width * 8 - 1);
+        let base = if base & sign_mask != 0 {
+            -((base & !sign_mask) as i64)
+        } else {
+            base as i64
+        };
+
+        Self::read_packed(&mut self.source, &mut self.buf, len, width)?;
+
+        if patch_width + patch_gap_width > 64 {
+            return Err(invalid_data("patch and gap widths of a patched base run exceed 64 bits"));
+        }
+        self.patches.clear();
+        Self::read_packed(&mut self.source, &mut self.patches, patch_list_len, closest_fixed_bits(patch_width + patch_gap_width))?;
+
+        // Each patch list entry holds the gap from the previous patched value followed by the patch
+        // itself. Gaps larger than 255 are split, using entries with a gap of 255 and no patch.
+        let patch_mask = (1u64 << patch_width) - 1;
+        let mut i = 0;
+        for &entry in &self.patches {
+            let gap = (entry as u64 >> patch_width) as usize;
+            let patch = entry as u64 & patch_mask;
+            i += gap;
+            if gap == 255 && patch == 0 {
+                continue;
+            }
+            if i >= len {
+                return Err(invalid_data("patch of a patched base run is out of bounds"));
+            }
+            self.buf[i] |= patch.checked_shl(width).unwrap_or(0) as i64;
+        }
+        for x in self.buf.iter_mut() {
+            *x = base.wrapping_add(*x);
+        }
+        Ok(())
+    }
+
+    fn read_delta(&mut self, header: u8) -> Result<()> {
+        let width_code = (header >> 1) & 0x1f;
+        // Number of values after the first one
+        let len = ((header & 1) as usize) << 8 | self.source.read_u8()? as usize;
+        let first = if self.signed {
+            self.read_signed_varint()?
+        } else {
+            read_varint_u64(&mut self.source)? as i64
+        };
+        self.buf.push(first);
+
+        let delta_base = self.read_signed_varint()?;
+        if width_code == 0 {
+            // Fixed delta
+            let mut x = first;
+            for _ in 0..len {
+                x = x.wrapping_add(delta_base);
+                self.buf.push(x);
+            }
+        } else {
+            let width = decode_bit_width(width_code);
+            if len == 0 {
+                return Err(invalid_data("delta run with a delta width has a single value"));
+            }
+            let mut x = first.wrapping_add(delta_base);
+            self.buf.push(x);
+            Self::read_packed(&mut self.source, &mut self.buf, len - 1, width)?;
+            // The remaining deltas are magnitudes, whose sign is given by the delta base.
+            for y in self.buf[2..].iter_mut() {
+                x = if delta_base < 0 { x.wrapping_sub(*y) } else { x.wrapping_add(*y) };
+                *y = x;
+            }
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn next(&mut self) -> Result<i64> {
+        if self.used == self.buf.len() {
+            self.read_run()?;
+        }
+        let x = self.buf[self.used];
+        self.used += 1;
+        Ok(x)
+    }
+
+    pub fn read_batch(&mut self, out: &mut [i64]) -> Result<()> {
+        let mut i = 0;
+        while i < out.len() {
+            if self.used == self.buf.len() {
+                self.read_run()?;
+            }
+            let n = (self.buf.len() - self.used).min(out.len() - i);
+            out[i..i + n].copy_from_slice(&self.buf[self.used..self.used + n]);
+            self.used += n;
+            i += n;
+        }
+        Ok(())
+    }
+
+    pub fn skip(&mut self, mut num_values: u64) -> Result<()> {
+        while num_values > 0 {
+            if self.used == self.buf.len() {
+                self.read_run()?;
+            }
+            let n = ((self.buf.len() - self.used) as u64).min(num_values);
+            self.used += n as usize;
+            num_values -= n;
+        }
+        Ok(())
+    }
+
+    /// Seeks to a position made of a compression stream position followed by the number of values
+    /// to skip in the run that starts there.
+    pub fn seek(&mut self, positions: &mut PositionProvider) -> Result<()> {
+        self.source.seek(positions)?;
+        self.buf.clear();
+        self.used = 0;
+        let rle_offset = positions.next()?;
+        self.skip(rle_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protos::orc_proto;
+    use crate::reader::compression::Compression;
+
+    fn decoder(input: Vec<u8>, signed: bool) -> IntRLEv2Decoder {
+        let compression = Compression::new(orc_proto::CompressionKind::NONE, 0).unwrap();
+        IntRLEv2Decoder::new(DecompressionStream::new(input, &compression), signed)
+    }
+
+    fn decode(input: Vec<u8>, signed: bool, len: usize) -> Vec<i64> {
+        let mut decoder = decoder(input, signed);
+        let mut out = vec![0; len];
+        decoder.read_batch(&mut out).unwrap();
+        assert!(decoder.next().is_err());
+        out
+    }
+
+    // Examples from the ORC specification
+    #[test]
+    fn test_short_repeat() {
+        assert_eq!(decode(vec![0x0a, 0x27, 0x10], false, 5), vec![10000; 5]);
+        assert_eq!(decode(vec![0x0a, 0x4e, 0x1f], true, 5), vec![-10000; 5]);
+    }
+
+    #[test]
+    fn test_direct() {
+        let input = vec![0x5e, 0x03, 0x5c, 0xa1, 0xab, 0x1e, 0xde, 0xad, 0xbe, 0xef];
+        assert_eq!(decode(input, false, 4), vec![23713, 43806, 57005, 48879]);
+        // Zigzag encoded with a width of 2 bits
+        assert_eq!(decode(vec![0x42, 0x03, 0b00011011], true, 4), vec![0, -1, 1, -2]);
+    }
+
+    #[test]
+    fn test_patched_base() {
+        let input = vec![
+            0x8e, 0x13, 0x2b, 0x21, 0x07, 0xd0, 0x1e, 0x00, 0x14, 0x70, 0x28, 0x32, 0x3c, 0x46, 0x50,
+            0x5a, 0x64, 0x6e, 0x78, 0x82, 0x8c, 0x96, 0xa0, 0xaa, 0xb4, 0xbe, 0xfc, 0xe8,
+        ];
+        let expected = vec![
+            2030, 2000, 2020, 1000000, 2040, 2050, 2060, 2070, 2080, 2090, 2100, 2110, 2120, 2130,
+            2140, 2150, 2160, 2170, 2180, 2190,
+        ];
+        assert_eq!(decode(input, false, 20), expected);
+    }
+
+    #[test]
+    fn test_patched_base_long_gap() {
+        // 300 values of width 1 over a base of -5, with a patch at index 270. The gap is stored as
+        // an entry with a gap of 255 and no patch, followed by an entry with a gap of 15.
+        // Header: width 1, 300 values, base width of 1 byte, patch width 1, gap width 8 and
+        // 2 patch entries. The base of -5 is stored in sign-magnitude form.
+        let mut input = vec![0x81, 0x2b, 0x00, 0xe2, 0x85];
+        input.extend(vec![0; 38]);
+        // Entries are packed in 9 bits (gap width plus patch width)
+        let entries: [u64; 2] = [255 << 1, 15 << 1 | 1];
+        let packed = (entries[0] << 9 | entries[1]) << 6;
+        input.extend(&[(packed >> 16) as u8, (packed >> 8) as u8, packed as u8]);
+
+        let mut expected = vec![-5; 300];
+        expected[270] = -5 + 2;
+        assert_eq!(decode(input, false, 300), expected);
+    }
+
+    #[test]
+    fn test_delta() {
+        let input = vec![0xc6, 0x09, 0x02, 0x02, 0x22, 0x42, 0x42, 0x46];
+        assert_eq!(decode(input, false, 10), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        // Fixed deltas
+        assert_eq!(decode(vec![0xc0, 0x03, 20, 3], true, 4), vec![10, 8, 6, 4]);
+        assert_eq!(decode(vec![0xc0, 0x04, 7, 0], false, 5), vec![7; 5]);
+        // Decreasing sequence with a negative delta base
+        assert_eq!(decode(vec![0xc2, 0x03, 100, 19, 0b01_10_11_00], true, 4), vec![50, 40, 39, 37]);
+    }
+
+    #[test]
+    fn test_skip_and_seek() {
+        let mut input = vec![0x0a, 0x27, 0x10];
+        input.extend(&[0x5e, 0x03, 0x5c, 0xa1, 0xab, 0x1e, 0xde, 0xad, 0xbe, 0xef]);
+        input.extend(&[0xc6, 0x09, 0x02, 0x02, 0x22, 0x42, 0x42, 0x46]);
+        let mut decoder = decoder(input, false);
+        decoder.skip(7).unwrap();
+        assert_eq!(decoder.next().unwrap(), 57005);
+        decoder.skip(4).unwrap();
+        assert_eq!(decoder.next().unwrap(), 7);
+
+        decoder.seek(&mut PositionProvider::new(&[3, 1])).unwrap();
+        assert_eq!(decoder.next().unwrap(), 43806);
+        decoder.seek(&mut PositionProvider::new(&[13, 9])).unwrap();
+        assert_eq!(decoder.next().unwrap(), 29);
+        decoder.seek(&mut PositionProvider::new(&[0, 4])).unwrap();
+        let mut out = vec![0; 3];
+        decoder.read_batch(&mut out).unwrap();
+        assert_eq!(out, vec![10000, 23713, 43806]);
+    }
+
+    #[test]
+    fn test_bit_widths() {
+        assert_eq!((0..32).map(decode_bit_width).collect::<Vec<_>>(), vec![
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+            26, 28, 30, 32, 40, 48, 56, 64,
+        ]);
+        assert_eq!(closest_fixed_bits(0), 1);
+        assert_eq!(closest_fixed_bits(25), 26);
+        assert_eq!(closest_fixed_bits(33), 40);
+        assert_eq!(closest_fixed_bits(57), 64);
+    }
+}
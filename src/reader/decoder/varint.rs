@@ -0,0 +1,94 @@
+use std::io::{Result, Error, ErrorKind};
+use crate::reader::compression::DecompressionStream;
+
+
+#[inline(always)]
+pub fn read_varint_u64(source: &mut DecompressionStream) -> Result<u64> {
+    let mut result: u64 = 0;
+    for i in 0..10 {
+        let b = source.read_u8()?;
+        result |= ((b & 0x7f) as u64) << (7 * i);
+        if b < 0x80 {
+            return Ok(result);
+        }
+    }
+    Err(Error::new(ErrorKind::InvalidData, "varint is too long for a 64-bit integer"))
+}
+
+#[inline(always)]
+pub fn zigzag_decode_i64(x: u64) -> i64 {
+    ((x >> 1) as i64) ^ -((x & 1) as i64)
+}
+
+pub fn read_varint_u128(source: &mut DecompressionStream) -> Result<u128> {
+    let mut result: u128 = 0;
+    for i in 0..19 {
+        let b = source.read_u8()?;
+        result |= ((b & 0x7f) as u128) << (7 * i);
+        if b < 0x80 {
+            return Ok(result);
+        }
+    }
+    Err(Error::new(ErrorKind::InvalidData, "varint is too long for a 128-bit integer"))
+}
+
+#[inline(always)]
+pub fn zigzag_decode_i128(x: u128) -> i128 {
+    ((x >> 1) as i128) ^ -((x & 1) as i128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protos::orc_proto;
+    use crate::reader::compression::Compression;
+
+    #[test]
+    fn test_read_varint_u64() {
+        let cases: Vec<(Vec<u8>, u64)> = vec![
+            (vec![0x00], 0),
+            (vec![0x7f], 127),
+            (vec![0x80, 0x01], 128),
+            (vec![0xff, 0x7f], 16383),
+            (vec![0x81, 0x80, 0x01], 16385),
+            (vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01], u64::MAX),
+        ];
+        let compression = Compression::new(orc_proto::CompressionKind::NONE, 0).unwrap();
+        for (input, expected_output) in cases {
+            let mut stream = DecompressionStream::new(input, &compression);
+            assert_eq!(read_varint_u64(&mut stream).unwrap(), expected_output);
+        }
+        let mut stream = DecompressionStream::new(vec![0x80; 11], &compression);
+        assert!(read_varint_u64(&mut stream).is_err());
+    }
+
+    #[test]
+    fn test_zigzag_decode_i64() {
+        let cases: Vec<(u64, i64)> = vec![(0, 0), (1, -1), (2, 1), (3, -2), (4, 2), (u64::MAX, i64::MIN)];
+        for (input, expected_output) in cases {
+            assert_eq!(zigzag_decode_i64(input), expected_output);
+        }
+    }
+
+    #[test]
+    fn test_read_varint_i128() {
+        use crate::writer::compression::{CompressionStream, NoCompression};
+        use crate::writer::encoder::VarInt;
+
+        let values: Vec<i128> = vec![0, 1, -1, 63, -64, 1 << 70, -(1 << 100) + 7, i128::MAX, i128::MIN];
+        let mut out = CompressionStream::new(&NoCompression::new().build());
+        for &x in &values {
+            x.write_varint(&mut out);
+        }
+        let mut bytes: Vec<u8> = Vec::new();
+        out.finish(&mut bytes).unwrap();
+
+        let compression = Compression::new(orc_proto::CompressionKind::NONE, 0).unwrap();
+        let mut stream = DecompressionStream::new(bytes, &compression);
+        for &x in &values {
+            assert_eq!(zigzag_decode_i128(read_varint_u128(&mut stream).unwrap()), x);
+        }
+        let mut stream = DecompressionStream::new(vec![0x80; 20], &compression);
+        assert!(read_varint_u128(&mut stream).is_err());
+    }
+}
@@ -1,16 +1,266 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, SeekFrom, Result, Error, ErrorKind};
+use protobuf::Message;
+
+use crate::protos::orc_proto;
 use crate::schema::Schema;
-use crate::reader::compression::Compression;
+use super::compression::{Compression, DecompressionStream};
+use super::data::common::StripeStreams;
+use super::row_reader::{RowReader, RowReaderOptions};
 
-pub struct Compression {
+/// Location of a stripe within the file, as recorded in the file footer.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StripeInformation {
+    pub offset: u64,
+    pub index_length: u64,
+    pub data_length: u64,
+    pub footer_length: u64,
+    pub num_rows: u64,
+}
 
+impl StripeInformation {
+    fn from_proto(stripe: &orc_proto::StripeInformation) -> Self {
+        StripeInformation {
+            offset: stripe.get_offset(),
+            index_length: stripe.get_indexLength(),
+            data_length: stripe.get_dataLength(),
+            footer_length: stripe.get_footerLength(),
+            num_rows: stripe.get_numberOfRows(),
+        }
+    }
 }
 
-pub struct Splice {
+/// An ORC file opened for reading. Opening the file reads and decodes the file tail (postscript,
+/// footer and metadata); stripes are only read on demand.
+pub struct OrcFile<R: Read + Seek> {
+    inner: R,
+    postscript: orc_proto::PostScript,
+    footer: orc_proto::Footer,
+    metadata: orc_proto::Metadata,
+    stripes: Vec<StripeInformation>,
+    compression: Compression,
+    schema: Schema,
+    user_metadata: BTreeMap<String, Vec<u8>>,
+}
+
+fn invalid_data(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
+
+impl<R: Read + Seek> OrcFile<R> {
+    const MAGIC: &'static str = "ORC";
+
+    pub fn open(mut inner: R) -> Result<Self> {
+        let file_len = inner.seek(SeekFrom::End(0))?;
+        if file_len < (Self::MAGIC.len() + 1) as u64 {
+            return Err(invalid_data(format!("file is too short ({} bytes) to be an ORC file", file_len)));
+        }
+
+        let mut postscript_len = 0u8;
+        inner.seek(SeekFrom::End(-1))?;
+        inner.read_exact(std::slice::from_mut(&mut postscript_len))?;
+        let postscript_start = (file_len - 1).checked_sub(postscript_len as u64)
+            .ok_or_else(|| invalid_data(format!("postscript length ({}) exceeds file length", postscript_len)))?;
+        let postscript_bytes = Self::read_range(&mut inner, postscript_start, postscript_len as u64)?;
+        let postscript = orc_proto::PostScript::parse_from_bytes(&postscript_bytes)?;
+        if postscript.get_magic() != Self::MAGIC {
+            return Err(invalid_data(format!("invalid postscript magic {:?}", postscript.get_magic())));
+        }
+
+        let compression = Compression::new(postscript.get_compression(), postscript.get_compressionBlockSize())?;
+
+        let footer_start = postscript_start.checked_sub(postscript.get_footerLength())
+            .ok_or_else(|| invalid_data("footer length exceeds file length".to_owned()))?;
+        let metadata_start = footer_start.checked_sub(postscript.get_metadataLength())
+            .ok_or_else(|| invalid_data("metadata length exceeds file length".to_owned()))?;
+
+        let footer_bytes = Self::read_range(&mut inner, footer_start, postscript.get_footerLength())?;
+        let footer: orc_proto::Footer = Self::parse_compressed(&compression, footer_bytes)?;
+        let metadata_bytes = Self::read_range(&mut inner, metadata_start, postscript.get_metadataLength())?;
+        let metadata: orc_proto::Metadata = Self::parse_compressed(&compression, metadata_bytes)?;
+
+        let stripes = footer.get_stripes().iter().map(StripeInformation::from_proto).collect();
+        let schema = Schema::from_proto_types(footer.get_types())?;
+        let user_metadata = footer.get_metadata().iter()
+            .map(|item| (item.get_name().to_owned(), item.get_value().to_vec()))
+            .collect();
+        Ok(OrcFile {
+            inner,
+            postscript,
+            footer,
+            metadata,
+            stripes,
+            compression,
+            schema,
+            user_metadata,
+        })
+    }
+
+    fn read_range(inner: &mut R, start: u64, len: u64) -> Result<Vec<u8>> {
+        let mut buf = vec![0; len as usize];
+        inner.seek(SeekFrom::Start(start))?;
+        inner.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn parse_compressed<M: Message>(compression: &Compression, bytes: Vec<u8>) -> Result<M> {
+        let mut stream = DecompressionStream::new(bytes, compression);
+        Ok(M::parse_from_reader(&mut stream)?)
+    }
+
+    /// Total number of rows in the file.
+    pub fn num_rows(&self) -> u64 {
+        self.footer.get_numberOfRows()
+    }
+
+    pub fn stripes(&self) -> &[StripeInformation] {
+        &self.stripes
+    }
+
+    /// Number of rows between row index entries (0 if the file has no row index).
+    pub fn row_index_stride(&self) -> u32 {
+        self.footer.get_rowIndexStride()
+    }
 
+    pub fn compression_kind(&self) -> orc_proto::CompressionKind {
+        self.compression.kind()
+    }
+
+    pub fn compression_block_size(&self) -> u64 {
+        self.compression.block_size() as u64
+    }
+
+    /// The schema of the file, rebuilt from the types in the file footer.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// The user metadata entries of the file footer, by name.
+    pub fn user_metadata(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.user_metadata
+    }
+
+    pub fn postscript(&self) -> &orc_proto::PostScript {
+        &self.postscript
+    }
+
+    pub fn footer(&self) -> &orc_proto::Footer {
+        &self.footer
+    }
+
+    pub fn metadata(&self) -> &orc_proto::Metadata {
+        &self.metadata
+    }
+
+    /// Returns a reader over the rows of the file.
+    pub fn row_reader(&mut self) -> Result<RowReader<'_, R>> {
+        RowReader::new(self, RowReaderOptions::new())
+    }
+
+    /// Returns a reader over the rows of the file, configured by `options`. Fails if the search
+    /// argument of `options` does not match the schema of the file.
+    pub fn row_reader_with_options(&mut self, options: RowReaderOptions) -> Result<RowReader<'_, R>> {
+        RowReader::new(self, options)
+    }
+
+    /// Reads the stripe footer of stripe `index`, and the streams for which `include` returns true.
+    pub(crate) fn read_stripe<I>(&mut self, index: usize, include: I) -> Result<StripeStreams>
+        where I: Fn(u32, orc_proto::Stream_Kind) -> bool
+    {
+        let stripe = self.stripes[index];
+        let streams_len = stripe.index_length + stripe.data_length;
+        let footer_bytes = Self::read_range(&mut self.inner, stripe.offset + streams_len, stripe.footer_length)?;
+        let footer: orc_proto::StripeFooter = Self::parse_compressed(&self.compression, footer_bytes)?;
+        let inner = &mut self.inner;
+        StripeStreams::read(&footer, streams_len, &self.compression, self.footer.get_rowIndexStride(), include,
+            |start, len| Self::read_range(inner, stripe.offset + start, len))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
 }
 
-pub struct Metadata {
-    schema: Schema;
-    compression: Compression;
-    splices: Vec<Splice>;
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::schema::Schema;
+    use crate::writer::{Config, Version, Writer};
+    use crate::writer::compression::{SnappyCompression, ZstdCompression, ZlibCompression, Lz4Compression, LzoCompression};
+
+    fn write_longs(config: Config, num_rows: i64) -> Vec<u8> {
+        let mut writer = Writer::new(Vec::new(), &Schema::Long, config).unwrap();
+        for i in 0..num_rows {
+            writer.data().unwrap_long().write(i);
+        }
+        writer.write_batch(num_rows as u64).unwrap();
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_open() {
+        let bytes = write_longs(Config::new().with_row_index_stride(1000), 5000);
+        let file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        assert_eq!(file.num_rows(), 5000);
+        assert_eq!(file.row_index_stride(), 1000);
+        assert_eq!(file.compression_kind(), orc_proto::CompressionKind::NONE);
+        assert_eq!(file.stripes().len(), 1);
+        assert_eq!(file.stripes()[0].offset, 3);
+        assert_eq!(file.stripes()[0].num_rows, 5000);
+        assert_eq!(file.metadata().get_stripeStats().len(), 1);
+        assert_eq!(file.schema(), &Schema::Long);
+    }
+
+    #[test]
+    fn test_open_multiple_stripes() {
+        let mut writer = Writer::new(Vec::new(), &Schema::Long, Config::new().with_stripe_size(1000)).unwrap();
+        for n in 0..10 {
+            for i in 0..1000 {
+                writer.data().unwrap_long().write(n * 1000000 + i * i);
+            }
+            writer.write_batch(1000).unwrap();
+        }
+        let bytes = writer.finish().unwrap();
+        let file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        assert_eq!(file.num_rows(), 10000);
+        assert!(file.stripes().len() > 1);
+        assert_eq!(file.stripes().iter().map(|s| s.num_rows).sum::<u64>(), 10000);
+        for w in file.stripes().windows(2) {
+            assert_eq!(w[0].offset + w[0].index_length + w[0].data_length + w[0].footer_length, w[1].offset);
+        }
+    }
+
+    #[test]
+    fn test_open_compressed() {
+        for compression in vec![
+            SnappyCompression::new().build(),
+            ZstdCompression::new().with_block_size(4096).unwrap().build(),
+            ZlibCompression::new().with_compression_level(1).unwrap().build(),
+            Lz4Compression::new().with_block_size(10000).unwrap().build(),
+            LzoCompression::new().build(),
+        ] {
+            let kind = compression.kind();
+            let bytes = write_longs(Config::new().with_compression(compression), 100000);
+            let file = OrcFile::open(Cursor::new(bytes)).unwrap();
+            assert_eq!(file.compression_kind(), kind);
+            assert_eq!(file.num_rows(), 100000);
+            assert_eq!(file.row_index_stride(), 10000);
+            assert_eq!(file.footer().get_types().len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_open_version() {
+        let bytes = write_longs(Config::new(), 100);
+        assert_eq!(OrcFile::open(Cursor::new(bytes)).unwrap().postscript().get_version(), &[0, 12]);
+        let bytes = write_longs(Config::new().with_version(Version::V0_11), 100);
+        assert_eq!(OrcFile::open(Cursor::new(bytes)).unwrap().postscript().get_version(), &[0, 11]);
+    }
+
+    #[test]
+    fn test_open_invalid() {
+        assert!(OrcFile::open(Cursor::new(b"OR".to_vec())).is_err());
+        assert!(OrcFile::open(Cursor::new(b"ORC\x00\x00\x00\x05".to_vec())).is_err());
+    }
+}
@@ -0,0 +1,843 @@
+use std::collections::HashMap;
+use std::io::{Read, Seek, Result, Error, ErrorKind};
+use std::ops::Range;
+
+use crate::protos::orc_proto;
+use super::metadata::OrcFile;
+use super::data::Data;
+use super::data::common::{BaseData, StripeStreams};
+use super::search_argument::{Predicate, SearchArgument, TruthValue};
+
+
+/// Options of a `RowReader`.
+#[derive(Clone, Default)]
+pub struct RowReaderOptions {
+    columns: Option<Vec<String>>,
+    search_argument: Option<SearchArgument>,
+}
+
+impl RowReaderOptions {
+    pub fn new() -> Self {
+        RowReaderOptions { columns: None, search_argument: None }
+    }
+
+    /// Only reads the struct fields at the given dot-separated paths (such as `"user.address.zip"`),
+    /// along with the structs enclosing them. Other fields are left out of `RowReader::data`, and
+    /// their streams are not read from the file.
+    pub fn with_columns(mut self, columns: &[&str]) -> Self {
+        self.columns = Some(columns.iter().map(|&c| c.to_owned()).collect());
+        self
+    }
+
+    /// Skips the stripes and row groups whose statistics show that no row matches `search_argument`.
+    /// Rows are not filtered individually: the rows that are read may still not match.
+    pub fn with_search_argument(mut self, search_argument: SearchArgument) -> Self {
+        self.search_argument = Some(search_argument);
+        self
+    }
+}
+
+/// Reads the rows of an ORC file in batches, stripe by stripe.
+pub struct RowReader<'a, R: Read + Seek> {
+    file: &'a mut OrcFile<R>,
+    data: Data,
+    // Whether each column is read, indexed by column id
+    included: Vec<bool>,
+    predicate: Option<Predicate>,
+    // Whether the file statistics allow any row to match the predicate
+    file_matches: bool,
+    // Index of the next stripe to load
+    next_stripe: usize,
+    // The streams of the current stripe, kept to reposition the decoders within it
+    stripe: Option<StripeStreams>,
+    stripe_start: u64,
+    // The ranges of rows of the current stripe to read, and the row the decoders are at
+    selected_rows: Vec<Range<u64>>,
+    row_in_stripe: u64,
+    batch_start: u64,
+}
+
+impl<'a, R: Read + Seek> RowReader<'a, R> {
+    pub(crate) fn new(file: &'a mut OrcFile<R>, options: RowReaderOptions) -> Result<Self> {
+        let schema = file.schema();
+        let included = match &options.columns {
+            Some(columns) => {
+                let mut included = vec![false; schema.num_columns() as usize];
+                included[0] = true;
+                for path in columns {
+                    let resolved = schema.resolve_path(path).ok_or_else(|| Error::new(ErrorKind::InvalidInput,
+                        format!("no struct field at path {:?}", path)))?;
+                    let &(column_id, column_schema) = resolved.last().unwrap();
+                    for &(ancestor, _) in &resolved {
+                        included[ancestor as usize] = true;
+                    }
+                    for c in column_id..column_id + column_schema.num_columns() {
+                        included[c as usize] = true;
+                    }
+                }
+                included
+            }
+            None => vec![true; schema.num_columns() as usize],
+        };
+        let data = Data::new_projected(schema, &mut 0, &included);
+        let predicate = match &options.search_argument {
+            Some(search_argument) => Some(search_argument.bind(file.schema())?),
+            None => None,
+        };
+        let file_matches = predicate.as_ref().map_or(true, |p| {
+            let stats = file.footer().get_statistics();
+            p.evaluate(|c| stats.get(c as usize)) != TruthValue::False
+        });
+        Ok(RowReader {
+            file,
+            data,
+            included,
+            predicate,
+            file_matches,
+            next_stripe: 0,
+            stripe: None,
+            stripe_start: 0,
+            selected_rows: Vec::new(),
+            row_in_stripe: 0,
+            batch_start: 0,
+        })
+    }
+
+    /// Reads the next batch of at most `max_rows` rows into `data()` and returns the number of rows
+    /// read, which is 0 once all rows have been read. A batch never spans more than one stripe, nor
+    /// rows skipped by the search argument.
+    pub fn read_batch(&mut self, max_rows: u64) -> Result<u64> {
+        loop {
+            let row_in_stripe = self.row_in_stripe;
+            if let Some(range) = self.selected_rows.iter().find(|r| r.end > row_in_stripe).cloned() {
+                if row_in_stripe < range.start {
+                    let streams = self.stripe.as_ref().unwrap();
+                    self.data.load_stripe(streams)?;
+                    self.data.seek(streams, range.start as usize)?;
+                    self.row_in_stripe = range.start;
+                }
+                let num_rows = max_rows.min(range.end - self.row_in_stripe);
+                self.data.read_batch(num_rows as usize)?;
+                self.batch_start = self.stripe_start + self.row_in_stripe;
+                self.row_in_stripe += num_rows;
+                return Ok(num_rows);
+            }
+
+            match (self.next_stripe..self.file.stripes().len()).find(|&i| self.stripe_matches(i)) {
+                Some(i) => self.load_stripe(i)?,
+                None => {
+                    self.unload_stripe();
+                    return Ok(0);
+                }
+            }
+        }
+    }
+
+    /// Positions the reader so that the next batch starts at row `row` of the file (seeking to
+    /// `num_rows()` moves to the end), or at the next row selected by the search argument. Within
+    /// the stripe, primitive columns seek to the start of the enclosing row group using the row index
+    /// and decode only the remaining rows. Columns nested in lists, maps, unions or structs with
+    /// nulls decode from the start of the stripe.
+    pub fn seek_to_row(&mut self, row: u64) -> Result<()> {
+        let mut stripe_start = 0;
+        for i in 0..self.file.stripes().len() {
+            let stripe = self.file.stripes()[i];
+            if row < stripe_start + stripe.num_rows {
+                if !self.stripe_matches(i) {
+                    self.unload_stripe();
+                    self.next_stripe = i + 1;
+                    return Ok(());
+                }
+                self.load_stripe(i)?;
+                let row_in_stripe = row - stripe_start;
+                // Rows before a selected range are skipped by `read_batch`
+                if self.selected_rows.iter().any(|r| r.contains(&row_in_stripe)) {
+                    self.data.seek(self.stripe.as_ref().unwrap(), row_in_stripe as usize)?;
+                }
+                self.row_in_stripe = row_in_stripe;
+                return Ok(());
+            }
+            stripe_start += stripe.num_rows;
+        }
+        if row > stripe_start {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                format!("cannot seek to row {} of a file with {} rows", row, stripe_start)));
+        }
+        self.unload_stripe();
+        self.next_stripe = self.file.stripes().len();
+        Ok(())
+    }
+
+    /// The data of the most recent batch.
+    pub fn data(&self) -> &Data {
+        &self.data
+    }
+
+    /// The row of the file at which the most recent batch starts.
+    pub fn row_number(&self) -> u64 {
+        self.batch_start
+    }
+
+    fn load_stripe(&mut self, index: usize) -> Result<()> {
+        // The row indexes and bloom filters of the columns of the predicate are needed even if the
+        // columns are not read.
+        let included = &self.included;
+        let predicate = &self.predicate;
+        let streams = self.file.read_stripe(index, |column_id, kind| {
+            let is_index = kind == orc_proto::Stream_Kind::ROW_INDEX || kind == orc_proto::Stream_Kind::BLOOM_FILTER_UTF8;
+            included.get(column_id as usize) == Some(&true)
+                || (is_index && predicate.as_ref().map_or(false, |p| p.columns().any(|c| c == column_id)))
+        })?;
+        self.data.load_stripe(&streams)?;
+        self.selected_rows = self.select_row_groups(index, &streams)?;
+        self.stripe = Some(streams);
+        self.stripe_start = self.file.stripes()[..index].iter().map(|s| s.num_rows).sum();
+        self.row_in_stripe = 0;
+        self.next_stripe = index + 1;
+        Ok(())
+    }
+
+    fn unload_stripe(&mut self) {
+        self.stripe = None;
+        self.selected_rows.clear();
+        self.row_in_stripe = 0;
+    }
+
+    /// Whether the file and stripe statistics allow any row of stripe `index` to match the predicate.
+    fn stripe_matches(&self, index: usize) -> bool {
+        let predicate = match &self.predicate {
+            Some(predicate) => predicate,
+            None => return true,
+        };
+        let stats = match self.file.metadata().get_stripeStats().get(index) {
+            Some(stripe_stats) => stripe_stats.get_colStats(),
+            None => &[],
+        };
+        self.file_matches && predicate.evaluate(|c| stats.get(c as usize)) != TruthValue::False
+    }
+
+    /// Returns the ranges of rows of stripe `index` in the row groups whose statistics allow some
+    /// row to match the predicate.
+    fn select_row_groups(&self, index: usize, streams: &StripeStreams) -> Result<Vec<Range<u64>>> {
+        let num_rows = self.file.stripes()[index].num_rows;
+        let stride = self.file.row_index_stride() as u64;
+        let predicate = match &self.predicate {
+            Some(predicate) if stride > 0 => predicate,
+            _ => return Ok(std::iter::once(0..num_rows).collect()),
+        };
+
+        // The row groups of a column only line up with those of the stripe if the structs enclosing
+        // the column have no nulls.
+        let stripe_stats = match self.file.metadata().get_stripeStats().get(index) {
+            Some(stripe_stats) => stripe_stats.get_colStats(),
+            None => &[],
+        };
+        let mut row_indexes = HashMap::new();
+        let mut bloom_filters = HashMap::new();
+        for column_id in predicate.columns() {
+            let aligned = predicate.ancestors(column_id).iter().all(|&a| {
+                stripe_stats.get(a as usize).map_or(false, |s| s.has_hasNull() && !s.get_hasNull())
+            });
+            if aligned {
+                if let Some(row_index) = streams.row_index(column_id)? {
+                    row_indexes.insert(column_id, row_index);
+                }
+                if let Some(column_bloom_filters) = streams.bloom_filters(column_id)? {
+                    bloom_filters.insert(column_id, column_bloom_filters);
+                }
+            }
+        }
+
+        let mut selected_rows: Vec<Range<u64>> = Vec::new();
+        for group in 0..(num_rows + stride - 1) / stride {
+            let truth = predicate.evaluate_with_bloom_filters(|c| {
+                row_indexes.get(&c)
+                    .and_then(|row_index| row_index.get_entry().get(group as usize))
+                    .filter(|entry| entry.has_statistics())
+                    .map(|entry| entry.get_statistics())
+            }, |c| {
+                bloom_filters.get(&c).and_then(|b| b.get(group as usize)).and_then(|b| b.as_ref())
+            });
+            if truth == TruthValue::False {
+                continue;
+            }
+            let rows = group * stride..((group + 1) * stride).min(num_rows);
+            match selected_rows.last_mut() {
+                Some(last) if last.end == rows.start => last.end = rows.end,
+                _ => selected_rows.push(rows),
+            }
+        }
+        Ok(selected_rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use crate::schema::{Field, Schema};
+    use crate::reader::data::GenericData;
+    use crate::reader::search_argument::{SearchArgument, Value as Literal};
+    use crate::writer::{self, Config, OutOfRangePolicy, Version, Writer};
+    use crate::writer::compression::{SnappyCompression, ZstdCompression};
+    use crate::writer::data::GenericData as _;
+
+    #[derive(Debug, PartialEq, Clone)]
+    enum Value {
+        Null,
+        Boolean(bool),
+        Byte(i8),
+        Long(i64),
+        Float(f32),
+        Double(f64),
+        Timestamp(i64, u32),
+        Decimal(i128),
+        String(String),
+        Binary(Vec<u8>),
+        Struct(Vec<Value>),
+        List(Vec<Value>),
+        Map(Vec<(Value, Value)>),
+        Union(usize, Box<Value>),
+    }
+
+    fn random_value(schema: &Schema, rng: &mut StdRng) -> Value {
+        if rng.gen_range(0, 8) == 0 {
+            return Value::Null;
+        }
+        match schema {
+            Schema::Boolean => Value::Boolean(rng.gen()),
+            Schema::Byte => Value::Byte(rng.gen()),
+            Schema::Short => Value::Long(rng.gen::<i16>() as i64),
+            Schema::Int => Value::Long(rng.gen::<i32>() as i64),
+            Schema::Long => Value::Long(match rng.gen_range(0, 3) {
+                0 => rng.gen(),
+                1 => rng.gen_range(-5, 5),
+                _ => 1000,
+            }),
+            Schema::Date => Value::Long(rng.gen_range(-20000, 20000)),
+            Schema::Float => Value::Float(rng.gen_range(-1e6, 1e6)),
+            Schema::Double => Value::Double(rng.gen()),
+            Schema::Timestamp => {
+                let nanos = [0, 1, 1000, 120000, 999999999, 500000000][rng.gen_range(0, 6)];
+                Value::Timestamp(rng.gen_range(-1_000_000_000, 1_000_000_000), nanos)
+            }
+            Schema::Decimal(_, _) => Value::Decimal(rng.gen::<i64>() as i128 * rng.gen_range(-1000, 1000)),
+            Schema::String | Schema::VarChar(_) =>
+                Value::String(format!("str-{}-\u{e9}", rng.gen_range(0, 40))),
+            // Char values are padded with spaces when written
+            Schema::Char(n) => Value::String(format!("{:<1$}", format!("c-{}", rng.gen_range(0, 40)), *n as usize)),
+            Schema::Binary => Value::Binary((0..rng.gen_range(0, 10)).map(|_| rng.gen()).collect()),
+            Schema::Struct(fields) => Value::Struct(fields.iter().map(|f| random_value(&f.1, rng)).collect()),
+            Schema::List(child) => Value::List((0..rng.gen_range(0, 4)).map(|_| random_value(child, rng)).collect()),
+            Schema::Map(key, value) => Value::Map((0..rng.gen_range(0, 4))
+                .map(|_| (random_value(key, rng), random_value(value, rng))).collect()),
+            Schema::Union(children) => {
+                let tag = rng.gen_range(0, children.len());
+                Value::Union(tag, Box::new(random_value(&children[tag], rng)))
+            }
+        }
+    }
+
+    fn write_value(data: &mut writer::data::Data, value: &Value) {
+        match value {
+            Value::Null => data.write_null(),
+            Value::Boolean(x) => data.unwrap_boolean().write(*x),
+            Value::Byte(x) => data.unwrap_byte().write(*x),
+            Value::Long(x) => data.unwrap_long().write(*x),
+            Value::Float(x) => data.unwrap_float().write(*x),
+            Value::Double(x) => data.unwrap_double().write(*x),
+            Value::Timestamp(sec, nanos) => data.unwrap_timestamp().write_nanos(*sec, *nanos),
+            Value::Decimal(x) => data.unwrap_decimal().write_i128(*x),
+            Value::String(x) => data.unwrap_string().write(x),
+            Value::Binary(x) => data.unwrap_binary().write(x),
+            Value::Struct(fields) => {
+                let struct_data = data.unwrap_struct();
+                for (i, field) in fields.iter().enumerate() {
+                    write_value(struct_data.child(i), field);
+                }
+                struct_data.write();
+            }
+            Value::List(elements) => {
+                let list_data = data.unwrap_list();
+                for element in elements {
+                    write_value(list_data.child(), element);
+                }
+                list_data.write(elements.len() as u64);
+            }
+            Value::Map(entries) => {
+                let map_data = data.unwrap_map();
+                for (key, value) in entries {
+                    let (keys, values) = map_data.children();
+                    write_value(keys, key);
+                    write_value(values, value);
+                }
+                map_data.write(entries.len() as u64);
+            }
+            Value::Union(tag, value) => {
+                let union_data = data.unwrap_union();
+                write_value(union_data.child(*tag), value);
+                union_data.write(*tag);
+            }
+        }
+    }
+
+    /// Reconstructs the rows of the batch in `data`.
+    fn read_values(data: &Data) -> Vec<Value> {
+        let values: Vec<Value> = match data {
+            Data::Boolean(x) => x.values().iter().map(|&x| Value::Boolean(x)).collect(),
+            Data::Byte(x) => x.values().iter().map(|&x| Value::Byte(x)).collect(),
+            Data::Long(x) => x.values().iter().map(|&x| Value::Long(x)).collect(),
+            Data::Float(x) => x.values().iter().map(|&x| Value::Float(x)).collect(),
+            Data::Double(x) => x.values().iter().map(|&x| Value::Double(x)).collect(),
+            Data::Timestamp(x) => x.seconds().iter().zip(x.nanos())
+                .map(|(&sec, &nanos)| Value::Timestamp(sec, nanos)).collect(),
+            Data::Decimal(x) => x.values().iter().map(|&x| Value::Decimal(x)).collect(),
+            Data::String(x) => x.values().map(|x| Value::String(x.to_owned())).collect(),
+            Data::Binary(x) => x.values().map(|x| Value::Binary(x.to_vec())).collect(),
+            Data::Struct(x) => {
+                let children: Vec<Vec<Value>> = x.children().iter().map(read_values).collect();
+                let num_values = x.present().iter().filter(|&&p| p).count();
+                (0..num_values).map(|i| Value::Struct(children.iter().map(|c| c[i].clone()).collect())).collect()
+            }
+            Data::List(x) => {
+                let elements = read_values(x.child());
+                x.offsets().windows(2).map(|w| Value::List(elements[w[0]..w[1]].to_vec())).collect()
+            }
+            Data::Map(x) => {
+                let (keys, values) = x.children();
+                let entries: Vec<(Value, Value)> = read_values(keys).into_iter().zip(read_values(values)).collect();
+                x.offsets().windows(2).map(|w| Value::Map(entries[w[0]..w[1]].to_vec())).collect()
+            }
+            Data::Union(x) => {
+                let mut children: Vec<_> = x.children().iter().map(|c| read_values(c).into_iter()).collect();
+                x.tags().iter().map(|&tag| {
+                    Value::Union(tag as usize, Box::new(children[tag as usize].next().unwrap()))
+                }).collect()
+            }
+        };
+        let mut values = values.into_iter();
+        data.present().iter().map(|&p| if p { values.next().unwrap() } else { Value::Null }).collect()
+    }
+
+    /// A random non-null value of a struct schema, as written at the root of a file.
+    fn random_row(schema: &Schema, rng: &mut StdRng) -> Value {
+        if let Schema::Struct(fields) = schema {
+            Value::Struct(fields.iter().map(|f| random_value(&f.1, rng)).collect())
+        } else { unreachable!() }
+    }
+
+    fn test_schema() -> Schema {
+        Schema::Struct(vec![
+            Field("boolean".to_owned(), Schema::Boolean),
+            Field("byte".to_owned(), Schema::Byte),
+            Field("short".to_owned(), Schema::Short),
+            Field("int".to_owned(), Schema::Int),
+            Field("long".to_owned(), Schema::Long),
+            Field("date".to_owned(), Schema::Date),
+            Field("float".to_owned(), Schema::Float),
+            Field("double".to_owned(), Schema::Double),
+            Field("timestamp".to_owned(), Schema::Timestamp),
+            Field("decimal".to_owned(), Schema::Decimal(38, 4)),
+            Field("string".to_owned(), Schema::String),
+            Field("char".to_owned(), Schema::Char(12)),
+            Field("varchar".to_owned(), Schema::VarChar(12)),
+            Field("binary".to_owned(), Schema::Binary),
+            Field("list".to_owned(), Schema::List(Box::new(Schema::Long))),
+            Field("map".to_owned(), Schema::Map(Box::new(Schema::String), Box::new(Schema::List(Box::new(Schema::Double))))),
+            Field("union".to_owned(), Schema::Union(vec![Schema::Int, Schema::String, Schema::Struct(vec![
+                Field("a".to_owned(), Schema::Boolean),
+            ])])),
+            Field("struct".to_owned(), Schema::Struct(vec![
+                Field("x".to_owned(), Schema::Long),
+                Field("y".to_owned(), Schema::Struct(vec![Field("z".to_owned(), Schema::String)])),
+            ])),
+        ])
+    }
+
+    fn write_rows(schema: &Schema, config: Config, rows: &[Value]) -> Vec<u8> {
+        let mut writer = Writer::new(Vec::new(), schema, config).unwrap();
+        for batch in rows.chunks(1000) {
+            for row in batch {
+                write_value(writer.data(), row);
+            }
+            writer.write_batch(batch.len() as u64).unwrap();
+        }
+        writer.finish().unwrap()
+    }
+
+    fn read_rows(bytes: Vec<u8>, batch_size: u64) -> Vec<Value> {
+        let mut file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        let mut reader = file.row_reader().unwrap();
+        let mut rows = Vec::new();
+        loop {
+            let num_rows = reader.read_batch(batch_size).unwrap();
+            if num_rows == 0 {
+                break;
+            }
+            assert_eq!(reader.data().num_rows() as u64, num_rows);
+            rows.extend(read_values(reader.data()));
+        }
+        rows
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let schema = test_schema();
+        let mut rng = StdRng::seed_from_u64(0);
+        let rows: Vec<Value> = (0..5000).map(|_| random_row(&schema, &mut rng)).collect();
+
+        let configs = vec![
+            Config::new(),
+            Config::new().with_version(Version::V0_12).with_row_index_stride(1000),
+            Config::new().with_compression(SnappyCompression::new().build()).with_stripe_size(50000),
+            Config::new().with_version(Version::V0_12).with_dictionary_key_size_threshold(0.0)
+                .with_compression(ZstdCompression::new().with_block_size(1000).unwrap().build()),
+            Config::new().with_row_index_stride(500).with_compression_threads(4)
+                .with_compression(ZstdCompression::new().with_block_size(1000).unwrap().build()),
+        ];
+        for config in configs {
+            let bytes = write_rows(&schema, config, &rows);
+            assert_eq!(read_rows(bytes.clone(), 1024), rows);
+            assert_eq!(read_rows(bytes, 7), rows);
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_multiple_stripes() {
+        let schema = Schema::Struct(vec![Field("x".to_owned(), Schema::Long), Field("s".to_owned(), Schema::String)]);
+        let mut rng = StdRng::seed_from_u64(1);
+        let rows: Vec<Value> = (0..20000).map(|_| random_row(&schema, &mut rng)).collect();
+        let bytes = write_rows(&schema, Config::new().with_stripe_size(10000), &rows);
+        assert!(OrcFile::open(Cursor::new(bytes.clone())).unwrap().stripes().len() > 1);
+        assert_eq!(read_rows(bytes, 3000), rows);
+    }
+
+    #[test]
+    fn test_seek_to_row() {
+        let schema = test_schema();
+        let mut rng = StdRng::seed_from_u64(2);
+        let rows: Vec<Value> = (0..12000).map(|_| random_row(&schema, &mut rng)).collect();
+        let configs = vec![
+            Config::new().with_row_index_stride(1000).with_stripe_size(100000)
+                .with_bloom_filter_columns(&["byte", "long", "double", "timestamp", "decimal", "string", "binary", "struct.x"]),
+            Config::new().with_version(Version::V0_12).with_row_index_stride(300)
+                .with_compression(ZstdCompression::new().with_block_size(2000).unwrap().build()),
+            Config::new().with_row_index_stride(0).with_compression(SnappyCompression::new().build()),
+        ];
+        for config in configs {
+            let bytes = write_rows(&schema, config, &rows);
+            let mut file = OrcFile::open(Cursor::new(bytes)).unwrap();
+            let mut reader = file.row_reader().unwrap();
+            for &row in &[11999, 0, 1000, 999, 6001, 300, 7777, 2345, 12000, 5] {
+                reader.seek_to_row(row).unwrap();
+                let mut values = Vec::new();
+                while values.len() < 50 {
+                    if reader.read_batch(17).unwrap() == 0 {
+                        break;
+                    }
+                    values.extend(read_values(reader.data()));
+                }
+                let end = (row as usize + 51).min(rows.len());
+                assert_eq!(values, &rows[row as usize..end]);
+            }
+            assert!(reader.seek_to_row(12001).is_err());
+        }
+    }
+
+    #[test]
+    fn test_seek_nested_columns() {
+        // Java and C++ writers place the row index entries of nested columns at rows of the stripe
+        // rather than their own rows. Replacing the row indexes of the child of a nullable struct
+        // and the elements of a list by that of a top-level column, whose entries are at rows of
+        // the stripe, checks that they are not used to seek those columns.
+        let schema = Schema::Struct(vec![
+            Field("a".to_owned(), Schema::Long),
+            Field("s".to_owned(), Schema::Struct(vec![Field("x".to_owned(), Schema::Long)])),
+            Field("l".to_owned(), Schema::List(Box::new(Schema::Long))),
+        ]);
+        let mut rng = StdRng::seed_from_u64(4);
+        let rows: Vec<Value> = (0..5000).map(|_| random_row(&schema, &mut rng)).collect();
+        let bytes = write_rows(&schema, Config::new().with_row_index_stride(500), &rows);
+        let mut file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        for &row in &[0, 499, 500, 1234, 4321, 4999] {
+            let mut streams = file.read_stripe(0, |_, _| true).unwrap();
+            streams.replace_row_index(3, 1);
+            streams.replace_row_index(5, 1);
+            let mut data = Data::new(&schema, &mut 0);
+            data.load_stripe(&streams).unwrap();
+            data.seek(&streams, row).unwrap();
+            let end = (row + 50).min(rows.len());
+            data.read_batch(end - row).unwrap();
+            assert_eq!(read_values(&data), &rows[row..end]);
+        }
+    }
+
+    #[test]
+    fn test_read_lz4_liblz4() {
+        // Compressed by liblz4 rather than by the writer, see testdata/make_lz4_liblz4.py
+        let bytes = include_bytes!("../../testdata/lz4_liblz4.orc");
+        let file = OrcFile::open(Cursor::new(&bytes[..])).unwrap();
+        assert_eq!(file.compression_kind(), orc_proto::CompressionKind::LZ4);
+        assert_eq!(file.compression_block_size(), 4096);
+        let expected: Vec<Value> = (0..3000).map(|i| Value::Struct(vec![
+            Value::Long((i * i) % 1000 - 500),
+            Value::String(format!("value-{:04}", i % 50)),
+        ])).collect();
+        assert_eq!(read_rows(bytes.to_vec(), 1000), expected);
+    }
+
+    #[test]
+    fn test_empty_file() {
+        let bytes = write_rows(&test_schema(), Config::new(), &[]);
+        assert_eq!(read_rows(bytes, 100), vec![]);
+    }
+
+    #[test]
+    fn test_out_of_range_policy() {
+        use crate::error::OrcError;
+        let schema = Schema::Struct(vec![
+            Field("short".to_owned(), Schema::Short),
+            Field("int".to_owned(), Schema::Int),
+            Field("decimal".to_owned(), Schema::Decimal(5, 2)),
+            Field("char".to_owned(), Schema::Char(3)),
+            Field("varchar".to_owned(), Schema::VarChar(3)),
+        ]);
+        let row = |short, int, decimal, char: &str, varchar: &str| Value::Struct(vec![
+            Value::Long(short), Value::Long(int), Value::Decimal(decimal),
+            Value::String(char.to_owned()), Value::String(varchar.to_owned()),
+        ]);
+        let in_range = row(-32768, 2147483647, -99999, "\u{e9}", "ab\u{e9}");
+        let out_of_range = row(32768, -2147483649, 100000, "abcd", "\u{e9}\u{e9}\u{e9}\u{e9}");
+        let rows = vec![in_range, out_of_range];
+
+        let bytes = write_rows(&schema, Config::new().with_out_of_range_policy(OutOfRangePolicy::Truncate), &rows);
+        assert_eq!(read_rows(bytes, 10), vec![
+            row(-32768, 2147483647, -99999, "\u{e9}  ", "ab\u{e9}"),
+            Value::Struct(vec![Value::Null, Value::Null, Value::Null,
+                Value::String("abc".to_owned()), Value::String("\u{e9}\u{e9}\u{e9}".to_owned())]),
+        ]);
+        let bytes = write_rows(&schema, Config::new().with_out_of_range_policy(OutOfRangePolicy::Null), &rows);
+        assert_eq!(read_rows(bytes, 10)[1], Value::Struct(vec![Value::Null; 5]));
+
+        for (column, expected_path) in (0..5).zip(&["short", "int", "decimal", "char", "varchar"]) {
+            let mut writer = Writer::new(Vec::new(), &schema, Config::new()).unwrap();
+            let data = writer.data().unwrap_struct();
+            for i in 0..5 {
+                let Value::Struct(values) = &rows[(i == column) as usize] else { unreachable!() };
+                write_value(data.child(i), &values[i]);
+            }
+            data.write();
+            match writer.write_batch(1) {
+                Err(OrcError::ValueOutOfRange { path, .. }) => assert_eq!(&path, expected_path),
+                r => panic!("unexpected result {:?}", r),
+            }
+        }
+    }
+
+    /// Reads the rows selected by `search_argument`, with their row numbers.
+    fn read_rows_matching(bytes: Vec<u8>, search_argument: SearchArgument) -> Vec<(u64, Value)> {
+        let mut file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        let options = RowReaderOptions::new().with_search_argument(search_argument);
+        let mut reader = file.row_reader_with_options(options).unwrap();
+        let mut rows = Vec::new();
+        while reader.read_batch(700).unwrap() > 0 {
+            let start = reader.row_number();
+            rows.extend(read_values(reader.data()).into_iter().enumerate().map(|(i, v)| (start + i as u64, v)));
+        }
+        rows
+    }
+
+    #[test]
+    fn test_search_argument() {
+        let schema = Schema::Struct(vec![
+            Field("x".to_owned(), Schema::Long),
+            Field("s".to_owned(), Schema::Struct(vec![Field("y".to_owned(), Schema::Long)])),
+        ]);
+        let rows: Vec<Value> = (0..20000).map(|i| Value::Struct(vec![
+            Value::Long(i),
+            Value::Struct(vec![if i % 3 == 0 { Value::Null } else { Value::Long(i / 10) }]),
+        ])).collect();
+        let config = Config::new().with_row_index_stride(1000).with_stripe_size(2000);
+        let bytes = write_rows(&schema, config, &rows);
+        assert!(OrcFile::open(Cursor::new(bytes.clone())).unwrap().stripes().len() > 2);
+
+        let x = || "x".to_owned();
+        let y = || "s.y".to_owned();
+        let cases = vec![
+            (SearchArgument::Between(x(), Literal::Long(2500), Literal::Long(3100)), 2000..4000),
+            (SearchArgument::Equals(y(), Literal::Long(1999)), 19000..20000),
+            (SearchArgument::And(vec![
+                SearchArgument::LessThan(x(), Literal::Long(5000)),
+                SearchArgument::Not(Box::new(SearchArgument::LessThan(x(), Literal::Long(3000)))),
+            ]), 3000..5000),
+            (SearchArgument::Or(vec![
+                SearchArgument::In(x(), vec![Literal::Long(10), Literal::Long(-4)]),
+                SearchArgument::Equals(x(), Literal::Long(19999)),
+            ]), 0..20000),
+            (SearchArgument::Equals(x(), Literal::Long(-1)), 0..0),
+            (SearchArgument::IsNull(x()), 0..0),
+            (SearchArgument::IsNull(y()), 0..20000),
+        ];
+        for (search_argument, expected_range) in cases {
+            let rows_read = read_rows_matching(bytes.clone(), search_argument.clone());
+            if let SearchArgument::Or(_) = search_argument {
+                // Only the first and last row groups can match
+                let row_numbers: Vec<u64> = rows_read.iter().map(|r| r.0).collect();
+                let expected: Vec<u64> = (0..1000).chain(19000..20000).collect();
+                assert_eq!(row_numbers, expected);
+            } else {
+                assert_eq!(rows_read.len() as u64, expected_range.end - expected_range.start, "{:?}", search_argument);
+                for (row_number, value) in rows_read {
+                    assert!(expected_range.contains(&row_number));
+                    assert_eq!(value, rows[row_number as usize]);
+                }
+            }
+        }
+
+        let mut file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        let options = RowReaderOptions::new().with_search_argument(
+            SearchArgument::Between(x(), Literal::Long(12345), Literal::Long(15000)));
+        let mut reader = file.row_reader_with_options(options).unwrap();
+        reader.seek_to_row(500).unwrap();
+        assert_eq!(reader.read_batch(10).unwrap(), 10);
+        assert_eq!(reader.row_number(), 12000);
+        reader.seek_to_row(14321).unwrap();
+        assert_eq!(reader.read_batch(10).unwrap(), 10);
+        assert_eq!(reader.row_number(), 14321);
+        assert_eq!(read_values(reader.data())[0], rows[14321]);
+        reader.seek_to_row(16000).unwrap();
+        assert_eq!(reader.read_batch(10).unwrap(), 0);
+
+        let options = RowReaderOptions::new().with_search_argument(SearchArgument::Equals("z".to_owned(), Literal::Long(1)));
+        assert!(file.row_reader_with_options(options).is_err());
+    }
+
+    #[test]
+    fn test_search_argument_partial_row_group() {
+        // The stripe ends with a partial row group, which its statistics must include
+        let schema = Schema::Struct(vec![Field("x".to_owned(), Schema::Long)]);
+        let rows: Vec<Value> = (0..15000).map(|i| Value::Struct(vec![Value::Long(i)])).collect();
+        let bytes = write_rows(&schema, Config::new(), &rows);
+        let file = OrcFile::open(Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(file.stripes().len(), 1);
+        assert_eq!(file.metadata().get_stripeStats()[0].get_colStats()[1].get_numberOfValues(), 15000);
+
+        let rows_read = read_rows_matching(bytes, SearchArgument::Equals("x".to_owned(), Literal::Long(14000)));
+        let expected: Vec<(u64, Value)> = (10000..15000).map(|i| (i, rows[i as usize].clone())).collect();
+        assert_eq!(rows_read, expected);
+    }
+
+    /// Counts the bytes read from `inner`.
+    struct CountingReader<R> {
+        inner: R,
+        bytes_read: std::rc::Rc<std::cell::Cell<usize>>,
+    }
+
+    impl<R: Read> Read for CountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.bytes_read.set(self.bytes_read.get() + n);
+            Ok(n)
+        }
+    }
+
+    impl<R: Seek> Seek for CountingReader<R> {
+        fn seek(&mut self, pos: std::io::SeekFrom) -> Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_projection() {
+        let schema = test_schema();
+        let mut rng = StdRng::seed_from_u64(3);
+        let rows: Vec<Value> = (0..10000).map(|_| random_row(&schema, &mut rng)).collect();
+        let config = Config::new().with_row_index_stride(1000).with_stripe_size(200000);
+        let bytes = write_rows(&schema, config, &rows);
+
+        let bytes_read = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut file = OrcFile::open(CountingReader { inner: Cursor::new(bytes.clone()), bytes_read: bytes_read.clone() })
+            .unwrap();
+        bytes_read.set(0);
+        let options = RowReaderOptions::new().with_columns(&["struct.y.z", "long", "map"]);
+        let mut reader = file.row_reader_with_options(options).unwrap();
+        let mut values = Vec::new();
+        while reader.read_batch(999).unwrap() > 0 {
+            if let Data::Struct(x) = reader.data() {
+                assert_eq!(x.field_names(), &["long", "map", "struct"]);
+                assert_eq!(x.child(2).unwrap_struct().field_names(), &["y"]);
+            } else { unreachable!() }
+            values.extend(read_values(reader.data()));
+        }
+        assert!(bytes_read.get() < bytes.len() / 2, "read {} of {} bytes", bytes_read.get(), bytes.len());
+
+        let expected: Vec<Value> = rows.iter().map(|row| {
+            if let Value::Struct(fields) = row {
+                let y = match &fields[17] {
+                    Value::Struct(x) => Value::Struct(vec![x[1].clone()]),
+                    Value::Null => Value::Null,
+                    _ => unreachable!(),
+                };
+                Value::Struct(vec![fields[4].clone(), fields[15].clone(), y])
+            } else { unreachable!() }
+        }).collect();
+        assert_eq!(values, expected);
+
+        // Seeking and predicates on columns that are not read
+        let options = RowReaderOptions::new().with_columns(&["string"])
+            .with_search_argument(SearchArgument::LessThan("long".to_owned(), Literal::Long(-1000)));
+        let mut reader = file.row_reader_with_options(options).unwrap();
+        reader.seek_to_row(4321).unwrap();
+        reader.read_batch(10).unwrap();
+        if let (Value::Struct(read), Value::Struct(row)) = (&read_values(reader.data())[0], &rows[4321]) {
+            assert_eq!(read, &vec![row[10].clone()]);
+        } else { unreachable!() }
+
+        let options = RowReaderOptions::new().with_columns(&["struct.w"]);
+        assert!(file.row_reader_with_options(options).is_err());
+    }
+
+    #[test]
+    fn test_search_argument_bloom_filters() {
+        let schema = Schema::Struct(vec![
+            Field("id".to_owned(), Schema::Long),
+            Field("name".to_owned(), Schema::String),
+            Field("time".to_owned(), Schema::Timestamp),
+            Field("amount".to_owned(), Schema::Decimal(10, 2)),
+        ]);
+        // Ids are spread so that the range of every row group covers almost all of them
+        let id = |i: i64| (i * 7919) % 20011;
+        let rows: Vec<Value> = (0..20000).map(|i| Value::Struct(vec![
+            Value::Long(id(i)),
+            Value::String(format!("name-{}", id(i))),
+            Value::Timestamp(id(i) * 3600, 500000000),
+            Value::Decimal(id(i) as i128 * 10),
+        ])).collect();
+        let config = Config::new().with_row_index_stride(1000);
+        let bytes_without_bloom_filters = write_rows(&schema, config.clone(), &rows);
+        let bytes = write_rows(&schema, config.with_bloom_filter_columns(&["id", "name", "time", "amount"]), &rows);
+
+        let lookup = Literal::Long(id(12345));
+        let rows_read = read_rows_matching(bytes_without_bloom_filters, SearchArgument::Equals("id".to_owned(), lookup));
+        assert_eq!(rows_read.len(), 20000);
+
+        let cases = vec![
+            (SearchArgument::Equals("id".to_owned(), Literal::Long(id(12345))), vec![12345]),
+            (SearchArgument::In("id".to_owned(), vec![Literal::Long(id(3456)), Literal::Long(id(17000))]), vec![3456, 17000]),
+            (SearchArgument::Equals("name".to_owned(), Literal::String(format!("name-{}", id(777)))), vec![777]),
+            (SearchArgument::Equals("time".to_owned(), Literal::Timestamp(id(9999) * 3600, 500000000)), vec![9999]),
+            (SearchArgument::Equals("amount".to_owned(), Literal::Decimal(id(5000) as i128, 1)), vec![5000]),
+            (SearchArgument::Equals("id".to_owned(), Literal::Long(20005)), vec![]),
+        ];
+        for (search_argument, expected_rows) in cases {
+            let rows_read = read_rows_matching(bytes.clone(), search_argument.clone());
+            // Allow for a few false positives of the bloom filters
+            assert!(rows_read.len() <= (expected_rows.len() + 2) * 1000, "{:?}: {} rows", search_argument, rows_read.len());
+            for row in expected_rows {
+                assert!(rows_read.iter().any(|r| r.0 == row), "{:?}: missing row {}", search_argument, row);
+            }
+        }
+    }
+}
@@ -0,0 +1,594 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::io::{Result, Error, ErrorKind};
+
+use crate::bloom_filter::BloomFilter;
+use crate::protos::orc_proto;
+use crate::reader::data::TimestampData;
+use crate::schema::Schema;
+
+
+/// Result of evaluating a search argument against the statistics of a set of rows.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TruthValue {
+    /// Every row matches.
+    True,
+    /// No row matches.
+    False,
+    /// Some rows may match.
+    Maybe,
+}
+
+impl TruthValue {
+    pub fn and(self, rhs: TruthValue) -> TruthValue {
+        match (self, rhs) {
+            (TruthValue::False, _) | (_, TruthValue::False) => TruthValue::False,
+            (TruthValue::True, TruthValue::True) => TruthValue::True,
+            _ => TruthValue::Maybe,
+        }
+    }
+
+    pub fn or(self, rhs: TruthValue) -> TruthValue {
+        match (self, rhs) {
+            (TruthValue::True, _) | (_, TruthValue::True) => TruthValue::True,
+            (TruthValue::False, TruthValue::False) => TruthValue::False,
+            _ => TruthValue::Maybe,
+        }
+    }
+
+}
+
+impl std::ops::Not for TruthValue {
+    type Output = TruthValue;
+
+    /// When no row matches a predicate, rows may still fail to match its negation because the
+    /// column is null, so the negation of `False` is `Maybe`.
+    fn not(self) -> TruthValue {
+        match self {
+            TruthValue::True => TruthValue::False,
+            _ => TruthValue::Maybe,
+        }
+    }
+}
+
+/// A literal to compare the values of a column with.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Boolean(bool),
+    /// Value of a Byte, Short, Int, Long or Date column.
+    Long(i64),
+    /// Value of a Float or Double column.
+    Double(f64),
+    /// Value of a String, Char or VarChar column.
+    String(String),
+    /// Unscaled value and scale of a Decimal column, e.g. `Decimal(1234, 2)` stands for 12.34.
+    Decimal(i128, u32),
+    /// Seconds relative to the ORC timestamp origin and nanoseconds, as passed to
+    /// `writer::data::TimestampData::write_nanos`.
+    Timestamp(i64, u32),
+}
+
+/// A predicate on the rows of a file, used to skip stripes and row groups whose statistics show
+/// that no row can match. Columns are given as dot-separated paths of struct field names. As in SQL,
+/// null values never satisfy a comparison.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SearchArgument {
+    Equals(String, Value),
+    LessThan(String, Value),
+    /// Matches values in the inclusive range between the two bounds.
+    Between(String, Value, Value),
+    In(String, Vec<Value>),
+    IsNull(String),
+    And(Vec<SearchArgument>),
+    Or(Vec<SearchArgument>),
+    Not(Box<SearchArgument>),
+}
+
+/// A search argument with its column paths resolved against the schema of a file.
+pub(crate) struct Predicate {
+    expr: Expr,
+    // The columns whose statistics are used, with the struct columns enclosing each of them
+    columns: BTreeMap<u32, Vec<u32>>,
+}
+
+enum Expr {
+    Leaf {
+        column_id: u32,
+        // Struct columns enclosing the column, whose nulls make the column null too
+        ancestors: Vec<u32>,
+        leaf: Leaf,
+    },
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+}
+
+enum Leaf {
+    IsNull,
+    Boolean(Op<bool>),
+    Long(Op<i64>),
+    Double(Op<f64>),
+    String(Op<String>),
+    // Values are scaled to the given scale
+    Decimal(Op<i128>, u32),
+    // Nanoseconds relative to the ORC timestamp origin
+    Timestamp(Op<i128>),
+}
+
+enum Op<T> {
+    Equals(T),
+    LessThan(T),
+    Between(T, T),
+    In(Vec<T>),
+}
+
+impl<T> Op<T> {
+    fn values(&self) -> Vec<&T> {
+        match self {
+            Op::Equals(x) | Op::LessThan(x) => vec![x],
+            Op::Between(x, y) => vec![x, y],
+            Op::In(xs) => xs.iter().collect(),
+        }
+    }
+
+    fn try_map<U, F: Fn(&T) -> Option<U>>(&self, f: F) -> Option<Op<U>> {
+        Some(match self {
+            Op::Equals(x) => Op::Equals(f(x)?),
+            Op::LessThan(x) => Op::LessThan(f(x)?),
+            Op::Between(x, y) => Op::Between(f(x)?, f(y)?),
+            Op::In(xs) => Op::In(xs.iter().map(f).collect::<Option<Vec<U>>>()?),
+        })
+    }
+}
+
+impl<T> Op<T> {
+    /// Whether no value can match, given whether the set of values may contain a value.
+    fn excluded_by<F: Fn(&T) -> bool>(&self, might_contain: F) -> bool {
+        match self {
+            Op::Equals(x) => !might_contain(x),
+            Op::In(xs) => !xs.iter().any(might_contain),
+            Op::LessThan(_) | Op::Between(_, _) => false,
+        }
+    }
+}
+
+impl<T: PartialOrd> Op<T> {
+    /// Evaluates the operation on a set of values whose non-null values lie between `min` and `max`.
+    fn evaluate(&self, min: &T, max: &T, has_null: bool) -> TruthValue {
+        let all = if has_null { TruthValue::Maybe } else { TruthValue::True };
+        let equals = |x: &T| {
+            if x < min || x > max {
+                TruthValue::False
+            } else if x == min && x == max {
+                all
+            } else {
+                TruthValue::Maybe
+            }
+        };
+        match self {
+            Op::Equals(x) => equals(x),
+            Op::LessThan(x) => {
+                if max < x { all } else if min >= x { TruthValue::False } else { TruthValue::Maybe }
+            }
+            Op::Between(x, y) => {
+                if max < x || min > y {
+                    TruthValue::False
+                } else if min >= x && max <= y {
+                    all
+                } else {
+                    TruthValue::Maybe
+                }
+            }
+            Op::In(xs) => xs.iter().fold(TruthValue::False, |acc, x| acc.or(equals(x))),
+        }
+    }
+}
+
+fn invalid_input(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, msg)
+}
+
+/// Converts an unscaled decimal value from scale `from` to the larger scale `to`.
+fn upscale(x: i128, from: u32, to: u32) -> Option<i128> {
+    10i128.checked_pow(to - from).and_then(|factor| x.checked_mul(factor))
+}
+
+/// Parses a decimal as written in `DecimalStatistics`, rounding it down (or up) to `scale` digits.
+fn parse_decimal(s: &str, scale: u32, round_up: bool) -> Option<i128> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (int_part, frac_part) = match digits.find('.') {
+        Some(i) => (&digits[..i], &digits[i + 1..]),
+        None => (digits, ""),
+    };
+    if int_part.is_empty() || !int_part.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let mut x: i128 = 0;
+    for (i, b) in int_part.bytes().chain(frac_part.bytes().chain(std::iter::repeat(b'0'))).enumerate() {
+        if i == int_part.len() + scale as usize {
+            break;
+        }
+        x = x.checked_mul(10)?.checked_add((b - b'0') as i128)?;
+    }
+    let truncated = frac_part.bytes().skip(scale as usize).any(|b| b != b'0');
+    if negative { x = -x; }
+    // Truncation rounds towards zero; step away from zero when that is the wrong direction.
+    if truncated && round_up != negative {
+        x = if round_up { x.checked_add(1)? } else { x.checked_sub(1)? };
+    }
+    Some(x)
+}
+
+impl SearchArgument {
+    /// Resolves the column paths against `schema` and checks the literals against the column types.
+    pub(crate) fn bind(&self, schema: &Schema) -> Result<Predicate> {
+        let expr = self.bind_expr(schema)?;
+        let mut columns = BTreeMap::new();
+        expr.collect_columns(&mut columns);
+        Ok(Predicate { expr, columns })
+    }
+
+    fn bind_expr(&self, schema: &Schema) -> Result<Expr> {
+        match self {
+            SearchArgument::And(args) => Ok(Expr::And(args.iter().map(|a| a.bind_expr(schema)).collect::<Result<_>>()?)),
+            SearchArgument::Or(args) => Ok(Expr::Or(args.iter().map(|a| a.bind_expr(schema)).collect::<Result<_>>()?)),
+            SearchArgument::Not(arg) => Ok(Expr::Not(Box::new(arg.bind_expr(schema)?))),
+            SearchArgument::IsNull(path) => Self::bind_leaf(schema, path, None),
+            SearchArgument::Equals(path, x) => Self::bind_leaf(schema, path, Some(Op::Equals(x))),
+            SearchArgument::LessThan(path, x) => Self::bind_leaf(schema, path, Some(Op::LessThan(x))),
+            SearchArgument::Between(path, x, y) => Self::bind_leaf(schema, path, Some(Op::Between(x, y))),
+            SearchArgument::In(path, xs) => Self::bind_leaf(schema, path, Some(Op::In(xs.iter().collect()))),
+        }
+    }
+
+    fn bind_leaf(schema: &Schema, path: &str, op: Option<Op<&Value>>) -> Result<Expr> {
+        let mut columns = schema.resolve_path(path)
+            .ok_or_else(|| invalid_input(format!("no struct field at path {:?}", path)))?;
+        let (column_id, column_schema) = columns.pop().unwrap();
+        let op = match op {
+            Some(op) => op,
+            None => return Ok(Expr::Leaf { column_id, ancestors: columns.iter().map(|c| c.0).collect(), leaf: Leaf::IsNull }),
+        };
+
+        let leaf = match column_schema {
+            Schema::Boolean => op.try_map(|x| if let Value::Boolean(x) = x { Some(*x) } else { None }).map(Leaf::Boolean),
+            Schema::Byte | Schema::Short | Schema::Int | Schema::Long | Schema::Date =>
+                op.try_map(|x| if let Value::Long(x) = x { Some(*x) } else { None }).map(Leaf::Long),
+            Schema::Float | Schema::Double => op.try_map(|x| match x {
+                Value::Double(x) => Some(*x),
+                Value::Long(x) => Some(*x as f64),
+                _ => None,
+            }).map(Leaf::Double),
+            Schema::String | Schema::Char(_) | Schema::VarChar(_) =>
+                op.try_map(|x| if let Value::String(x) = x { Some(x.clone()) } else { None }).map(Leaf::String),
+            Schema::Decimal(_, column_scale) => {
+                // Compare at a scale that represents both the column and the literals exactly.
+                let scale = op.values().iter().fold(*column_scale, |scale, x| match x {
+                    Value::Decimal(_, s) => scale.max(*s),
+                    _ => scale,
+                });
+                let decimal = op.try_map(|x| match x {
+                    Value::Decimal(x, s) => upscale(*x, *s, scale),
+                    Value::Long(x) => upscale(*x as i128, 0, scale),
+                    _ => None,
+                });
+                decimal.map(|op| Leaf::Decimal(op, scale))
+            }
+            // Compared as nanoseconds since the UNIX epoch, like the statistics
+            Schema::Timestamp => op.try_map(|x| match x {
+                Value::Timestamp(sec, nanos) =>
+                    Some((*sec - TimestampData::EPOCH_SECONDS) as i128 * 1_000_000_000 + *nanos as i128),
+                _ => None,
+            }).map(Leaf::Timestamp),
+            _ => return Err(invalid_input(format!("column {:?} does not support comparisons", path))),
+        };
+        let leaf = leaf.ok_or_else(|| invalid_input(format!("literals {:?} do not match the type of column {:?}",
+            op.values(), path)))?;
+        Ok(Expr::Leaf { column_id, ancestors: columns.iter().map(|c| c.0).collect(), leaf })
+    }
+}
+
+impl Expr {
+    fn collect_columns(&self, out: &mut BTreeMap<u32, Vec<u32>>) {
+        match self {
+            Expr::Leaf { column_id, ancestors, .. } => {
+                out.insert(*column_id, ancestors.clone());
+                for (i, &ancestor) in ancestors.iter().enumerate() {
+                    out.insert(ancestor, ancestors[..i].to_vec());
+                }
+            }
+            Expr::And(args) | Expr::Or(args) => args.iter().for_each(|a| a.collect_columns(out)),
+            Expr::Not(arg) => arg.collect_columns(out),
+        }
+    }
+
+    fn evaluate<'a, 'b, F, B>(&self, stats: &F, bloom_filters: &B) -> TruthValue
+        where F: Fn(u32) -> Option<&'a orc_proto::ColumnStatistics>,
+              B: Fn(u32) -> Option<&'b BloomFilter>
+    {
+        match self {
+            Expr::Leaf { column_id, ancestors, leaf } => {
+                if bloom_filters(*column_id).map_or(false, |b| leaf.excluded_by(b)) {
+                    return TruthValue::False;
+                }
+                let column_stats = match stats(*column_id) {
+                    Some(s) if s.has_numberOfValues() && s.has_hasNull() => s,
+                    _ => return TruthValue::Maybe,
+                };
+                let has_null = column_stats.get_hasNull() || ancestors.iter()
+                    .any(|&a| stats(a).map_or(true, |s| !s.has_hasNull() || s.get_hasNull()));
+                leaf.evaluate(column_stats, has_null)
+            }
+            Expr::And(args) => args.iter().fold(TruthValue::True, |acc, a| acc.and(a.evaluate(stats, bloom_filters))),
+            Expr::Or(args) => args.iter().fold(TruthValue::False, |acc, a| acc.or(a.evaluate(stats, bloom_filters))),
+            Expr::Not(arg) => !arg.evaluate(stats, bloom_filters),
+        }
+    }
+}
+
+impl Leaf {
+    /// Whether the bloom filter of the values shows that none of them matches.
+    fn excluded_by(&self, bloom_filter: &BloomFilter) -> bool {
+        match self {
+            Leaf::IsNull | Leaf::Boolean(_) => false,
+            Leaf::Long(op) => op.excluded_by(|&x| bloom_filter.might_contain_long(x)),
+            Leaf::Double(op) => op.excluded_by(|&x| bloom_filter.might_contain_double(x)),
+            Leaf::String(op) => op.excluded_by(|x| bloom_filter.might_contain_bytes(x.as_bytes())),
+            Leaf::Decimal(op, scale) => op.excluded_by(|&x| bloom_filter.might_contain_decimal(x, *scale)),
+            // Timestamps are hashed as milliseconds since the UNIX epoch
+            Leaf::Timestamp(op) => op.excluded_by(|&x| {
+                i64::try_from(x.div_euclid(1_000_000)).map_or(true, |millis| bloom_filter.might_contain_long(millis))
+            }),
+        }
+    }
+
+    fn evaluate(&self, stats: &orc_proto::ColumnStatistics, has_null: bool) -> TruthValue {
+        let num_values = stats.get_numberOfValues();
+        if let Leaf::IsNull = self {
+            return if !has_null {
+                TruthValue::False
+            } else if num_values == 0 {
+                TruthValue::True
+            } else {
+                TruthValue::Maybe
+            };
+        }
+        if num_values == 0 {
+            return TruthValue::False;
+        }
+
+        let result = match self {
+            Leaf::IsNull => unreachable!(),
+            Leaf::Boolean(op) => {
+                stats.bucketStatistics.as_ref().and_then(|s| s.get_count().first()).map(|&num_true| {
+                    op.evaluate(&(num_true == num_values), &(num_true > 0), has_null)
+                })
+            }
+            Leaf::Long(op) => {
+                // Date columns have date statistics, or integer statistics in files of older writers
+                let date_bounds = stats.dateStatistics.as_ref().filter(|s| s.has_minimum() && s.has_maximum())
+                    .map(|s| (s.get_minimum() as i64, s.get_maximum() as i64));
+                let int_bounds = || stats.intStatistics.as_ref().filter(|s| s.has_minimum() && s.has_maximum())
+                    .map(|s| (s.get_minimum(), s.get_maximum()));
+                date_bounds.or_else(int_bounds).map(|(min, max)| op.evaluate(&min, &max, has_null))
+            }
+            Leaf::Double(op) => {
+                stats.doubleStatistics.as_ref().filter(|s| s.has_minimum() && s.has_maximum())
+                    .filter(|s| !s.get_minimum().is_nan() && !s.get_maximum().is_nan())
+                    .map(|s| op.evaluate(&s.get_minimum(), &s.get_maximum(), has_null))
+            }
+            Leaf::String(op) => {
+                stats.stringStatistics.as_ref().filter(|s| s.has_minimum() && s.has_maximum())
+                    .map(|s| op.evaluate(&s.get_minimum().to_owned(), &s.get_maximum().to_owned(), has_null))
+            }
+            Leaf::Decimal(op, scale) => {
+                stats.decimalStatistics.as_ref().and_then(|s| {
+                    let min = parse_decimal(s.get_minimum(), *scale, false)?;
+                    let max = parse_decimal(s.get_maximum(), *scale, true)?;
+                    Some(op.evaluate(&min, &max, has_null))
+                })
+            }
+            Leaf::Timestamp(op) => {
+                // Statistics are milliseconds since the UNIX epoch in UTC, or, without the UTC
+                // bounds (before ORC-135), in the time zone of the writer, which is assumed to be
+                // UTC. They are rounded down to milliseconds, so widen them to cover all nanoseconds.
+                stats.timestampStatistics.as_ref().and_then(|s| {
+                    let (min, max) = if s.has_minimumUtc() && s.has_maximumUtc() {
+                        (s.get_minimumUtc(), s.get_maximumUtc())
+                    } else if s.has_minimum() && s.has_maximum() {
+                        (s.get_minimum(), s.get_maximum())
+                    } else {
+                        return None;
+                    };
+                    let min = min as i128 * 1_000_000;
+                    let max = max as i128 * 1_000_000 + 999_999;
+                    Some(op.evaluate(&min, &max, has_null))
+                })
+            }
+        };
+        result.unwrap_or(TruthValue::Maybe)
+    }
+}
+
+impl Predicate {
+    /// Evaluates the predicate given the statistics of each column (`None` if unknown).
+    pub fn evaluate<'a, F>(&self, stats: F) -> TruthValue
+        where F: Fn(u32) -> Option<&'a orc_proto::ColumnStatistics>
+    {
+        self.expr.evaluate(&stats, &|_| None)
+    }
+
+    /// Like `evaluate`, also testing equality predicates against the bloom filter of each column
+    /// (`None` if unknown).
+    pub fn evaluate_with_bloom_filters<'a, 'b, F, B>(&self, stats: F, bloom_filters: B) -> TruthValue
+        where F: Fn(u32) -> Option<&'a orc_proto::ColumnStatistics>,
+              B: Fn(u32) -> Option<&'b BloomFilter>
+    {
+        self.expr.evaluate(&stats, &bloom_filters)
+    }
+
+    /// The columns whose statistics are used, in increasing order.
+    pub fn columns(&self) -> impl Iterator<Item = u32> + '_ {
+        self.columns.keys().copied()
+    }
+
+    /// The struct columns enclosing `column_id`, outermost first.
+    pub fn ancestors(&self, column_id: u32) -> &[u32] {
+        self.columns.get(&column_id).map_or(&[], |a| a.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Field;
+
+    fn schema() -> Schema {
+        Schema::Struct(vec![
+            Field("x".to_owned(), Schema::Long),
+            Field("s".to_owned(), Schema::String),
+            Field("d".to_owned(), Schema::Decimal(10, 2)),
+            Field("b".to_owned(), Schema::Boolean),
+        ])
+    }
+
+    fn long_stats(min: i64, max: i64, num_values: u64, has_null: bool) -> orc_proto::ColumnStatistics {
+        let mut stats = orc_proto::ColumnStatistics::new();
+        stats.set_numberOfValues(num_values);
+        stats.set_hasNull(has_null);
+        let mut int_stats = orc_proto::IntegerStatistics::new();
+        int_stats.set_minimum(min);
+        int_stats.set_maximum(max);
+        stats.set_intStatistics(int_stats);
+        stats
+    }
+
+    fn evaluate(sarg: SearchArgument, column_stats: &[orc_proto::ColumnStatistics]) -> TruthValue {
+        sarg.bind(&schema()).unwrap().evaluate(|c| column_stats.get(c as usize))
+    }
+
+    #[test]
+    fn test_truth_value() {
+        use TruthValue::*;
+        assert_eq!(True.and(Maybe), Maybe);
+        assert_eq!(Maybe.and(False), False);
+        assert_eq!(False.or(Maybe), Maybe);
+        assert_eq!(Maybe.or(True), True);
+        assert_eq!(!True, False);
+        assert_eq!(!False, Maybe);
+        assert_eq!(!Maybe, Maybe);
+    }
+
+    #[test]
+    fn test_evaluate_long() {
+        let x = || "x".to_owned();
+        let stats = vec![long_stats(0, 0, 100, false), long_stats(10, 20, 100, false)];
+        assert_eq!(evaluate(SearchArgument::Equals(x(), Value::Long(9)), &stats), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::Equals(x(), Value::Long(10)), &stats), TruthValue::Maybe);
+        assert_eq!(evaluate(SearchArgument::LessThan(x(), Value::Long(10)), &stats), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::LessThan(x(), Value::Long(21)), &stats), TruthValue::True);
+        assert_eq!(evaluate(SearchArgument::Between(x(), Value::Long(21), Value::Long(30)), &stats), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::Between(x(), Value::Long(5), Value::Long(30)), &stats), TruthValue::True);
+        assert_eq!(evaluate(SearchArgument::Between(x(), Value::Long(15), Value::Long(30)), &stats), TruthValue::Maybe);
+        assert_eq!(evaluate(SearchArgument::In(x(), vec![Value::Long(1), Value::Long(25)]), &stats), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::In(x(), vec![Value::Long(1), Value::Long(12)]), &stats), TruthValue::Maybe);
+        assert_eq!(evaluate(SearchArgument::IsNull(x()), &stats), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::Not(Box::new(SearchArgument::IsNull(x()))), &stats), TruthValue::Maybe);
+        assert_eq!(evaluate(SearchArgument::Not(Box::new(SearchArgument::LessThan(x(), Value::Long(30)))), &stats),
+            TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::And(vec![
+            SearchArgument::LessThan(x(), Value::Long(30)),
+            SearchArgument::Equals(x(), Value::Long(40)),
+        ]), &stats), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::Or(vec![
+            SearchArgument::Equals(x(), Value::Long(40)),
+            SearchArgument::Equals(x(), Value::Long(15)),
+        ]), &stats), TruthValue::Maybe);
+
+        // With nulls, no comparison is true for every row
+        let stats = vec![long_stats(0, 0, 100, false), long_stats(10, 20, 90, true)];
+        assert_eq!(evaluate(SearchArgument::LessThan(x(), Value::Long(21)), &stats), TruthValue::Maybe);
+        assert_eq!(evaluate(SearchArgument::IsNull(x()), &stats), TruthValue::Maybe);
+        let stats = vec![long_stats(0, 0, 100, false), long_stats(0, 0, 0, true)];
+        assert_eq!(evaluate(SearchArgument::IsNull(x()), &stats), TruthValue::True);
+        assert_eq!(evaluate(SearchArgument::Equals(x(), Value::Long(0)), &stats), TruthValue::False);
+
+        // Unknown statistics
+        assert_eq!(evaluate(SearchArgument::Equals(x(), Value::Long(9)), &[]), TruthValue::Maybe);
+    }
+
+    #[test]
+    fn test_evaluate_other_types() {
+        let mut string_stats = orc_proto::ColumnStatistics::new();
+        string_stats.set_numberOfValues(10);
+        string_stats.set_hasNull(false);
+        string_stats.mut_stringStatistics().set_minimum("apple".to_owned());
+        string_stats.mut_stringStatistics().set_maximum("melon".to_owned());
+        let mut decimal_stats = orc_proto::ColumnStatistics::new();
+        decimal_stats.set_numberOfValues(10);
+        decimal_stats.set_hasNull(false);
+        decimal_stats.mut_decimalStatistics().set_minimum("-1.5".to_owned());
+        decimal_stats.mut_decimalStatistics().set_maximum("2.25".to_owned());
+        let mut bool_stats = orc_proto::ColumnStatistics::new();
+        bool_stats.set_numberOfValues(10);
+        bool_stats.set_hasNull(false);
+        bool_stats.mut_bucketStatistics().set_count(vec![10, 0]);
+        let stats = vec![long_stats(0, 0, 10, false), long_stats(0, 0, 10, false), string_stats, decimal_stats, bool_stats];
+
+        let s = || "s".to_owned();
+        assert_eq!(evaluate(SearchArgument::Equals(s(), Value::String("zebra".to_owned())), &stats), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::Equals(s(), Value::String("banana".to_owned())), &stats), TruthValue::Maybe);
+        let d = || "d".to_owned();
+        assert_eq!(evaluate(SearchArgument::LessThan(d(), Value::Decimal(-1500, 3)), &stats), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::LessThan(d(), Value::Decimal(-1499, 3)), &stats), TruthValue::Maybe);
+        assert_eq!(evaluate(SearchArgument::LessThan(d(), Value::Long(3)), &stats), TruthValue::True);
+        assert_eq!(evaluate(SearchArgument::Equals(d(), Value::Decimal(2251, 3)), &stats), TruthValue::False);
+        let b = || "b".to_owned();
+        assert_eq!(evaluate(SearchArgument::Equals(b(), Value::Boolean(false)), &stats), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::Equals(b(), Value::Boolean(true)), &stats), TruthValue::True);
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse_decimal("12.345", 3, false), Some(12345));
+        assert_eq!(parse_decimal("12.345", 5, false), Some(1234500));
+        assert_eq!(parse_decimal("12.345", 1, false), Some(123));
+        assert_eq!(parse_decimal("12.345", 1, true), Some(124));
+        assert_eq!(parse_decimal("-12.345", 1, false), Some(-124));
+        assert_eq!(parse_decimal("-12.345", 1, true), Some(-123));
+        assert_eq!(parse_decimal("-7", 0, true), Some(-7));
+        assert_eq!(parse_decimal("1.2.3", 2, true), None);
+        assert_eq!(parse_decimal("", 2, true), None);
+    }
+
+    #[test]
+    fn test_evaluate_bloom_filters() {
+        let mut bloom_filter = BloomFilter::new(100, 0.01);
+        (0..100).for_each(|i| bloom_filter.add_long(i * 2));
+        let stats = vec![long_stats(0, 0, 100, false), long_stats(0, 198, 100, false)];
+        let evaluate = |sarg: SearchArgument| {
+            sarg.bind(&schema()).unwrap()
+                .evaluate_with_bloom_filters(|c| stats.get(c as usize), |c| Some(&bloom_filter).filter(|_| c == 1))
+        };
+        let x = || "x".to_owned();
+        assert_eq!(evaluate(SearchArgument::Equals(x(), Value::Long(42))), TruthValue::Maybe);
+        assert_eq!(evaluate(SearchArgument::Equals(x(), Value::Long(43))), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::In(x(), vec![Value::Long(43), Value::Long(45)])), TruthValue::False);
+        assert_eq!(evaluate(SearchArgument::In(x(), vec![Value::Long(43), Value::Long(44)])), TruthValue::Maybe);
+        assert_eq!(evaluate(SearchArgument::Between(x(), Value::Long(43), Value::Long(43))), TruthValue::Maybe);
+        assert_eq!(evaluate(SearchArgument::Not(Box::new(SearchArgument::Equals(x(), Value::Long(43))))),
+            TruthValue::Maybe);
+    }
+
+    #[test]
+    fn test_bind_invalid() {
+        assert!(SearchArgument::Equals("y".to_owned(), Value::Long(1)).bind(&schema()).is_err());
+        assert!(SearchArgument::Equals("x".to_owned(), Value::String("1".to_owned())).bind(&schema()).is_err());
+        assert!(SearchArgument::In("s".to_owned(), vec![Value::String("1".to_owned()), Value::Long(1)])
+            .bind(&schema()).is_err());
+        assert!(SearchArgument::IsNull("x.y".to_owned()).bind(&schema()).is_err());
+    }
+}
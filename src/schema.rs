@@ -1,9 +1,14 @@
-#[derive(Clone)]
+use std::io::{Result, Error, ErrorKind};
+
+use crate::protos::orc_proto;
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Field(pub String, pub Schema);
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Schema {
     Boolean,
+    Byte,
     Short,
     Int,
     Long,
@@ -21,3 +26,270 @@ pub enum Schema {
     Map(Box<Schema>, Box<Schema>),
     Union(Vec<Schema>),
 }
+
+fn invalid_types(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidData, format!("invalid file types: {}", msg))
+}
+
+impl Schema {
+    // Defaults used by the Java implementation for types written without these attributes
+    const DEFAULT_PRECISION: u32 = 38;
+    const DEFAULT_SCALE: u32 = 10;
+    const DEFAULT_LENGTH: u32 = 256;
+
+    /// Rebuilds a schema from the flattened list of types in a file footer (the inverse of the
+    /// writer's type flattening). The types must be numbered in pre-order, with the root at index 0.
+    pub fn from_proto_types(types: &[orc_proto::Type]) -> Result<Schema> {
+        let mut column_id = 0;
+        let schema = Self::from_proto_type(types, &mut column_id)?;
+        if column_id as usize != types.len() {
+            return Err(invalid_types(format!("types {}..{} are not reachable from the root", column_id, types.len())));
+        }
+        Ok(schema)
+    }
+
+    /// Number of columns in the schema, including the root.
+    pub fn num_columns(&self) -> u32 {
+        1 + match self {
+            Schema::Struct(fields) => fields.iter().map(|f| f.1.num_columns()).sum(),
+            Schema::List(child) => child.num_columns(),
+            Schema::Map(key, value) => key.num_columns() + value.num_columns(),
+            Schema::Union(children) => children.iter().map(|c| c.num_columns()).sum(),
+            _ => 0,
+        }
+    }
+
+    /// The column id of the struct field at a dot-separated `path` of field names, such as
+    /// `"user.address.zip"`.
+    pub fn column_id(&self, path: &str) -> Option<u32> {
+        self.resolve_path(path).map(|columns| columns.last().unwrap().0)
+    }
+
+    /// The column ids and schemas along a dot-separated path of field names, starting with the root.
+    pub(crate) fn resolve_path(&self, path: &str) -> Option<Vec<(u32, &Schema)>> {
+        let mut columns = vec![(0, self)];
+        for name in path.split('.') {
+            let (id, schema) = *columns.last().unwrap();
+            let fields = if let Schema::Struct(fields) = schema { fields } else { return None };
+            let mut child_id = id + 1;
+            let mut child = None;
+            for field in fields {
+                if field.0 == name {
+                    child = Some((child_id, &field.1));
+                    break;
+                }
+                child_id += field.1.num_columns();
+            }
+            columns.push(child?);
+        }
+        Some(columns)
+    }
+
+    /// The path of each column, by column id (see `OrcError` for the format of the paths).
+    pub(crate) fn column_paths(&self) -> Vec<String> {
+        let mut paths = Vec::new();
+        self.push_column_paths(String::new(), &mut paths);
+        paths
+    }
+
+    fn push_column_paths(&self, path: String, out: &mut Vec<String>) {
+        let child_path = |name: &str| if path.is_empty() { name.to_owned() } else { format!("{}.{}", path, name) };
+        let children: Vec<(String, &Schema)> = match self {
+            Schema::Struct(fields) => fields.iter().map(|f| (child_path(&f.0), &f.1)).collect(),
+            Schema::List(child) => vec![(child_path("_elem"), child)],
+            Schema::Map(key, value) => vec![(child_path("_key"), key), (child_path("_value"), value)],
+            Schema::Union(children) => children.iter().enumerate().map(|(i, c)| (child_path(&i.to_string()), c)).collect(),
+            _ => Vec::new(),
+        };
+        out.push(path);
+        for (child_path, child) in children {
+            child.push_column_paths(child_path, out);
+        }
+    }
+
+    fn from_proto_type(types: &[orc_proto::Type], column_id: &mut u32) -> Result<Schema> {
+        let id = *column_id;
+        let t = types.get(id as usize).ok_or_else(|| invalid_types(format!("type {} is out of range", id)))?;
+        *column_id += 1;
+
+        let num_subtypes = t.get_subtypes().len();
+        let expect_subtypes = |n: usize| {
+            if num_subtypes == n { Ok(()) } else {
+                Err(invalid_types(format!("type {} ({:?}) has {} subtypes, expected {}", id, t.get_kind(), num_subtypes, n)))
+            }
+        };
+        // Requiring each subtype to be the next id in pre-order also rules out cycles.
+        let mut children = || -> Result<Vec<Schema>> {
+            let mut children = Vec::with_capacity(num_subtypes);
+            for &subtype in t.get_subtypes() {
+                if subtype != *column_id {
+                    return Err(invalid_types(format!("subtype {} of type {} is not in pre-order (expected {})",
+                        subtype, id, *column_id)));
+                }
+                children.push(Self::from_proto_type(types, column_id)?);
+            }
+            Ok(children)
+        };
+
+        let schema = match t.get_kind() {
+            orc_proto::Type_Kind::BOOLEAN => Schema::Boolean,
+            orc_proto::Type_Kind::BYTE => Schema::Byte,
+            orc_proto::Type_Kind::SHORT => Schema::Short,
+            orc_proto::Type_Kind::INT => Schema::Int,
+            orc_proto::Type_Kind::LONG => Schema::Long,
+            orc_proto::Type_Kind::DATE => Schema::Date,
+            orc_proto::Type_Kind::FLOAT => Schema::Float,
+            orc_proto::Type_Kind::DOUBLE => Schema::Double,
+            orc_proto::Type_Kind::TIMESTAMP => Schema::Timestamp,
+            orc_proto::Type_Kind::DECIMAL => {
+                let precision = if t.has_precision() { t.get_precision() } else { Self::DEFAULT_PRECISION };
+                let scale = if t.has_scale() { t.get_scale() } else { Self::DEFAULT_SCALE };
+                if precision == 0 || precision > 38 || scale > precision {
+                    return Err(invalid_types(format!("type {} has invalid decimal precision {} and scale {}",
+                        id, precision, scale)));
+                }
+                Schema::Decimal(precision, scale)
+            }
+            orc_proto::Type_Kind::STRING => Schema::String,
+            orc_proto::Type_Kind::BINARY => Schema::Binary,
+            orc_proto::Type_Kind::CHAR | orc_proto::Type_Kind::VARCHAR => {
+                let len = if t.has_maximumLength() { t.get_maximumLength() } else { Self::DEFAULT_LENGTH };
+                if t.get_kind() == orc_proto::Type_Kind::CHAR { Schema::Char(len) } else { Schema::VarChar(len) }
+            }
+            orc_proto::Type_Kind::STRUCT => {
+                if t.get_fieldNames().len() != num_subtypes {
+                    return Err(invalid_types(format!("type {} has {} field names for {} subtypes",
+                        id, t.get_fieldNames().len(), num_subtypes)));
+                }
+                let names = t.get_fieldNames().iter().cloned();
+                Schema::Struct(names.zip(children()?).map(|(name, schema)| Field(name, schema)).collect())
+            }
+            orc_proto::Type_Kind::LIST => {
+                expect_subtypes(1)?;
+                Schema::List(Box::new(children()?.remove(0)))
+            }
+            orc_proto::Type_Kind::MAP => {
+                expect_subtypes(2)?;
+                let mut children = children()?;
+                let value = children.pop().unwrap();
+                let key = children.pop().unwrap();
+                Schema::Map(Box::new(key), Box::new(value))
+            }
+            orc_proto::Type_Kind::UNION => {
+                if num_subtypes == 0 || num_subtypes > 256 {
+                    return Err(invalid_types(format!("union type {} has {} subtypes", id, num_subtypes)));
+                }
+                Schema::Union(children()?)
+            }
+        };
+        match schema {
+            Schema::Struct(_) | Schema::List(_) | Schema::Map(_, _) | Schema::Union(_) => {}
+            _ => expect_subtypes(0)?,
+        }
+        Ok(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::reader::OrcFile;
+    use crate::writer::{Config, Writer};
+
+    fn make_types(schema: &Schema) -> Vec<orc_proto::Type> {
+        let bytes = Writer::new(Vec::new(), schema, Config::new()).unwrap().finish().unwrap();
+        OrcFile::open(Cursor::new(bytes)).unwrap().footer().get_types().to_vec()
+    }
+
+    #[test]
+    fn test_from_proto_types() {
+        let schema = Schema::Struct(vec![
+            Field("a".to_owned(), Schema::Boolean),
+            Field("b".to_owned(), Schema::Decimal(20, 5)),
+            Field("c".to_owned(), Schema::Char(7)),
+            Field("d".to_owned(), Schema::VarChar(300)),
+            Field("e".to_owned(), Schema::List(Box::new(Schema::Struct(vec![
+                Field("x".to_owned(), Schema::Date),
+                Field("y".to_owned(), Schema::Map(Box::new(Schema::String), Box::new(Schema::Timestamp))),
+            ])))),
+            Field("f".to_owned(), Schema::Union(vec![Schema::Short, Schema::Binary, Schema::Float, Schema::Byte])),
+            Field("g".to_owned(), Schema::Double),
+        ]);
+        assert_eq!(Schema::from_proto_types(&make_types(&schema)).unwrap(), schema);
+        assert_eq!(Schema::from_proto_types(&make_types(&Schema::Int)).unwrap(), Schema::Int);
+    }
+
+    #[test]
+    fn test_column_id() {
+        let schema = Schema::Struct(vec![
+            Field("a".to_owned(), Schema::List(Box::new(Schema::Long))),
+            Field("user".to_owned(), Schema::Struct(vec![
+                Field("name".to_owned(), Schema::String),
+                Field("address".to_owned(), Schema::Struct(vec![
+                    Field("street".to_owned(), Schema::String),
+                    Field("zip".to_owned(), Schema::Int),
+                ])),
+            ])),
+            Field("b".to_owned(), Schema::Map(Box::new(Schema::String), Box::new(Schema::Double))),
+            Field("c".to_owned(), Schema::Boolean),
+        ]);
+        assert_eq!(schema.num_columns(), 12);
+        assert_eq!(schema.column_id("a"), Some(1));
+        assert_eq!(schema.column_id("user"), Some(3));
+        assert_eq!(schema.column_id("user.address.zip"), Some(7));
+        assert_eq!(schema.column_id("b"), Some(8));
+        assert_eq!(schema.column_id("c"), Some(11));
+        assert_eq!(schema.column_id("user.zip"), None);
+        assert_eq!(schema.column_id("c.d"), None);
+        assert_eq!(schema.column_id(""), None);
+
+        let paths = schema.column_paths();
+        assert_eq!(paths.len(), 12);
+        assert_eq!(&paths[..3], &["", "a", "a._elem"]);
+        assert_eq!(paths[7], "user.address.zip");
+        assert_eq!(&paths[8..], &["b", "b._key", "b._value", "c"]);
+        assert_eq!(Schema::Union(vec![Schema::Long, Schema::String]).column_paths(), vec!["", "0", "1"]);
+    }
+
+    #[test]
+    fn test_from_proto_types_invalid() {
+        let schema = Schema::Struct(vec![
+            Field("a".to_owned(), Schema::List(Box::new(Schema::Long))),
+            Field("b".to_owned(), Schema::Decimal(10, 2)),
+        ]);
+        let types = make_types(&schema);
+        assert!(Schema::from_proto_types(&[]).is_err());
+
+        // Subtype out of range, pointing back at the root (a cycle), and not in pre-order
+        for subtypes in vec![vec![1, 7], vec![1, 0], vec![3, 1]] {
+            let mut invalid = types.clone();
+            invalid[0].set_subtypes(subtypes);
+            assert!(Schema::from_proto_types(&invalid).is_err());
+        }
+
+        let mut invalid = types.clone();
+        invalid.push(orc_proto::Type::new());
+        assert!(Schema::from_proto_types(&invalid).is_err());
+
+        let mut invalid = types.clone();
+        invalid[1].set_subtypes(vec![2, 3]);
+        assert!(Schema::from_proto_types(&invalid).is_err());
+
+        let mut invalid = types.clone();
+        invalid[0].mut_fieldNames().pop();
+        assert!(Schema::from_proto_types(&invalid).is_err());
+
+        let mut invalid = types.clone();
+        invalid[3].set_scale(11);
+        assert!(Schema::from_proto_types(&invalid).is_err());
+
+        let mut invalid = types;
+        invalid[3].clear_precision();
+        invalid[3].clear_scale();
+        assert_eq!(Schema::from_proto_types(&invalid).unwrap(), Schema::Struct(vec![
+            Field("a".to_owned(), Schema::List(Box::new(Schema::Long))),
+            Field("b".to_owned(), Schema::Decimal(38, 10)),
+        ]));
+    }
+}
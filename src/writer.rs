@@ -1,29 +1,92 @@
 use super::protos::orc_proto;
 use super::schema::Schema;
 use protobuf::{CodedOutputStream, Message, RepeatedField};
+use std::collections::BTreeMap;
 use std::io::{Result, Write};
 use std::slice;
+use std::sync::Arc;
+
+use crate::bloom_filter::BloomFilter;
+use crate::error::OrcError;
 
 use count_write::CountWrite;
 use statistics::{BaseStatistics, Statistics};
 use stripe::{Stripe, StripeInfo};
 
 use data::{Data, BaseData};
-use compression::{Compression, CompressionStream, NoCompression};
+use compression::{Compression, CompressionPool, CompressionStream, NoCompression};
 
 pub mod compression;
 pub mod data;
 mod stripe;
-mod encoder;
+pub(crate) mod encoder;
 mod statistics;
 mod count_write;
 
 
+/// The ORC format version written. Version 0.12, the default, allows the integer run-length
+/// encoding version 2 (`DIRECT_V2` column encodings), which is usually more compact than version 1.
+/// Version 0.11 is for older readers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Version {
+    V0_11,
+    V0_12,
+}
+
+impl Version {
+    /// The encoding of the columns that have integer streams.
+    pub(crate) fn direct_encoding(&self) -> orc_proto::ColumnEncoding_Kind {
+        match self {
+            Version::V0_11 => orc_proto::ColumnEncoding_Kind::DIRECT,
+            Version::V0_12 => orc_proto::ColumnEncoding_Kind::DIRECT_V2,
+        }
+    }
+
+    /// The encoding of dictionary encoded string columns.
+    pub(crate) fn dictionary_encoding(&self) -> orc_proto::ColumnEncoding_Kind {
+        match self {
+            Version::V0_11 => orc_proto::ColumnEncoding_Kind::DICTIONARY,
+            Version::V0_12 => orc_proto::ColumnEncoding_Kind::DICTIONARY_V2,
+        }
+    }
+
+    fn to_proto(self) -> Vec<u32> {
+        match self {
+            Version::V0_11 => vec![0, 11],
+            Version::V0_12 => vec![0, 12],
+        }
+    }
+}
+
+/// What to do with a value that doesn't fit the type of its column: a Short or Int out of range,
+/// a Char or VarChar longer than its length, or a Decimal with more digits than its precision.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutOfRangePolicy {
+    /// The value is not written, and the next `Writer::write_batch` returns
+    /// `OrcError::ValueOutOfRange`.
+    Error,
+    /// Char and VarChar values are truncated to their length, as Hive does. The other values,
+    /// which can't be truncated, are written as null.
+    Truncate,
+    /// A null is written instead of the value.
+    Null,
+}
+
 #[derive(Clone)]
 pub struct Config {
     row_index_stride: u32,
     compression: Compression,
+    compression_threads: usize,
+    pub(crate) out_of_range_policy: OutOfRangePolicy,
     stripe_size: usize,
+    version: Version,
+    dictionary_key_size_threshold: f64,
+    bloom_filter_columns: Vec<String>,
+    bloom_filter_fpp: f64,
+    // Column ids of `bloom_filter_columns`, resolved against the schema when the writer is created
+    bloom_filter_column_ids: Vec<u32>,
+    // Paths of the columns by column id, for error messages; set when the writer is created
+    column_paths: Arc<Vec<String>>,
 }
 
 impl Config {
@@ -31,7 +94,15 @@ impl Config {
         Config {
             row_index_stride: 10000,
             compression: NoCompression::new().build(),
+            compression_threads: 0,
+            out_of_range_policy: OutOfRangePolicy::Error,
             stripe_size: 67108864,
+            version: Version::V0_12,
+            dictionary_key_size_threshold: 0.8,
+            bloom_filter_columns: Vec::new(),
+            bloom_filter_fpp: 0.01,
+            bloom_filter_column_ids: Vec::new(),
+            column_paths: Arc::new(Vec::new()),
         }
     }
 
@@ -45,10 +116,82 @@ impl Config {
         self
     }
 
+    /// Compresses the blocks of the streams on this many threads, rather than on the thread
+    /// writing the data. The blocks of a stripe are only waited for when writing it out.
+    pub fn with_compression_threads(mut self, num_threads: usize) -> Self {
+        self.compression_threads = num_threads;
+        self
+    }
+
+    /// What to do with values that don't fit the type of their column; `OutOfRangePolicy::Error`
+    /// by default. Char values shorter than their length are always padded with spaces.
+    pub fn with_out_of_range_policy(mut self, policy: OutOfRangePolicy) -> Self {
+        self.out_of_range_policy = policy;
+        self
+    }
+
     pub fn with_stripe_size(mut self, stripe_size: usize) -> Self {
         self.stripe_size = stripe_size;
         self
     }
+
+    pub fn with_version(mut self, version: Version) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// String columns are dictionary encoded in stripes where the ratio of distinct values to
+    /// non-null values is at most this threshold, checked at the end of the first row group of the
+    /// stripe (or at the end of the stripe, if shorter). A threshold of 0 disables dictionary
+    /// encoding.
+    pub fn with_dictionary_key_size_threshold(mut self, threshold: f64) -> Self {
+        self.dictionary_key_size_threshold = threshold;
+        self
+    }
+
+    /// Writes a bloom filter for each row group of the struct fields at the given dot-separated paths
+    /// (such as `"user.address.zip"`). Bloom filters are written for Byte, Short, Int, Long, Date, Double,
+    /// Timestamp, Decimal, String, Char, VarChar and Binary columns, if the row index is enabled.
+    pub fn with_bloom_filter_columns(mut self, columns: &[&str]) -> Self {
+        self.bloom_filter_columns = columns.iter().map(|&c| c.to_owned()).collect();
+        self
+    }
+
+    /// The false positive probability of the bloom filters, which must be between 0 and 1.
+    pub fn with_bloom_filter_fpp(mut self, fpp: f64) -> Self {
+        self.bloom_filter_fpp = fpp;
+        self
+    }
+
+    pub(crate) fn column_path(&self, column_id: u32) -> String {
+        self.column_paths.get(column_id as usize).cloned().unwrap_or_default()
+    }
+
+    /// A new bloom filter for a row group of column `column_id`, if the column has bloom filters.
+    pub(crate) fn bloom_filter(&self, column_id: u32) -> Option<BloomFilter> {
+        if self.row_index_stride > 0 && self.bloom_filter_column_ids.contains(&column_id) {
+            Some(BloomFilter::new(self.row_index_stride as u64, self.bloom_filter_fpp))
+        } else {
+            None
+        }
+    }
+}
+
+/// Checks the parts of the schema which can't be written.
+fn validate_schema(schema: &Schema) -> std::result::Result<(), OrcError> {
+    match schema {
+        Schema::Struct(fields) => fields.iter().try_for_each(|f| validate_schema(&f.1)),
+        Schema::List(child) => validate_schema(child),
+        Schema::Map(key, value) => validate_schema(key).and_then(|_| validate_schema(value)),
+        Schema::Union(variants) => {
+            if variants.len() > 256 {
+                return Err(OrcError::InvalidConfig(
+                    format!("unions are limited to at most 256 variants, found {}", variants.len())));
+            }
+            variants.iter().try_for_each(validate_schema)
+        }
+        _ => Ok(()),
+    }
 }
 
 #[must_use]
@@ -57,17 +200,34 @@ pub struct Writer<W: Write> {
     config: Config,
     current_stripe: Stripe,
     stripe_infos: Vec<StripeInfo>,
+    user_metadata: BTreeMap<String, Vec<u8>>,
 }
 
 impl<W: Write> Writer<W> {
     const HEADER_LENGTH: u64 = 3;
 
-    pub fn new(inner: W, schema: &Schema, config: Config) -> Result<Self> {
+    pub fn new(inner: W, schema: &Schema, mut config: Config) -> std::result::Result<Self, OrcError> {
+        validate_schema(schema)?;
+        let fpp_valid = config.bloom_filter_fpp > 0.0 && config.bloom_filter_fpp < 1.0;
+        if !config.bloom_filter_columns.is_empty() && !fpp_valid {
+            return Err(OrcError::InvalidConfig(
+                format!("bloom filter false positive probability {} is not between 0 and 1", config.bloom_filter_fpp)));
+        }
+        config.bloom_filter_column_ids = config.bloom_filter_columns.iter().map(|path| {
+            schema.column_id(path).ok_or_else(|| OrcError::InvalidConfig(
+                format!("no struct field at path {:?} for bloom filters", path)))
+        }).collect::<std::result::Result<_, _>>()?;
+        config.column_paths = Arc::new(schema.column_paths());
+        if config.compression_threads > 0 {
+            let pool = Arc::new(CompressionPool::new(config.compression_threads));
+            config.compression = config.compression.with_pool(pool);
+        }
         let mut writer = Self {
             inner: CountWrite::new(inner),
             current_stripe: Stripe::new(schema, &config),
             config,
             stripe_infos: Vec::new(),
+            user_metadata: BTreeMap::new(),
         };
         writer.write_header()?;
         Ok(writer)
@@ -81,7 +241,17 @@ impl<W: Write> Writer<W> {
         &self.inner.inner
     }
 
-    pub fn write_batch(&mut self, num_rows: u64) -> Result<()> {
+    /// Adds an entry to the user metadata in the file footer, replacing any previous entry with the
+    /// same name.
+    pub fn add_user_metadata(&mut self, name: &str, value: &[u8]) {
+        self.user_metadata.insert(name.to_owned(), value.to_vec());
+    }
+
+    /// Completes a batch of `num_rows` rows, whose values have been written to the columns of
+    /// `data()`. It fails if the number of values written to a column doesn't match, or if a value
+    /// was rejected when it was written. After an error, the file can't be completed, and the writer
+    /// should be discarded.
+    pub fn write_batch(&mut self, num_rows: u64) -> std::result::Result<(), OrcError> {
         self.current_stripe.write_batch(num_rows)?;
         if self.current_stripe.data.estimated_size() > self.config.stripe_size {
             self.current_stripe.finish(&mut self.inner, &mut self.stripe_infos)?;
@@ -89,7 +259,7 @@ impl<W: Write> Writer<W> {
         Ok(())
     }
 
-    pub fn finish(mut self) -> Result<W> {
+    pub fn finish(mut self) -> std::result::Result<W, OrcError> {
         self.current_stripe
             .finish(&mut self.inner, &mut self.stripe_infos)?;
         let content_length = self.current_stripe.offset - Self::HEADER_LENGTH;
@@ -157,6 +327,10 @@ impl<W: Write> Writer<W> {
                 t.set_kind(orc_proto::Type_Kind::BOOLEAN);
                 types.push(t);
             }
+            Data::Byte(_) => {
+                t.set_kind(orc_proto::Type_Kind::BYTE);
+                types.push(t);
+            }
             Data::Long(long_data) => {
                 t.set_kind(match long_data.schema() {
                     Schema::Short => orc_proto::Type_Kind::SHORT,
@@ -281,7 +455,13 @@ impl<W: Write> Writer<W> {
         Self::make_types(&self.current_stripe.data, &mut types);
         footer.set_types(RepeatedField::from_vec(types));
 
-        footer.set_metadata(RepeatedField::from_vec(vec![]));
+        let user_metadata = self.user_metadata.iter().map(|(name, value)| {
+            let mut item = orc_proto::UserMetadataItem::new();
+            item.set_name(name.clone());
+            item.set_value(value.clone());
+            item
+        }).collect();
+        footer.set_metadata(RepeatedField::from_vec(user_metadata));
         footer.set_numberOfRows(self.stripe_infos.iter().map(|x| x.num_rows).sum());
         footer.set_statistics(RepeatedField::from_vec(stats));
         footer.set_rowIndexStride(self.config.row_index_stride);
@@ -300,10 +480,269 @@ impl<W: Write> Writer<W> {
         postscript.set_writerVersion(6);
         postscript.set_metadataLength(metadata_length);
         postscript.set_footerLength(footer_length);
-        postscript.set_version(vec![0, 12]);
+        postscript.set_version(self.config.version.to_proto());
         postscript.set_magic("ORC".to_owned());
         postscript.write_to(&mut coded_out)?;
         coded_out.flush()?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use crate::reader::OrcFile;
+    use crate::schema::Field;
+    use crate::writer::data::GenericData;
+
+    #[test]
+    fn test_bloom_filters() {
+        let schema = Schema::Struct(vec![
+            Field("id".to_owned(), Schema::Long),
+            Field("name".to_owned(), Schema::String),
+            Field("flag".to_owned(), Schema::Boolean),
+        ]);
+        let config = Config::new().with_row_index_stride(1000).with_bloom_filter_fpp(0.05)
+            .with_bloom_filter_columns(&["id", "name", "flag"]);
+        let mut writer = Writer::new(Vec::new(), &schema, config).unwrap();
+        for i in 0..2500 {
+            let data = writer.data().unwrap_struct();
+            data.child(0).unwrap_long().write(i);
+            if i % 10 == 0 {
+                data.child(1).write_null();
+            } else {
+                data.child(1).unwrap_string().write(&format!("name-{}", i));
+            }
+            data.child(2).unwrap_boolean().write(i % 2 == 0);
+            data.write();
+        }
+        writer.write_batch(2500).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let mut file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        let streams = file.read_stripe(0, |_, _| true).unwrap();
+        let bloom_filter_index = |column_id| streams.stream(column_id, orc_proto::Stream_Kind::BLOOM_FILTER_UTF8)
+            .map(|mut stream| orc_proto::BloomFilterIndex::parse_from_reader(&mut stream).unwrap());
+
+        let ids = bloom_filter_index(1).unwrap();
+        assert_eq!(ids.get_bloomFilter().len(), 3);
+        let mut expected = BloomFilter::new(1000, 0.05);
+        (1000..2000).for_each(|i| expected.add_long(i));
+        assert_eq!(ids.get_bloomFilter()[1], expected.to_proto());
+
+        let names = bloom_filter_index(2).unwrap();
+        let mut expected = BloomFilter::new(1000, 0.05);
+        (2000..2500).filter(|i| i % 10 != 0).for_each(|i| expected.add_bytes(format!("name-{}", i).as_bytes()));
+        assert_eq!(names.get_bloomFilter()[2], expected.to_proto());
+
+        // Bloom filters are not written for boolean columns
+        assert!(bloom_filter_index(0).is_none());
+        assert!(bloom_filter_index(3).is_none());
+        assert_eq!(streams.encoding(1).unwrap().get_bloomEncoding(), 1);
+        assert!(!streams.encoding(3).unwrap().has_bloomEncoding());
+    }
+
+    #[test]
+    fn test_bloom_filters_invalid() {
+        let schema = Schema::Struct(vec![Field("id".to_owned(), Schema::Long)]);
+        assert!(Writer::new(Vec::new(), &schema, Config::new().with_bloom_filter_columns(&["x"])).is_err());
+        let config = Config::new().with_bloom_filter_columns(&["id"]).with_bloom_filter_fpp(1.5);
+        assert!(Writer::new(Vec::new(), &schema, config).is_err());
+    }
+
+    #[test]
+    fn test_compression_threads() {
+        // Compressing on other threads gives the same file, including the row index positions
+        let schema = Schema::Struct(vec![Field("x".to_owned(), Schema::Long), Field("s".to_owned(), Schema::String)]);
+        let write = |config: Config| {
+            let config = config.with_row_index_stride(2000).with_stripe_size(100000)
+                .with_compression(compression::ZstdCompression::new().with_block_size(1000).unwrap().build());
+            let mut writer = Writer::new(Vec::new(), &schema, config).unwrap();
+            for i in 0..20000 {
+                let data = writer.data().unwrap_struct();
+                data.child(0).unwrap_long().write(i * i);
+                data.child(1).unwrap_string().write(&format!("{}", i % 1000));
+                data.write();
+            }
+            writer.write_batch(20000).unwrap();
+            writer.finish().unwrap()
+        };
+        assert_eq!(write(Config::new().with_compression_threads(3)), write(Config::new()));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_rayon() {
+        // Finishing the columns in parallel gives the same file as finishing them one after another,
+        // which is what a single rayon thread does
+        let schema = Schema::Struct(vec![
+            Field("x".to_owned(), Schema::Long),
+            Field("s".to_owned(), Schema::String),
+            Field("y".to_owned(), Schema::Struct(vec![
+                Field("d".to_owned(), Schema::Double),
+                Field("l".to_owned(), Schema::List(Box::new(Schema::Int))),
+            ])),
+        ]);
+        let write = |num_threads: usize, config: Config| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(num_threads).build().unwrap();
+            pool.install(|| {
+                let config = config.with_row_index_stride(2000).with_stripe_size(100000)
+                    .with_compression(compression::ZstdCompression::new().with_block_size(1000).unwrap().build());
+                let mut writer = Writer::new(Vec::new(), &schema, config).unwrap();
+                for i in 0..20000 {
+                    let data = writer.data().unwrap_struct();
+                    data.child(0).unwrap_long().write(i * i);
+                    data.child(1).unwrap_string().write(&format!("{}", i % 1000));
+                    let y = data.child(2).unwrap_struct();
+                    if i % 7 == 0 {
+                        y.write_null();
+                    } else {
+                        y.child(0).unwrap_double().write(i as f64 / 3.0);
+                        let l = y.child(1).unwrap_list();
+                        for j in 0..i % 4 {
+                            l.child().unwrap_long().write(i * j);
+                        }
+                        l.write((i % 4) as u64);
+                        y.write();
+                    }
+                    data.write();
+                }
+                writer.write_batch(20000).unwrap();
+                writer.finish().unwrap()
+            })
+        };
+        let sequential = write(1, Config::new());
+        assert_eq!(write(4, Config::new()), sequential);
+        assert_eq!(write(4, Config::new().with_compression_threads(2)), sequential);
+    }
+
+    #[test]
+    fn test_errors() {
+        let schema = Schema::Struct(vec![
+            Field("a".to_owned(), Schema::Struct(vec![
+                Field("b".to_owned(), Schema::Long),
+                Field("c".to_owned(), Schema::String),
+            ])),
+            Field("u".to_owned(), Schema::Union(vec![Schema::Long, Schema::Boolean])),
+        ]);
+
+        let mut writer = Writer::new(Vec::new(), &schema, Config::new()).unwrap();
+        let data = writer.data().unwrap_struct();
+        data.child(0).unwrap_struct().child(0).unwrap_long().write(1);
+        match data.child(0).unwrap_struct().child(1).as_long() {
+            Err(OrcError::SchemaMismatch { column_id: 3, path, expected: "Long", actual: "String" }) =>
+                assert_eq!(path, "a.c"),
+            e => panic!("unexpected result {:?}", e.map(|_| ())),
+        }
+        data.child(0).unwrap_struct().write();
+        data.child(1).write_null();
+        data.write();
+        match writer.write_batch(1) {
+            Err(OrcError::RowCountMismatch { column_id: 3, path, expected: 1, actual: 0 }) => assert_eq!(path, "a.c"),
+            r => panic!("unexpected result {:?}", r),
+        }
+
+        let mut writer = Writer::new(Vec::new(), &schema, Config::new()).unwrap();
+        let data = writer.data().unwrap_struct();
+        data.child(0).write_null();
+        data.child(1).unwrap_union().write(2);
+        data.write();
+        match writer.write_batch(1) {
+            Err(OrcError::ValueOutOfRange { column_id: 4, path, .. }) => assert_eq!(path, "u"),
+            r => panic!("unexpected result {:?}", r),
+        }
+
+        let schema = Schema::Union(vec![Schema::Boolean; 257]);
+        assert!(matches!(Writer::new(Vec::new(), &schema, Config::new()), Err(OrcError::InvalidConfig(_))));
+        assert!(compression::ZstdCompression::new().with_compression_level(30).is_err());
+        assert!(compression::ZlibCompression::new().with_compression_level(10).is_err());
+        assert!(compression::SnappyCompression::new().with_block_size(0).is_err());
+        assert!(compression::Lz4Compression::new().with_block_size(1 << 23).is_err());
+    }
+
+    #[test]
+    fn test_date_statistics() {
+        use crate::reader::{RowReaderOptions, SearchArgument};
+        use crate::reader::search_argument::Value;
+
+        let schema = Schema::Struct(vec![Field("d".to_owned(), Schema::Date)]);
+        let mut writer = Writer::new(Vec::new(), &schema, Config::new().with_row_index_stride(100)).unwrap();
+        for i in 0..300 {
+            let data = writer.data().unwrap_struct();
+            data.child(0).unwrap_long().write(i - 10);
+            data.write();
+        }
+        writer.write_batch(300).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let mut file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        let stats = &file.footer().get_statistics()[1];
+        assert!(!stats.has_intStatistics());
+        assert_eq!(stats.get_dateStatistics().get_minimum(), -10);
+        assert_eq!(stats.get_dateStatistics().get_maximum(), 289);
+
+        let streams = file.read_stripe(0, |_, _| true).unwrap();
+        let mut index = streams.stream(1, orc_proto::Stream_Kind::ROW_INDEX).unwrap();
+        let index = orc_proto::RowIndex::parse_from_reader(&mut index).unwrap();
+        let stats = index.get_entry()[1].get_statistics().get_dateStatistics();
+        assert_eq!((stats.get_minimum(), stats.get_maximum()), (90, 189));
+
+        // Row groups are skipped based on the date statistics
+        let sarg = SearchArgument::Between("d".to_owned(), Value::Long(200), Value::Long(1000));
+        let mut reader = file.row_reader_with_options(RowReaderOptions::new().with_search_argument(sarg)).unwrap();
+        assert_eq!(reader.read_batch(1000).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_timestamp_statistics() {
+        use crate::reader::{RowReaderOptions, SearchArgument};
+        use crate::reader::search_argument::Value;
+        use crate::writer::data::TimestampData;
+
+        let schema = Schema::Struct(vec![Field("t".to_owned(), Schema::Timestamp)]);
+        let mut writer = Writer::new(Vec::new(), &schema, Config::new().with_row_index_stride(2)).unwrap();
+        for &(sec_epoch, nanos) in &[(-1, 500_000_000), (-86400, 999_999), (1_600_000_000, 1_999_999), (1_600_000_001, 0)] {
+            let data = writer.data().unwrap_struct();
+            data.child(0).unwrap_timestamp().write_nanos_epoch(sec_epoch, nanos);
+            data.write();
+        }
+        writer.write_batch(4).unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let mut file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        let stats = file.footer().get_statistics()[1].get_timestampStatistics();
+        assert_eq!((stats.get_minimum(), stats.get_maximum()), (-86_400_000, 1_600_000_001_000));
+        assert_eq!((stats.get_minimumUtc(), stats.get_maximumUtc()), (-86_400_000, 1_600_000_001_000));
+        let streams = file.read_stripe(0, |_, _| true).unwrap();
+        let mut index = streams.stream(1, orc_proto::Stream_Kind::ROW_INDEX).unwrap();
+        let index = orc_proto::RowIndex::parse_from_reader(&mut index).unwrap();
+        let stats = index.get_entry()[0].get_statistics().get_timestampStatistics();
+        assert_eq!((stats.get_minimumUtc(), stats.get_maximumUtc()), (-86_400_000, -500));
+        let stats = index.get_entry()[1].get_statistics().get_timestampStatistics();
+        assert_eq!((stats.get_minimumUtc(), stats.get_maximumUtc()), (1_600_000_000_001, 1_600_000_001_000));
+
+        // Row groups are skipped based on the statistics
+        let sec = |sec_epoch| sec_epoch + TimestampData::EPOCH_SECONDS;
+        let sarg = SearchArgument::Between("t".to_owned(), Value::Timestamp(sec(-1), 0), Value::Timestamp(sec(0), 0));
+        let mut reader = file.row_reader_with_options(RowReaderOptions::new().with_search_argument(sarg)).unwrap();
+        assert_eq!(reader.read_batch(1000).unwrap(), 2);
+        assert_eq!(reader.data().unwrap_struct().child(0).unwrap_timestamp().seconds_epoch(0), -1);
+    }
+
+    #[test]
+    fn test_user_metadata() {
+        let mut writer = Writer::new(Vec::new(), &Schema::Long, Config::new()).unwrap();
+        writer.add_user_metadata("source", b"kafka:events:3");
+        writer.data().unwrap_long().write(1);
+        writer.write_batch(1).unwrap();
+        writer.add_user_metadata("fingerprint", &[0, 255, 7]);
+        writer.add_user_metadata("source", b"kafka:events:4");
+        let bytes = writer.finish().unwrap();
+
+        let file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        assert_eq!(file.user_metadata().len(), 2);
+        assert_eq!(file.user_metadata()["source"], b"kafka:events:4");
+        assert_eq!(file.user_metadata()["fingerprint"], vec![0, 255, 7]);
+    }
+}
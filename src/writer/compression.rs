@@ -1,34 +1,59 @@
 use crate::protos::orc_proto;
+use std::cell::RefCell;
 use std::io::{Result, Write};
+use std::sync::Arc;
 use byteorder::{LittleEndian, WriteBytesExt};
 
 
 use crate::buffer::Buffer;
+use crate::error::OrcError;
 use common::{CompressionTrait, Compressor};
+pub(crate) use pool::CompressionPool;
+use pool::{CompressedBlock, PendingBlocks};
 
 pub use no_compression::NoCompression;
 pub use snappy::SnappyCompression;
 pub use self::zstd::ZstdCompression;
+pub use zlib::ZlibCompression;
+pub use lz4::Lz4Compression;
+pub use lzo::LzoCompression;
 
 mod common;
 mod no_compression;
 mod snappy;
 mod zstd;
+mod zlib;
+mod lz4;
+mod lzo;
+mod pool;
 
 #[derive(Clone)]
-pub struct Compression(CompressionEnum);
+pub struct Compression {
+    codec: CompressionEnum,
+    // Threads to compress the blocks on, rather than on the thread writing the stream
+    pool: Option<Arc<CompressionPool>>,
+}
 
 impl Compression {
+    fn new(codec: CompressionEnum) -> Self {
+        Compression { codec, pool: None }
+    }
+
+    pub(crate) fn with_pool(mut self, pool: Arc<CompressionPool>) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
     pub(crate) fn kind(&self) -> orc_proto::CompressionKind {
-        self.0.kind()
+        self.codec.kind()
     }
 
     pub(crate) fn block_size(&self) -> usize {
-        self.0.block_size()
+        self.codec.block_size()
     }
 
     fn compressor(&self) -> Option<Box<dyn Compressor>> {
-        self.0.compressor()
+        self.codec.compressor()
     }
 }
 
@@ -37,6 +62,9 @@ enum CompressionEnum {
     No(NoCompression),
     Snappy(SnappyCompression),
     Zstd(ZstdCompression),
+    Zlib(ZlibCompression),
+    Lz4(Lz4Compression),
+    Lzo(LzoCompression),
 }
 
 // We could eliminate this boilerplate using enum-dispatch, but it doesn't work yet with RLS.
@@ -47,6 +75,9 @@ impl CompressionTrait for CompressionEnum {
             CompressionEnum::No(x) => x.kind(),
             CompressionEnum::Snappy(x) => x.kind(),
             CompressionEnum::Zstd(x) => x.kind(),
+            CompressionEnum::Zlib(x) => x.kind(),
+            CompressionEnum::Lz4(x) => x.kind(),
+            CompressionEnum::Lzo(x) => x.kind(),
         }
     }
 
@@ -55,6 +86,9 @@ impl CompressionTrait for CompressionEnum {
             CompressionEnum::No(x) => x.block_size(),
             CompressionEnum::Snappy(x) => x.block_size(),
             CompressionEnum::Zstd(x) => x.block_size(),
+            CompressionEnum::Zlib(x) => x.block_size(),
+            CompressionEnum::Lz4(x) => x.block_size(),
+            CompressionEnum::Lzo(x) => x.block_size(),
         }
     }
 
@@ -63,6 +97,9 @@ impl CompressionTrait for CompressionEnum {
             CompressionEnum::No(x) => x.compressor(),
             CompressionEnum::Snappy(x) => x.compressor(),
             CompressionEnum::Zstd(x) => x.compressor(),
+            CompressionEnum::Zlib(x) => x.compressor(),
+            CompressionEnum::Lz4(x) => x.compressor(),
+            CompressionEnum::Lzo(x) => x.compressor(),
         }
     }
 
@@ -70,23 +107,41 @@ impl CompressionTrait for CompressionEnum {
 
 impl NoCompression {
     pub fn build(self) -> Compression {
-        Compression(CompressionEnum::No(self))
+        Compression::new(CompressionEnum::No(self))
     }
 }
 
 impl SnappyCompression {
     pub fn build(self) -> Compression {
-        Compression(CompressionEnum::Snappy(self))
+        Compression::new(CompressionEnum::Snappy(self))
     }
 }
 
 impl ZstdCompression {
     pub fn build(self) -> Compression {
-        Compression(CompressionEnum::Zstd(self))
+        Compression::new(CompressionEnum::Zstd(self))
     }
 
 }
 
+impl ZlibCompression {
+    pub fn build(self) -> Compression {
+        Compression::new(CompressionEnum::Zlib(self))
+    }
+}
+
+impl Lz4Compression {
+    pub fn build(self) -> Compression {
+        Compression::new(CompressionEnum::Lz4(self))
+    }
+}
+
+impl LzoCompression {
+    pub fn build(self) -> Compression {
+        Compression::new(CompressionEnum::Lzo(self))
+    }
+}
+
 struct BlockInfo {
     is_original: bool,
     length: usize,
@@ -94,23 +149,31 @@ struct BlockInfo {
 
 pub(crate) struct CompressionStream {
     compressor: Option<Box<dyn Compressor>>,
+    block_size: usize,
     buf: Buffer,
     output: Buffer,
     output_block_info: Vec<BlockInfo>,
+    // Blocks handed to the compression pool, if any. Row index positions refer to blocks by their
+    // index, whose offset in the stream is only resolved when recording the position.
+    pending: Option<Box<RefCell<PendingBlocks>>>,
+    // First compression error, reported by `finish`
+    error: Option<Box<OrcError>>,
 }
 
 #[derive(Copy, Clone)]
 pub(crate) struct CompressionStreamPosition {
-    // Offset of the start of the compression block (if applicable)
-    block_start: Option<u64>,
-    // Offset within the block
+    // Index of the compression block in the stream (if applicable)
+    block: Option<usize>,
+    // Offset within the (uncompressed) block
     offset: u64,
 }
 
 impl CompressionStreamPosition {
-    pub fn record(&self, out: &mut Vec<u64>) {
-        if let Some(x) = self.block_start {
-            out.push(x);
+    /// Records the position, whose block must have been compressed in `stream` (or still be
+    /// pending), so that its offset in the stream can be computed.
+    pub fn record(&self, stream: &CompressionStream, out: &mut Vec<u64>) {
+        if let Some(block) = self.block {
+            out.push(stream.block_start(block));
         }
         out.push(self.offset);
     }
@@ -118,43 +181,74 @@ impl CompressionStreamPosition {
 
 impl CompressionStream {
     pub fn new(compression: &Compression) -> Self {
+        let compressor = compression.compressor();
+        let pending = match (&compression.pool, &compressor) {
+            (Some(pool), Some(_)) => Some(Box::new(RefCell::new(PendingBlocks::new(pool.clone(), compression.codec.clone())))),
+            _ => None,
+        };
+        // Uncompressed streams are a single unbounded block
+        let block_size = if compressor.is_some() { compression.block_size() } else { usize::MAX };
         CompressionStream {
-            compressor: compression.compressor(),
+            compressor,
+            block_size,
             buf: Buffer::with_capacity(compression.block_size()),
             output: Buffer::new(),
             output_block_info: Vec::new(),
+            pending,
+            error: None,
         }
     }
 
     pub fn position(&self) -> CompressionStreamPosition {
         CompressionStreamPosition {
-            block_start: if let Some(_) = &self.compressor {
-                    Some((self.output.len() + 3*self.output_block_info.len()) as u64)
+            block: if self.compressor.is_some() {
+                    let num_pending = self.pending.as_ref().map_or(0, |p| p.borrow().len());
+                    Some(self.output_block_info.len() + num_pending)
                 } else { None },
             offset: self.buf.len() as u64,
         }
     }
 
+    /// The offset in the stream of the block `block`, including the headers of the previous
+    /// blocks. This waits for the previous blocks if they are being compressed by the pool.
+    fn block_start(&self, block: usize) -> u64 {
+        let num_appended = block.min(self.output_block_info.len());
+        let appended_len: usize = self.output_block_info[..num_appended].iter().map(|b| 3 + b.length).sum();
+        let pending_len = match &self.pending {
+            Some(pending) if block > num_appended => pending.borrow_mut().wait_for(block - num_appended),
+            _ => 0,
+        };
+        (appended_len + pending_len) as u64
+    }
+
     fn finish_block(&mut self) {
         if self.buf.len() == 0 {
             return;
         }
-        if let Some(compressor) = &mut self.compressor {
+        if let Some(pending) = &mut self.pending {
+            let block = std::mem::replace(&mut self.buf, Buffer::with_capacity(self.block_size));
+            pending.get_mut().submit(block);
+        } else if let Some(compressor) = &mut self.compressor {
             let i = self.output.len();
-            compressor.compress(&self.buf, &mut self.output);
+            let failed = match compressor.compress(&self.buf, &mut self.output) {
+                Ok(()) => false,
+                Err(e) => {
+                    self.error.get_or_insert(Box::new(e));
+                    true
+                }
+            };
             let len = self.output.len() - i;
-            if len > self.buf.len() {
+            if failed || len > self.buf.len() {
                 // Compression was unsuccessful, in that the compressed output was larger than
                 // the input. In this case, the ORC spec requires that we instead store the
                 // original uncompressed data. With a little fancier bookkeeping, we could
                 // avoid copying here and just keep the data where it already is (in self.buf).
-                let new_output_len = i + self.buf.len();
-                self.output[i..new_output_len].copy_from_slice(&self.buf);
+                self.output.resize(i);
+                self.output.write_bytes(&self.buf);
                 self.output_block_info.push(BlockInfo {
                     is_original: true,
                     length: self.buf.len(),
                 });
-                self.output.resize(new_output_len);
             } else {
                 self.output_block_info.push(BlockInfo {
                     is_original: false,
@@ -165,9 +259,26 @@ impl CompressionStream {
         }
     }
 
+    /// Appends the blocks compressed by the pool to the output.
+    fn append_pending(&mut self) {
+        if let Some(pending) = &mut self.pending {
+            let (blocks, error) = pending.get_mut().take_done();
+            if let Some(e) = error {
+                self.error.get_or_insert(Box::new(e));
+            }
+            for CompressedBlock { data, is_original } in blocks {
+                self.output.write_bytes(&data);
+                self.output_block_info.push(BlockInfo {
+                    is_original,
+                    length: data.len(),
+                });
+            }
+        }
+    }
+
     #[inline(always)]
     pub fn write_u8(&mut self, b: u8) {
-        if self.buf.len() >= self.buf.capacity() {
+        if self.buf.len() >= self.block_size {
             self.finish_block();
             self.buf.write_u8(b);
         } else {
@@ -176,25 +287,35 @@ impl CompressionStream {
     }
 
     #[inline(always)]
-    pub fn write_bytes(&mut self, bytes: &[u8]) {
+    pub fn write_bytes(&mut self, mut bytes: &[u8]) {
         if let Some(_) = &mut self.compressor {
-            if self.buf.len() + bytes.len() > self.buf.capacity() {
-                let i = self.buf.capacity() - self.buf.len();
+            while self.buf.len() + bytes.len() > self.block_size {
+                let i = self.block_size - self.buf.len();
                 self.buf.write_bytes(&bytes[..i]);
                 self.finish_block();
-                self.buf.resize(0);
-                self.buf.write_bytes(&bytes[i..]);
-            } else {
-                self.buf.write_bytes(bytes);
+                bytes = &bytes[i..];
             }
+            self.buf.write_bytes(bytes);
         } else {
             self.buf.write_bytes(bytes);
         }
     }
 
+    /// Compresses the buffered data and collects the blocks compressed by the pool, so that `finish`
+    /// only has to copy the blocks out.
+    pub fn compress_remaining(&mut self) {
+        if self.compressor.is_some() {
+            self.finish_block();
+            self.append_pending();
+        }
+    }
+
     pub fn finish<W: Write>(&mut self, out: &mut W) -> Result<()> {
         if let Some(_) = &self.compressor {
-            self.finish_block();
+            self.compress_remaining();
+            if let Some(e) = self.error.take() {
+                return Err((*e).into());
+            }
             let mut i = 0;
             for info in &self.output_block_info {
                 let header = info.length * 2 + (info.is_original as usize);
@@ -213,7 +334,8 @@ impl CompressionStream {
     }
 
     pub fn estimated_size(&self) -> usize {
-        self.output.len() + self.buf.len()
+        let pending_len = self.pending.as_ref().map_or(0, |p| p.borrow().estimated_size());
+        self.output.len() + self.buf.len() + pending_len
     }
 }
 
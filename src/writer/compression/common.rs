@@ -1,3 +1,4 @@
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::buffer::Buffer;
 
@@ -9,6 +10,14 @@ pub trait CompressionTrait {
     fn compressor(&self) -> Option<Box<dyn Compressor>>;
 }
 
-pub trait Compressor {
-    fn compress(&mut self, input: &[u8], output: &mut Buffer);
+pub trait Compressor: Send {
+    fn compress(&mut self, input: &[u8], output: &mut Buffer) -> Result<(), OrcError>;
+}
+
+pub fn check_block_size(block_size: usize) -> Result<(), OrcError> {
+    if block_size == 0 || block_size > MAX_BLOCK_SIZE {
+        return Err(OrcError::InvalidConfig(
+            format!("compression block size {} is not between 1 and {}", block_size, MAX_BLOCK_SIZE)));
+    }
+    Ok(())
 }
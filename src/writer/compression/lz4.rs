@@ -0,0 +1,58 @@
+use crate::error::OrcError;
+use crate::protos::orc_proto;
+use super::common::{CompressionTrait, Compressor, check_block_size};
+use crate::buffer::Buffer;
+
+#[derive(Clone)]
+pub struct Lz4Compression {
+    block_size: usize,
+}
+
+impl Lz4Compression {
+    pub fn new() -> Self {
+        Self {
+            block_size: 262144,
+        }
+    }
+
+    pub fn with_block_size(mut self, block_size: usize) -> Result<Self, OrcError> {
+        check_block_size(block_size)?;
+        self.block_size = block_size;
+        Ok(self)
+    }
+}
+
+impl Default for Lz4Compression {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompressionTrait for Lz4Compression {
+    fn kind(&self) -> orc_proto::CompressionKind {
+        orc_proto::CompressionKind::LZ4
+    }
+
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn compressor(&self) -> Option<Box<dyn Compressor>> {
+        Some(Box::new(Lz4Compressor {}))
+    }
+}
+
+struct Lz4Compressor {}
+
+impl Compressor for Lz4Compressor {
+    fn compress(&mut self, input: &[u8], output: &mut Buffer) -> Result<(), OrcError> {
+        // ORC uses the LZ4 block format, without the frame header or a size prefix.
+        let current_len = output.len();
+        let max_additional_len = lz4_flex::block::get_maximum_output_size(input.len());
+        output.ensure_size(current_len + max_additional_len);
+        let additional_len = lz4_flex::block::compress_into(input, &mut output[current_len..])
+            .map_err(|e| OrcError::Compression(e.to_string()))?;
+        output.resize(current_len + additional_len);
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+use crate::error::OrcError;
+use crate::protos::orc_proto;
+use super::common::{CompressionTrait, Compressor, check_block_size};
+use crate::buffer::Buffer;
+
+#[derive(Clone)]
+pub struct LzoCompression {
+    block_size: usize,
+}
+
+impl LzoCompression {
+    pub fn new() -> Self {
+        Self {
+            block_size: 262144,
+        }
+    }
+
+    pub fn with_block_size(mut self, block_size: usize) -> Result<Self, OrcError> {
+        check_block_size(block_size)?;
+        self.block_size = block_size;
+        Ok(self)
+    }
+}
+
+impl Default for LzoCompression {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompressionTrait for LzoCompression {
+    fn kind(&self) -> orc_proto::CompressionKind {
+        orc_proto::CompressionKind::LZO
+    }
+
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn compressor(&self) -> Option<Box<dyn Compressor>> {
+        Some(Box::new(LzoCompressor {
+            hash_table: vec![0; 1 << HASH_BITS],
+        }))
+    }
+}
+
+const HASH_BITS: u32 = 14;
+const MAX_M3_DISTANCE: usize = 0x4000;
+const MAX_M4_DISTANCE: usize = 0xbfff;
+
+/// A simple greedy LZO1X compressor. It only uses the 3- and 4-byte match instructions, which is
+/// enough to produce blocks that any LZO1X decompressor accepts.
+struct LzoCompressor {
+    // Position (plus one) of the last occurrence of each hash of 4 bytes, or 0 if none
+    hash_table: Vec<u32>,
+}
+
+impl Compressor for LzoCompressor {
+    fn compress(&mut self, input: &[u8], output: &mut Buffer) -> Result<(), OrcError> {
+        for x in self.hash_table.iter_mut() {
+            *x = 0;
+        }
+        // Offset in `output` of the byte holding the number of literals following the last match
+        let mut last_match: Option<usize> = None;
+        let mut literal_start = 0;
+        let mut i = 0;
+        while i + 4 <= input.len() {
+            let key = u32::from_le_bytes([input[i], input[i + 1], input[i + 2], input[i + 3]]);
+            let hash = (key.wrapping_mul(2654435761) >> (32 - HASH_BITS)) as usize;
+            let candidate = self.hash_table[hash] as usize;
+            self.hash_table[hash] = (i + 1) as u32;
+            if candidate > 0 && i - (candidate - 1) <= MAX_M4_DISTANCE
+                    && input[candidate - 1..candidate + 3] == input[i..i + 4] {
+                let start = candidate - 1;
+                let mut len = 4;
+                while i + len < input.len() && input[start + len] == input[i + len] {
+                    len += 1;
+                }
+                write_literals(&input[literal_start..i], last_match, output);
+                last_match = Some(write_match(i - start, len, output));
+                i += len;
+                literal_start = i;
+            } else {
+                i += 1;
+            }
+        }
+        write_literals(&input[literal_start..], last_match, output);
+        // End of stream marker
+        output.write_bytes(&[0x11, 0, 0]);
+        Ok(())
+    }
+}
+
+/// Writes the remainder of a length which didn't fit in the bits of its instruction.
+fn write_length_extension(mut len: usize, output: &mut Buffer) {
+    while len > 255 {
+        output.write_u8(0);
+        len -= 255;
+    }
+    output.write_u8(len as u8);
+}
+
+fn write_literals(literals: &[u8], last_match: Option<usize>, output: &mut Buffer) {
+    let len = literals.len();
+    if len == 0 {
+        return;
+    }
+    match last_match {
+        None if len <= 238 => output.write_u8(17 + len as u8),
+        // Up to 3 literals are encoded in the last match instruction
+        Some(pos) if len <= 3 => output[pos] |= len as u8,
+        _ if len <= 18 => output.write_u8(len as u8 - 3),
+        _ => {
+            output.write_u8(0);
+            write_length_extension(len - 18, output);
+        }
+    }
+    output.write_bytes(literals);
+}
+
+/// Writes a match of at least 3 bytes, returning the offset of the byte to later hold the number
+/// of literals following it.
+fn write_match(distance: usize, len: usize, output: &mut Buffer) -> usize {
+    let len = len - 2;
+    let distance = if distance <= MAX_M3_DISTANCE {
+        let distance = distance - 1;
+        if len <= 31 {
+            output.write_u8(32 | len as u8);
+        } else {
+            output.write_u8(32);
+            write_length_extension(len - 31, output);
+        }
+        distance
+    } else {
+        let distance = distance - MAX_M3_DISTANCE;
+        let high_bit = ((distance & 0x4000) >> 11) as u8;
+        if len <= 7 {
+            output.write_u8(16 | high_bit | len as u8);
+        } else {
+            output.write_u8(16 | high_bit);
+            write_length_extension(len - 7, output);
+        }
+        distance & 0x3fff
+    };
+    let pos = output.len();
+    output.write_bytes(&[(distance << 2) as u8, (distance >> 6) as u8]);
+    pos
+}
@@ -0,0 +1,207 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use crate::buffer::Buffer;
+use crate::error::OrcError;
+use super::CompressionEnum;
+use super::common::CompressionTrait;
+
+type Job = Box<dyn FnOnce() + Send>;
+// A compressed block, or the original block and the compression error
+type BlockResult = Result<CompressedBlock, (CompressedBlock, OrcError)>;
+
+/// A fixed set of threads compressing the blocks of all the streams of a writer.
+pub(crate) struct CompressionPool {
+    sender: Option<Mutex<Sender<Job>>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+/// The contents of a compression block as written to the stream: either the compressed data, or the
+/// original data if compression did not make it smaller.
+pub(crate) struct CompressedBlock {
+    pub data: Buffer,
+    pub is_original: bool,
+}
+
+impl CompressedBlock {
+    fn new(original: Buffer, compressed: Buffer) -> Self {
+        if compressed.len() > original.len() {
+            CompressedBlock { data: original, is_original: true }
+        } else {
+            CompressedBlock { data: compressed, is_original: false }
+        }
+    }
+}
+
+impl CompressionPool {
+    pub fn new(num_threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let threads = (0..num_threads).map(|_| {
+            let receiver = receiver.clone();
+            thread::spawn(move || loop {
+                let job = receiver.lock().unwrap().recv();
+                match job {
+                    Ok(job) => job(),
+                    // The pool was dropped
+                    Err(_) => break,
+                }
+            })
+        }).collect();
+        CompressionPool {
+            sender: Some(Mutex::new(sender)),
+            threads,
+        }
+    }
+
+    fn submit(&self, codec: &CompressionEnum, block: Buffer) -> Receiver<BlockResult> {
+        let (sender, receiver) = mpsc::channel();
+        let codec = codec.clone();
+        let job = Box::new(move || {
+            let mut compressed = Buffer::new();
+            let result = match codec.compressor().unwrap().compress(&block, &mut compressed) {
+                Ok(()) => Ok(CompressedBlock::new(block, compressed)),
+                // The block is still stored, uncompressed, to keep the stream consistent
+                Err(e) => Err((CompressedBlock { data: block, is_original: true }, e)),
+            };
+            // The stream may have been dropped without waiting for its blocks
+            let _ = sender.send(result);
+        });
+        self.sender.as_ref().unwrap().lock().unwrap().send(job).unwrap();
+        receiver
+    }
+}
+
+impl Drop for CompressionPool {
+    fn drop(&mut self) {
+        // Closing the channel makes the threads exit once they are done with the queued jobs
+        self.sender.take();
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The blocks of a stream which were handed to a `CompressionPool`.
+pub(crate) struct PendingBlocks {
+    pool: Arc<CompressionPool>,
+    codec: CompressionEnum,
+    // Blocks still being compressed, in order, with their uncompressed length
+    pending: VecDeque<(usize, Receiver<BlockResult>)>,
+    // Total uncompressed length of the pending blocks
+    pending_len: usize,
+    // Blocks compressed but not yet appended to the output of the stream, in order
+    done: Vec<CompressedBlock>,
+    // Total length of the done blocks, including their headers
+    done_len: usize,
+    // First error in compressing a block
+    error: Option<OrcError>,
+}
+
+impl PendingBlocks {
+    pub fn new(pool: Arc<CompressionPool>, codec: CompressionEnum) -> Self {
+        PendingBlocks {
+            pool,
+            codec,
+            pending: VecDeque::new(),
+            pending_len: 0,
+            done: Vec::new(),
+            done_len: 0,
+            error: None,
+        }
+    }
+
+    pub fn submit(&mut self, block: Buffer) {
+        self.pending_len += block.len();
+        self.pending.push_back((block.len(), self.pool.submit(&self.codec, block)));
+    }
+
+    /// The number of blocks submitted and not yet taken by `take_done`.
+    pub fn len(&self) -> usize {
+        self.done.len() + self.pending.len()
+    }
+
+    /// Waits for the first `num_blocks` blocks not yet taken by `take_done` to be compressed,
+    /// returning the length they take in the stream (including the headers).
+    pub fn wait_for(&mut self, num_blocks: usize) -> usize {
+        while self.done.len() < num_blocks && self.receive_next() {}
+        self.done[..num_blocks.min(self.done.len())].iter().map(|b| 3 + b.data.len()).sum()
+    }
+
+    /// Waits for the next pending block to be compressed, if any, returning whether there was one.
+    fn receive_next(&mut self) -> bool {
+        let (len, receiver) = match self.pending.pop_front() {
+            Some(x) => x,
+            None => return false,
+        };
+        let block = match receiver.recv().expect("compression thread panicked") {
+            Ok(block) => block,
+            Err((block, e)) => {
+                self.error.get_or_insert(e);
+                block
+            }
+        };
+        self.pending_len -= len;
+        self.done_len += 3 + block.data.len();
+        self.done.push(block);
+        true
+    }
+
+    /// Waits for all the pending blocks, and returns them along with the previously compressed ones,
+    /// and the first error in compressing them.
+    pub fn take_done(&mut self) -> (Vec<CompressedBlock>, Option<OrcError>) {
+        while self.receive_next() {}
+        self.done_len = 0;
+        (std::mem::take(&mut self.done), self.error.take())
+    }
+
+    /// The approximate length the blocks will take in the stream.
+    pub fn estimated_size(&self) -> usize {
+        self.pending_len + self.done_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::compression::{CompressionStream, CompressionStreamPosition, ZstdCompression};
+
+    fn write_row_groups(stream: &mut CompressionStream) -> Vec<CompressionStreamPosition> {
+        let mut positions = Vec::new();
+        for i in 0..10u32 {
+            positions.push(stream.position());
+            for j in 0..700u32 {
+                stream.write_bytes(&(i * j).to_le_bytes());
+            }
+        }
+        positions
+    }
+
+    #[test]
+    fn test_blocks_stay_queued() {
+        // Keeps the only thread of the pool busy until the positions are taken
+        let pool = Arc::new(CompressionPool::new(1));
+        let (release, blocked) = mpsc::channel::<()>();
+        pool.sender.as_ref().unwrap().lock().unwrap().send(Box::new(move || { let _ = blocked.recv(); })).unwrap();
+        let compression = ZstdCompression::new().with_block_size(1000).unwrap().build();
+        let mut stream = CompressionStream::new(&compression.clone().with_pool(pool));
+        let positions = write_row_groups(&mut stream);
+        {
+            let pending = stream.pending.as_ref().unwrap().borrow();
+            assert_eq!(pending.done.len(), 0);
+            assert_eq!(pending.pending.len(), 27);
+        }
+        release.send(()).unwrap();
+
+        let mut expected_stream = CompressionStream::new(&compression);
+        let expected_positions = write_row_groups(&mut expected_stream);
+        for (position, expected) in positions.iter().zip(&expected_positions) {
+            let (mut out, mut expected_out) = (Vec::new(), Vec::new());
+            position.record(&stream, &mut out);
+            expected.record(&expected_stream, &mut expected_out);
+            assert_eq!(out, expected_out);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use snap;
+use crate::error::OrcError;
 use crate::protos::orc_proto;
-use super::common::{CompressionTrait, Compressor, MAX_BLOCK_SIZE};
+use super::common::{CompressionTrait, Compressor, check_block_size};
 use crate::buffer::Buffer;
 
 #[derive(Clone)]
@@ -15,10 +16,10 @@ impl SnappyCompression {
         }
     }
 
-    pub fn with_block_size(mut self, block_size: usize) -> Self {
-        assert!(block_size <= MAX_BLOCK_SIZE);
+    pub fn with_block_size(mut self, block_size: usize) -> Result<Self, OrcError> {
+        check_block_size(block_size)?;
         self.block_size = block_size;
-        self
+        Ok(self)
     } 
 }
 
@@ -43,11 +44,13 @@ struct SnappyCompressor {
 }
 
 impl Compressor for SnappyCompressor {
-    fn compress(&mut self, input: &[u8], output: &mut Buffer) {
+    fn compress(&mut self, input: &[u8], output: &mut Buffer) -> Result<(), OrcError> {
         let current_len = output.len();
         let max_additional_len = snap::max_compress_len(input.len());
         output.ensure_size(current_len + max_additional_len);
-        let additional_len = self.encoder.compress(input, &mut output[current_len..]).unwrap();
+        let additional_len = self.encoder.compress(input, &mut output[current_len..])
+            .map_err(|e| OrcError::Compression(e.to_string()))?;
         output.resize(current_len + additional_len);
+        Ok(())
     }
 }
@@ -0,0 +1,73 @@
+use std::io::Write;
+use flate2::write::DeflateEncoder;
+
+use crate::error::OrcError;
+use crate::protos::orc_proto;
+use super::common::{CompressionTrait, Compressor, check_block_size};
+use crate::buffer::Buffer;
+
+#[derive(Clone)]
+pub struct ZlibCompression {
+    block_size: usize,
+    compression_level: u32,
+}
+
+impl ZlibCompression {
+    pub fn new() -> Self {
+        Self {
+            block_size: 262144,
+            compression_level: 6,
+        }
+    }
+
+    pub fn with_block_size(mut self, block_size: usize) -> Result<Self, OrcError> {
+        check_block_size(block_size)?;
+        self.block_size = block_size;
+        Ok(self)
+    }
+
+    pub fn with_compression_level(mut self, compression_level: u32) -> Result<Self, OrcError> {
+        if compression_level > 9 {
+            return Err(OrcError::InvalidConfig(
+                format!("zlib compression level {} is not between 0 and 9", compression_level)));
+        }
+        self.compression_level = compression_level;
+        Ok(self)
+    }
+}
+
+impl Default for ZlibCompression {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompressionTrait for ZlibCompression {
+    fn kind(&self) -> orc_proto::CompressionKind {
+        orc_proto::CompressionKind::ZLIB
+    }
+
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn compressor(&self) -> Option<Box<dyn Compressor>> {
+        Some(Box::new(ZlibCompressor {
+            compression_level: self.compression_level
+        }))
+    }
+}
+
+struct ZlibCompressor {
+    compression_level: u32,
+}
+
+impl Compressor for ZlibCompressor {
+    fn compress(&mut self, input: &[u8], output: &mut Buffer) -> Result<(), OrcError> {
+        // ORC uses raw deflate, i.e. without the zlib header and checksum.
+        let mut encoder = DeflateEncoder::new(output, flate2::Compression::new(self.compression_level));
+        encoder.write_all(input).map_err(|e| OrcError::Compression(e.to_string()))?;
+        encoder.finish().map_err(|e| OrcError::Compression(e.to_string()))?;
+        Ok(())
+    }
+}
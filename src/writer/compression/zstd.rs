@@ -1,8 +1,9 @@
 use zstd;
 use zstd_safe;
 
+use crate::error::OrcError;
 use crate::protos::orc_proto;
-use super::common::{CompressionTrait, Compressor, MAX_BLOCK_SIZE};
+use super::common::{CompressionTrait, Compressor, check_block_size};
 use crate::buffer::Buffer;
 
 #[derive(Clone)]
@@ -19,16 +20,19 @@ impl ZstdCompression {
         }
     }
 
-    pub fn with_block_size(mut self, block_size: usize) -> Self {
-        assert!(block_size <= MAX_BLOCK_SIZE);
+    pub fn with_block_size(mut self, block_size: usize) -> Result<Self, OrcError> {
+        check_block_size(block_size)?;
         self.block_size = block_size;
-        self
+        Ok(self)
     }
 
-    pub fn with_compression_level(mut self, compression_level: i32) -> Self {
-        assert!(compression_level >= 1 && compression_level <= 22);
+    pub fn with_compression_level(mut self, compression_level: i32) -> Result<Self, OrcError> {
+        if !(1..=22).contains(&compression_level) {
+            return Err(OrcError::InvalidConfig(
+                format!("zstd compression level {} is not between 1 and 22", compression_level)));
+        }
         self.compression_level = compression_level;
-        self
+        Ok(self)
     }
 }
 
@@ -53,7 +57,7 @@ struct ZstdCompressor {
 }
 
 impl Compressor for ZstdCompressor {
-    fn compress(&mut self, input: &[u8], output: &mut Buffer) {
+    fn compress(&mut self, input: &[u8], output: &mut Buffer) -> Result<(), OrcError> {
         let current_len = output.len();
         let max_additional_len = zstd_safe::compress_bound(input.len());
         output.ensure_size(current_len + max_additional_len);
@@ -62,7 +66,8 @@ impl Compressor for ZstdCompressor {
             &mut output[current_len..],
             self.compression_level,
         )
-        .unwrap();
+        .map_err(|e| OrcError::Compression(e.to_string()))?;
         output.resize(current_len + additional_len);
+        Ok(())
     }
 }
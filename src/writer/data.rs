@@ -1,5 +1,6 @@
 use std::io::{Write, Result};
 
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::schema::Schema;
 use super::Config;
@@ -9,6 +10,7 @@ use crate::writer::count_write::CountWrite;
 
 pub use common::{GenericData, BaseData};
 pub use boolean::BooleanData;
+pub use byte::ByteData;
 pub use long::LongData;
 pub use float::FloatData;
 pub use double::DoubleData;
@@ -23,6 +25,7 @@ pub use union::UnionData;
 
 mod common;
 mod boolean;
+mod byte;
 mod long;
 mod float;
 mod double;
@@ -37,6 +40,7 @@ mod union;
 
 pub enum Data {
     Boolean(BooleanData),
+    Byte(ByteData),
     Long(LongData),
     Float(FloatData),
     Double(DoubleData),
@@ -54,6 +58,7 @@ impl Data {
     pub(crate) fn new(schema: &Schema, config: &Config, column_id: &mut u32) -> Self {
         match schema {
             Schema::Boolean => Data::Boolean(BooleanData::new(config, column_id)),
+            Schema::Byte => Data::Byte(ByteData::new(config, column_id)),
             Schema::Short | Schema::Int | Schema::Long | Schema::Date => 
                 Data::Long(LongData::new(schema, config, column_id)),
             Schema::Float => Data::Float(FloatData::new(config, column_id)),
@@ -70,52 +75,180 @@ impl Data {
         }
     }
 
+    /// The name of the variant, for error messages.
+    fn type_name(&self) -> &'static str {
+        match self {
+            Data::Boolean(_) => "Boolean",
+            Data::Byte(_) => "Byte",
+            Data::Long(_) => "Long",
+            Data::Float(_) => "Float",
+            Data::Double(_) => "Double",
+            Data::Timestamp(_) => "Timestamp",
+            Data::Decimal(_) => "Decimal",
+            Data::String(_) => "String",
+            Data::Binary(_) => "Binary",
+            Data::Struct(_) => "Struct",
+            Data::List(_) => "List",
+            Data::Map(_) => "Map",
+            Data::Union(_) => "Union",
+        }
+    }
+
+    fn config(&self) -> &Config {
+        match self {
+            Data::Boolean(x) => &x.config,
+            Data::Byte(x) => &x.config,
+            Data::Long(x) => &x.config,
+            Data::Float(x) => &x.config,
+            Data::Double(x) => &x.config,
+            Data::Timestamp(x) => &x.config,
+            Data::Decimal(x) => &x.config,
+            Data::String(x) => &x.config,
+            Data::Binary(x) => &x.config,
+            Data::Struct(x) => &x.config,
+            Data::List(x) => &x.config,
+            Data::Map(x) => &x.config,
+            Data::Union(x) => &x.config,
+        }
+    }
+
+    fn schema_mismatch(&self, expected: &'static str) -> OrcError {
+        OrcError::SchemaMismatch {
+            column_id: self.column_id(),
+            path: self.config().column_path(self.column_id()),
+            expected,
+            actual: self.type_name(),
+        }
+    }
+
+    /// The data of a Boolean column, or an error if the column has another type.
+    pub fn as_boolean(&mut self) -> std::result::Result<&mut BooleanData, OrcError> {
+        if let Data::Boolean(x) = self { Ok(x) } else { Err(self.schema_mismatch("Boolean")) }
+    }
+
+    /// Like `as_boolean`, but panics if the column has another type.
     pub fn unwrap_boolean(&mut self) -> &mut BooleanData {
-        if let Data::Boolean(x) = self { x } else { panic!("unwrap_boolean called on incorrect type of data"); }
+        self.as_boolean().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The data of a Byte column, or an error if the column has another type.
+    pub fn as_byte(&mut self) -> std::result::Result<&mut ByteData, OrcError> {
+        if let Data::Byte(x) = self { Ok(x) } else { Err(self.schema_mismatch("Byte")) }
+    }
+
+    /// Like `as_byte`, but panics if the column has another type.
+    pub fn unwrap_byte(&mut self) -> &mut ByteData {
+        self.as_byte().unwrap_or_else(|e| panic!("{}", e))
     }
 
+    /// The data of a Long column, or an error if the column has another type.
+    pub fn as_long(&mut self) -> std::result::Result<&mut LongData, OrcError> {
+        if let Data::Long(x) = self { Ok(x) } else { Err(self.schema_mismatch("Long")) }
+    }
+
+    /// Like `as_long`, but panics if the column has another type.
     pub fn unwrap_long(&mut self) -> &mut LongData {
-        if let Data::Long(x) = self { x } else { panic!("unwrap_long called on incorrect type of data"); }
+        self.as_long().unwrap_or_else(|e| panic!("{}", e))
     }
 
+    /// The data of a Float column, or an error if the column has another type.
+    pub fn as_float(&mut self) -> std::result::Result<&mut FloatData, OrcError> {
+        if let Data::Float(x) = self { Ok(x) } else { Err(self.schema_mismatch("Float")) }
+    }
+
+    /// Like `as_float`, but panics if the column has another type.
     pub fn unwrap_float(&mut self) -> &mut FloatData {
-        if let Data::Float(x) = self { x } else { panic!("unwrap_float called on incorrect type of data"); }
+        self.as_float().unwrap_or_else(|e| panic!("{}", e))
     }
 
+    /// The data of a Double column, or an error if the column has another type.
+    pub fn as_double(&mut self) -> std::result::Result<&mut DoubleData, OrcError> {
+        if let Data::Double(x) = self { Ok(x) } else { Err(self.schema_mismatch("Double")) }
+    }
+
+    /// Like `as_double`, but panics if the column has another type.
     pub fn unwrap_double(&mut self) -> &mut DoubleData {
-        if let Data::Double(x) = self { x } else { panic!("unwrap_double called on incorrect type of data"); }
+        self.as_double().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The data of a Timestamp column, or an error if the column has another type.
+    pub fn as_timestamp(&mut self) -> std::result::Result<&mut TimestampData, OrcError> {
+        if let Data::Timestamp(x) = self { Ok(x) } else { Err(self.schema_mismatch("Timestamp")) }
     }
 
+    /// Like `as_timestamp`, but panics if the column has another type.
     pub fn unwrap_timestamp(&mut self) -> &mut TimestampData {
-        if let Data::Timestamp(x) = self { x } else { panic!("unwrap_timestamp called on incorrect type of data"); }
+        self.as_timestamp().unwrap_or_else(|e| panic!("{}", e))
     }
 
+    /// The data of a Decimal column, or an error if the column has another type.
+    pub fn as_decimal(&mut self) -> std::result::Result<&mut DecimalData, OrcError> {
+        if let Data::Decimal(x) = self { Ok(x) } else { Err(self.schema_mismatch("Decimal")) }
+    }
+
+    /// Like `as_decimal`, but panics if the column has another type.
     pub fn unwrap_decimal(&mut self) -> &mut DecimalData {
-        if let Data::Decimal(x) = self { x } else { panic!("unwrap_decimal called on incorrect type of data"); }
+        self.as_decimal().unwrap_or_else(|e| panic!("{}", e))
     }
 
+    /// The data of a String column, or an error if the column has another type.
+    pub fn as_string(&mut self) -> std::result::Result<&mut StringData, OrcError> {
+        if let Data::String(x) = self { Ok(x) } else { Err(self.schema_mismatch("String")) }
+    }
+
+    /// Like `as_string`, but panics if the column has another type.
     pub fn unwrap_string(&mut self) -> &mut StringData {
-        if let Data::String(x) = self { x } else { panic!("unwrap_string called on incorrect type of data"); }
+        self.as_string().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The data of a Binary column, or an error if the column has another type.
+    pub fn as_binary(&mut self) -> std::result::Result<&mut BinaryData, OrcError> {
+        if let Data::Binary(x) = self { Ok(x) } else { Err(self.schema_mismatch("Binary")) }
     }
 
+    /// Like `as_binary`, but panics if the column has another type.
     pub fn unwrap_binary(&mut self) -> &mut BinaryData {
-        if let Data::Binary(x) = self { x } else { panic!("unwrap_binary called on incorrect type of data"); }
+        self.as_binary().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The data of a Struct column, or an error if the column has another type.
+    pub fn as_struct(&mut self) -> std::result::Result<&mut StructData, OrcError> {
+        if let Data::Struct(x) = self { Ok(x) } else { Err(self.schema_mismatch("Struct")) }
     }
 
+    /// Like `as_struct`, but panics if the column has another type.
     pub fn unwrap_struct(&mut self) -> &mut StructData {
-        if let Data::Struct(x) = self { x } else { panic!("unwrap_struct called on incorrect type of data"); }
+        self.as_struct().unwrap_or_else(|e| panic!("{}", e))
     }
 
+    /// The data of a List column, or an error if the column has another type.
+    pub fn as_list(&mut self) -> std::result::Result<&mut ListData, OrcError> {
+        if let Data::List(x) = self { Ok(x) } else { Err(self.schema_mismatch("List")) }
+    }
+
+    /// Like `as_list`, but panics if the column has another type.
     pub fn unwrap_list(&mut self) -> &mut ListData {
-        if let Data::List(x) = self { x } else { panic!("unwrap_list called on incorrect type of data"); }
+        self.as_list().unwrap_or_else(|e| panic!("{}", e))
     }
 
+    /// The data of a Map column, or an error if the column has another type.
+    pub fn as_map(&mut self) -> std::result::Result<&mut MapData, OrcError> {
+        if let Data::Map(x) = self { Ok(x) } else { Err(self.schema_mismatch("Map")) }
+    }
+
+    /// Like `as_map`, but panics if the column has another type.
     pub fn unwrap_map(&mut self) -> &mut MapData {
-        if let Data::Map(x) = self { x } else { panic!("unwrap_map called on incorrect type of data"); }
+        self.as_map().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// The data of a Union column, or an error if the column has another type.
+    pub fn as_union(&mut self) -> std::result::Result<&mut UnionData, OrcError> {
+        if let Data::Union(x) = self { Ok(x) } else { Err(self.schema_mismatch("Union")) }
     }
 
+    /// Like `as_union`, but panics if the column has another type.
     pub fn unwrap_union(&mut self) -> &mut UnionData {
-        if let Data::Union(x) = self { x } else { panic!("unwrap_union called on incorrect type of data"); }
+        self.as_union().unwrap_or_else(|e| panic!("{}", e))
     }
 }
 
@@ -123,6 +256,7 @@ impl GenericData for Data {
     fn write_null(&mut self) {
         match self {
             Data::Boolean(x) => x.write_null(),
+            Data::Byte(x) => x.write_null(),
             Data::Long(x) => x.write_null(),
             Data::Float(x) => x.write_null(),
             Data::Double(x) => x.write_null(),
@@ -144,6 +278,7 @@ impl BaseData for Data {
     fn column_id(&self) -> u32 {
         match self {
             Data::Boolean(x) => x.column_id(),
+            Data::Byte(x) => x.column_id(),
             Data::Long(x) => x.column_id(),
             Data::Float(x) => x.column_id(),
             Data::Double(x) => x.column_id(),
@@ -158,9 +293,28 @@ impl BaseData for Data {
         }
     }
 
+    fn finish_encoding(&mut self) {
+        match self {
+            Data::Boolean(x) => x.finish_encoding(),
+            Data::Byte(x) => x.finish_encoding(),
+            Data::Long(x) => x.finish_encoding(),
+            Data::Float(x) => x.finish_encoding(),
+            Data::Double(x) => x.finish_encoding(),
+            Data::Timestamp(x) => x.finish_encoding(),
+            Data::Decimal(x) => x.finish_encoding(),
+            Data::String(x) => x.finish_encoding(),
+            Data::Binary(x) => x.finish_encoding(),
+            Data::Struct(x) => x.finish_encoding(),
+            Data::List(x) => x.finish_encoding(),
+            Data::Map(x) => x.finish_encoding(),
+            Data::Union(x) => x.finish_encoding(),
+        }
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         match self {
             Data::Boolean(x) => x.write_index_streams(out, stream_infos_out),
+            Data::Byte(x) => x.write_index_streams(out, stream_infos_out),
             Data::Long(x) => x.write_index_streams(out, stream_infos_out),
             Data::Float(x) => x.write_index_streams(out, stream_infos_out),
             Data::Double(x) => x.write_index_streams(out, stream_infos_out),
@@ -178,6 +332,7 @@ impl BaseData for Data {
     fn write_data_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         match self {
             Data::Boolean(x) => x.write_data_streams(out, stream_infos_out),
+            Data::Byte(x) => x.write_data_streams(out, stream_infos_out),
             Data::Long(x) => x.write_data_streams(out, stream_infos_out),
             Data::Float(x) => x.write_data_streams(out, stream_infos_out),
             Data::Double(x) => x.write_data_streams(out, stream_infos_out),
@@ -195,6 +350,7 @@ impl BaseData for Data {
     fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>) {
         match self {
             Data::Boolean(x) => x.column_encodings(out),
+            Data::Byte(x) => x.column_encodings(out),
             Data::Long(x) => x.column_encodings(out),
             Data::Float(x) => x.column_encodings(out),
             Data::Double(x) => x.column_encodings(out),
@@ -212,6 +368,7 @@ impl BaseData for Data {
     fn statistics(&self, out: &mut Vec<Statistics>) {
         match self {
             Data::Boolean(x) => x.statistics(out),
+            Data::Byte(x) => x.statistics(out),
             Data::Long(x) => x.statistics(out),
             Data::Float(x) => x.statistics(out),
             Data::Double(x) => x.statistics(out),
@@ -226,9 +383,10 @@ impl BaseData for Data {
         }
     }
 
-    fn verify_row_count(&self, row_count: u64) {
+    fn verify_row_count(&mut self, row_count: u64) -> std::result::Result<(), OrcError> {
         match self {
             Data::Boolean(x) => x.verify_row_count(row_count),
+            Data::Byte(x) => x.verify_row_count(row_count),
             Data::Long(x) => x.verify_row_count(row_count),
             Data::Float(x) => x.verify_row_count(row_count),
             Data::Double(x) => x.verify_row_count(row_count),
@@ -246,6 +404,7 @@ impl BaseData for Data {
     fn estimated_size(&self) -> usize {
         match self {
             Data::Boolean(x) => x.estimated_size(),
+            Data::Byte(x) => x.estimated_size(),
             Data::Long(x) => x.estimated_size(),
             Data::Float(x) => x.estimated_size(),
             Data::Double(x) => x.estimated_size(),
@@ -1,13 +1,15 @@
 use std::io::{Write, Result};
 
+use crate::bloom_filter::{BloomFilter, BLOOM_ENCODING_UTF8_UTC};
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::writer::Config;
 use crate::writer::count_write::CountWrite;
 use crate::writer::compression::{CompressionStream, CompressionStreamPosition};
-use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, UnsignedIntRLEv1, IntRLEv1Position};
+use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, UnsignedIntRLE, IntRLEPosition};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, BinaryStatistics};
-use crate::writer::data::common::{BaseData, GenericData, write_index};
+use crate::writer::data::common::{BaseData, GenericData, write_index, write_bloom_filters, check_row_count};
 
 
 pub struct BinaryData {
@@ -17,34 +19,37 @@ pub struct BinaryData {
     row_group_stats: BinaryStatistics,
     row_group_position: BinaryDataPosition,
     row_index_entries: Vec<BinaryRowIndexEntry>,
-    config: Config,
+    // Bloom filter of the current row group, if the column has bloom filters
+    bloom_filter: Option<BloomFilter>,
+    pub(crate) config: Config,
 }
 
 struct BinaryDataStreams {
     present: BooleanRLE,
     data: CompressionStream,
-    lengths: UnsignedIntRLEv1,
+    lengths: UnsignedIntRLE,
 }
 
 #[derive(Copy, Clone)]
 struct BinaryDataPosition {
     present: BooleanRLEPosition,
     data: CompressionStreamPosition,
-    lengths: IntRLEv1Position,
+    lengths: IntRLEPosition,
 }
 
 struct BinaryRowIndexEntry {
     position: BinaryDataPosition,
     stats: BinaryStatistics,
+    bloom_filter: Option<BloomFilter>,
 }
 
 impl BinaryDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
+    pub fn record(&self, streams: &BinaryDataStreams, include_present: bool, out: &mut Vec<u64>) {
         if include_present {
-            self.present.record(out);
+            self.present.record(streams.present.sink(), out);
         }
-        self.data.record(out);
-        self.lengths.record(out);
+        self.data.record(&streams.data, out);
+        self.lengths.record(streams.lengths.sink(), out);
     }
 }
 
@@ -65,7 +70,7 @@ impl BinaryData {
         let streams = BinaryDataStreams {
             present: BooleanRLE::new(&config.compression),
             data: CompressionStream::new(&config.compression),
-            lengths: UnsignedIntRLEv1::new(&config.compression),
+            lengths: UnsignedIntRLE::new(&config.compression, config.version),
         };
         Self {
             column_id: cid,
@@ -73,6 +78,7 @@ impl BinaryData {
             row_group_stats: BinaryStatistics::new(),
             row_group_position: streams.position(),
             row_index_entries: Vec::new(),
+            bloom_filter: config.bloom_filter(cid),
             config: config.clone(),
             streams,
         }
@@ -82,6 +88,9 @@ impl BinaryData {
         self.streams.present.write(true);
         self.streams.data.write_bytes(x);
         self.streams.lengths.write(x.len() as u64);
+        if let Some(bloom_filter) = &mut self.bloom_filter {
+            bloom_filter.add_bytes(x);
+        }
         self.row_group_stats.update(x);
         self.check_row_group();
     }
@@ -95,9 +104,11 @@ impl BinaryData {
     fn finish_row_group(&mut self) {
         if self.row_group_stats.num_values > 0 {
             self.stripe_stats.merge(&self.row_group_stats);
+            let bloom_filter = std::mem::replace(&mut self.bloom_filter, self.config.bloom_filter(self.column_id));
             self.row_index_entries.push(BinaryRowIndexEntry {
                 position: self.row_group_position,
                 stats: self.row_group_stats,
+                bloom_filter,
             });
             self.row_group_position = self.streams.position();
             self.row_group_stats = BinaryStatistics::new();
@@ -116,18 +127,29 @@ impl GenericData for BinaryData {
 impl BaseData for BinaryData {
     fn column_id(&self) -> u32 { self.column_id }
 
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        self.streams.data.compress_remaining();
+        self.streams.lengths.finish_encoding();
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         self.finish_row_group();
         let mut row_index_entries: Vec<orc_proto::RowIndexEntry> = Vec::new();
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
             row_index_entry.set_positions(positions);
             row_index_entry.set_statistics(Statistics::Binary(entry.stats).to_proto());
             row_index_entries.push(row_index_entry);
         }
         write_index(row_index_entries, self.column_id(), &self.config.compression, out, stream_infos_out)?;
+        write_bloom_filters(self.row_index_entries.iter().filter_map(|e| e.bloom_filter.as_ref()), self.column_id,
+            &self.config.compression, out, stream_infos_out)?;
         Ok(())
     }
 
@@ -166,7 +188,10 @@ impl BaseData for BinaryData {
 
     fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>) {
         let mut encoding = orc_proto::ColumnEncoding::new();
-        encoding.set_kind(orc_proto::ColumnEncoding_Kind::DIRECT);
+        encoding.set_kind(self.config.version.direct_encoding());
+        if self.bloom_filter.is_some() {
+            encoding.set_bloomEncoding(BLOOM_ENCODING_UTF8_UTC);
+        }
         out.push(encoding);
     }
 
@@ -179,11 +204,9 @@ impl BaseData for BinaryData {
             self.streams.lengths.estimated_size()
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
         let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type Binary), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
-        }
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
+        Ok(())
     }
 }
@@ -0,0 +1,198 @@
+use std::io::{Write, Result};
+
+use crate::bloom_filter::{BloomFilter, BLOOM_ENCODING_UTF8_UTC};
+use crate::error::OrcError;
+use crate::protos::orc_proto;
+use crate::writer::Config;
+use crate::writer::count_write::CountWrite;
+use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, ByteRLE, ByteRLEPosition};
+use crate::writer::stripe::StreamInfo;
+use crate::writer::statistics::{Statistics, BaseStatistics, LongStatistics};
+use crate::writer::data::common::{GenericData, BaseData, write_index, write_bloom_filters, check_row_count};
+
+
+/// Data of a Byte (tinyint) column, whose values are byte run-length encoded, with the integer
+/// statistics and bloom filters of the other integer columns.
+pub struct ByteData {
+    pub(crate) column_id: u32,
+    streams: ByteDataStreams,
+    stripe_stats: LongStatistics,
+    row_group_stats: LongStatistics,
+    row_group_position: ByteDataPosition,
+    row_index_entries: Vec<ByteRowIndexEntry>,
+    // Bloom filter of the current row group, if the column has bloom filters
+    bloom_filter: Option<BloomFilter>,
+    pub(crate) config: Config,
+}
+
+struct ByteDataStreams {
+    present: BooleanRLE,
+    data: ByteRLE,
+}
+
+#[derive(Copy, Clone)]
+struct ByteDataPosition {
+    present: BooleanRLEPosition,
+    data: ByteRLEPosition,
+}
+
+struct ByteRowIndexEntry {
+    position: ByteDataPosition,
+    stats: LongStatistics,
+    bloom_filter: Option<BloomFilter>,
+}
+
+impl ByteDataPosition {
+    pub fn record(&self, streams: &ByteDataStreams, include_present: bool, out: &mut Vec<u64>) {
+        if include_present {
+            self.present.record(streams.present.sink(), out);
+        }
+        self.data.record(streams.data.sink(), out);
+    }
+}
+
+impl ByteDataStreams {
+    pub fn position(&self) -> ByteDataPosition {
+        ByteDataPosition {
+            present: self.present.position(),
+            data: self.data.position(),
+        }
+    }
+}
+
+impl ByteData {
+    pub(crate) fn new(config: &Config, column_id: &mut u32) -> Self {
+        let cid = *column_id;
+        *column_id += 1;
+        let streams = ByteDataStreams {
+            present: BooleanRLE::new(&config.compression),
+            data: ByteRLE::new(&config.compression),
+        };
+        Self {
+            column_id: cid,
+            stripe_stats: LongStatistics::new(),
+            row_group_stats: LongStatistics::new(),
+            row_group_position: streams.position(),
+            row_index_entries: Vec::new(),
+            bloom_filter: config.bloom_filter(cid),
+            config: config.clone(),
+            streams,
+        }
+    }
+
+    fn check_row_group(&mut self) {
+        if self.row_group_stats.num_values == self.config.row_index_stride as u64 {
+            self.finish_row_group();
+        }
+    }
+
+    fn finish_row_group(&mut self) {
+        if self.row_group_stats.num_values > 0 {
+            self.stripe_stats.merge(&self.row_group_stats);
+            let bloom_filter = std::mem::replace(&mut self.bloom_filter, self.config.bloom_filter(self.column_id));
+            self.row_index_entries.push(ByteRowIndexEntry {
+                position: self.row_group_position,
+                stats: self.row_group_stats,
+                bloom_filter,
+            });
+            self.row_group_position = self.streams.position();
+            self.row_group_stats = LongStatistics::new();
+        }
+    }
+
+    pub fn write(&mut self, x: i8) {
+        self.streams.present.write(true);
+        self.streams.data.write(x as u8);
+        if let Some(bloom_filter) = &mut self.bloom_filter {
+            bloom_filter.add_long(x as i64);
+        }
+        self.row_group_stats.update(x as i64);
+        self.check_row_group();
+    }
+}
+
+impl GenericData for ByteData {
+    fn write_null(&mut self) {
+        self.streams.present.write(false);
+        self.row_group_stats.update_null();
+        self.check_row_group();
+    }
+}
+
+
+impl BaseData for ByteData {
+    fn column_id(&self) -> u32 { self.column_id }
+
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        self.streams.data.finish_encoding();
+    }
+
+    fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
+        self.finish_row_group();
+        let mut row_index_entries: Vec<orc_proto::RowIndexEntry> = Vec::new();
+        for entry in &self.row_index_entries {
+            let mut row_index_entry = orc_proto::RowIndexEntry::new();
+            let mut positions: Vec<u64> = Vec::new();
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
+            row_index_entry.set_positions(positions);
+            row_index_entry.set_statistics(Statistics::Long(entry.stats).to_proto());
+            row_index_entries.push(row_index_entry);
+        }
+        write_index(row_index_entries, self.column_id(), &self.config.compression, out, stream_infos_out)?;
+        write_bloom_filters(self.row_index_entries.iter().filter_map(|e| e.bloom_filter.as_ref()), self.column_id,
+            &self.config.compression, out, stream_infos_out)?;
+        Ok(())
+    }
+
+    fn write_data_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
+        if self.stripe_stats.has_null() {
+            let present_start_pos = out.pos();
+            self.streams.present.finish(out)?;
+            let present_len = (out.pos() - present_start_pos) as u64;
+            stream_infos_out.push(StreamInfo {
+                kind: orc_proto::Stream_Kind::PRESENT,
+                column_id: self.column_id,
+                length: present_len,
+            });
+        }
+
+        let data_start_pos = out.pos();
+        self.streams.data.finish(out)?;
+        let data_len = (out.pos() - data_start_pos) as u64;
+        stream_infos_out.push(StreamInfo {
+            kind: orc_proto::Stream_Kind::DATA,
+            column_id: self.column_id,
+            length: data_len,
+        });
+
+        Ok(())
+    }
+
+    fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>) {
+        // Byte run-length encoding is the same in all versions
+        let mut encoding = orc_proto::ColumnEncoding::new();
+        encoding.set_kind(orc_proto::ColumnEncoding_Kind::DIRECT);
+        if self.bloom_filter.is_some() {
+            encoding.set_bloomEncoding(BLOOM_ENCODING_UTF8_UTC);
+        }
+        out.push(encoding);
+    }
+
+    fn statistics(&self, out: &mut Vec<Statistics>) {
+        out.push(Statistics::Long(self.stripe_stats));
+    }
+
+    fn estimated_size(&self) -> usize {
+        self.streams.present.estimated_size() + self.streams.data.estimated_size()
+    }
+
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
+        let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
+        Ok(())
+    }
+}
@@ -1,11 +1,15 @@
 use std::io::{Write, Result};
 use protobuf::{CodedOutputStream, Message, RepeatedField};
 
+use crate::bloom_filter::BloomFilter;
+use crate::error::OrcError;
 use crate::protos::orc_proto;
+use crate::writer::Config;
 use crate::writer::count_write::CountWrite;
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::Statistics;
 use crate::writer::compression::{Compression, CompressionStream};
+use crate::writer::data::Data;
 
 pub trait GenericData {
     fn write_null(&mut self);
@@ -13,14 +17,58 @@ pub trait GenericData {
 
 pub trait BaseData: GenericData {
     fn column_id(&self) -> u32;
+    /// Finishes the encoders of the column and its descendants, and compresses their remaining
+    /// data, so that writing the streams only copies them out. Called before writing the streams.
+    fn finish_encoding(&mut self);
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()>;
     fn write_data_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()>;
     fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>);
     fn statistics(&self, out: &mut Vec<Statistics>);
-    fn verify_row_count(&self, num_rows: u64);
+    /// Checks that the number of values written to the column (and its descendants) matches the
+    /// number of rows, returning any error recorded while writing values.
+    fn verify_row_count(&mut self, num_rows: u64) -> std::result::Result<(), OrcError>;
     fn estimated_size(&self) -> usize;
 }
 
+pub(crate) fn check_row_count(config: &Config, column_id: u32, rows_written: u64, expected: u64)
+        -> std::result::Result<(), OrcError> {
+    if rows_written != expected {
+        return Err(OrcError::RowCountMismatch {
+            column_id,
+            path: config.column_path(column_id),
+            expected,
+            actual: rows_written,
+        });
+    }
+    Ok(())
+}
+
+/// Records that a value written to column `column_id` is out of range, unless an earlier error
+/// was recorded. The first error is returned by the next `verify_row_count`.
+pub(crate) fn record_out_of_range(config: &Config, column_id: u32, error: &mut Option<OrcError>,
+        message: impl FnOnce() -> String) {
+    if error.is_none() {
+        *error = Some(OrcError::ValueOutOfRange { column_id, path: config.column_path(column_id), message: message() });
+    }
+}
+
+/// Finishes the encoding of the children of a compound column.
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn finish_children_encoding(children: &mut [Data]) {
+    for child in children {
+        child.finish_encoding();
+    }
+}
+
+/// Finishes the encoding of the children of a compound column, in parallel. Each child compresses
+/// into the buffers of its own streams, which are then written out in order.
+#[cfg(feature = "rayon")]
+pub(crate) fn finish_children_encoding(children: &mut [Data]) {
+    use rayon::prelude::*;
+
+    children.par_iter_mut().for_each(|child| child.finish_encoding());
+}
+
 pub fn write_index<W: Write>(
         entries: Vec<orc_proto::RowIndexEntry>, 
         column_id: u32,
@@ -46,3 +94,35 @@ pub fn write_index<W: Write>(
 
     Ok(())
 }
+
+/// Writes the bloom filters of the row groups of a column. Nothing is written for columns without
+/// bloom filters.
+pub fn write_bloom_filters<'a, W: Write>(
+        bloom_filters: impl Iterator<Item = &'a BloomFilter>,
+        column_id: u32,
+        compression: &Compression,
+        out: &mut CountWrite<W>,
+        stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
+    let bloom_filters: Vec<orc_proto::BloomFilter> = bloom_filters.map(|b| b.to_proto()).collect();
+    if bloom_filters.is_empty() {
+        return Ok(());
+    }
+    let index_start_pos = out.pos();
+
+    let mut compression_stream = CompressionStream::new(compression);
+    let mut coded_out = CodedOutputStream::new(&mut compression_stream);
+    let mut bloom_filter_index = orc_proto::BloomFilterIndex::new();
+    bloom_filter_index.set_bloomFilter(RepeatedField::from_vec(bloom_filters));
+    bloom_filter_index.write_to(&mut coded_out)?;
+    coded_out.flush()?;
+    compression_stream.finish(out)?;
+
+    let index_len = (out.pos() - index_start_pos) as u64;
+    stream_infos_out.push(StreamInfo {
+        kind: orc_proto::Stream_Kind::BLOOM_FILTER_UTF8,
+        column_id,
+        length: index_len,
+    });
+
+    Ok(())
+}
@@ -1,14 +1,16 @@
 use std::io::{Write, Result};
 
+use crate::bloom_filter::{BloomFilter, BLOOM_ENCODING_UTF8_UTC};
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::schema::Schema;
-use crate::writer::Config;
+use crate::writer::{Config, OutOfRangePolicy};
 use crate::writer::count_write::CountWrite;
 use crate::writer::compression::{CompressionStream, CompressionStreamPosition};
-use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, SignedIntRLEv1, IntRLEv1Position, VarInt};
+use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, SignedIntRLE, IntRLEPosition, VarInt};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, DecimalStatistics};
-use crate::writer::data::common::{GenericData, BaseData, write_index};
+use crate::writer::data::common::{GenericData, BaseData, write_index, write_bloom_filters, check_row_count, record_out_of_range};
 
 
 pub struct DecimalData {
@@ -20,7 +22,11 @@ pub struct DecimalData {
     row_group_stats: DecimalStatistics,
     row_group_position: DecimalDataPosition,
     row_index_entries: Vec<DecimalRowIndexEntry>,
-    config: Config,
+    // Bloom filter of the current row group, if the column has bloom filters
+    bloom_filter: Option<BloomFilter>,
+    // First error in writing a value, reported by the next `verify_row_count`
+    error: Option<OrcError>,
+    pub(crate) config: Config,
 }
 
 struct DecimalDataStreams {
@@ -28,28 +34,29 @@ struct DecimalDataStreams {
     data: CompressionStream,
     // Only the constant 'scale' is repeatedly written to this. It is only included to satisfy 
     // ORC v1 spec (should no longer be needed in ORC v2).
-    secondary_scale: SignedIntRLEv1,  
+    secondary_scale: SignedIntRLE,  
 }
 
 #[derive(Copy, Clone)]
 struct DecimalDataPosition {
     present: BooleanRLEPosition,
     data: CompressionStreamPosition,
-    secondary_scale: IntRLEv1Position,
+    secondary_scale: IntRLEPosition,
 }
 
 struct DecimalRowIndexEntry {
     position: DecimalDataPosition,
     stats: DecimalStatistics,
+    bloom_filter: Option<BloomFilter>,
 }
 
 impl DecimalDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
+    pub fn record(&self, streams: &DecimalDataStreams, include_present: bool, out: &mut Vec<u64>) {
         if include_present {
-            self.present.record(out);
+            self.present.record(streams.present.sink(), out);
         }
-        self.data.record(out);
-        self.secondary_scale.record(out);
+        self.data.record(&streams.data, out);
+        self.secondary_scale.record(streams.secondary_scale.sink(), out);
     }
 }
 
@@ -71,7 +78,7 @@ impl DecimalData {
             let streams = DecimalDataStreams {
                 present: BooleanRLE::new(&config.compression),
                 data: CompressionStream::new(&config.compression),
-                secondary_scale: SignedIntRLEv1::new(&config.compression),
+                secondary_scale: SignedIntRLE::new(&config.compression, config.version),
             };
             Self {
                 column_id: cid,
@@ -81,6 +88,8 @@ impl DecimalData {
                 row_group_stats: DecimalStatistics::new(*scale),
                 row_group_position: streams.position(),
                 row_index_entries: Vec::new(),
+                bloom_filter: config.bloom_filter(cid),
+                error: None,
                 config: config.clone(),
                 streams,
             }
@@ -96,31 +105,66 @@ impl DecimalData {
     fn finish_row_group(&mut self) {
         if self.row_group_stats.num_values > 0 {
             self.stripe_stats.merge(&self.row_group_stats);
+            let bloom_filter = std::mem::replace(&mut self.bloom_filter, self.config.bloom_filter(self.column_id));
             self.row_index_entries.push(DecimalRowIndexEntry {
                 position: self.row_group_position,
                 stats: self.row_group_stats,
+                bloom_filter,
             });
             self.row_group_position = self.streams.position();
             self.row_group_stats = DecimalStatistics::new(self.scale);
         }
     }
 
+    /// Writes the unscaled value `x`, which must have at most `precision` digits, or is otherwise
+    /// handled according to `Config::with_out_of_range_policy`.
     pub fn write_i64(&mut self, x: i64) {
+        if !self.check_precision(x as i128) {
+            return;
+        }
         self.streams.present.write(true);
         x.write_varint(&mut self.streams.data);
         self.streams.secondary_scale.write(self.scale as i64);
+        if let Some(bloom_filter) = &mut self.bloom_filter {
+            bloom_filter.add_decimal(x as i128, self.scale);
+        }
         self.row_group_stats.update(x as i128);
         self.check_row_group();
     }
 
+    /// Writes the unscaled value `x`, which must have at most `precision` digits, or is otherwise
+    /// handled according to `Config::with_out_of_range_policy`.
     pub fn write_i128(&mut self, x: i128) {
+        if !self.check_precision(x) {
+            return;
+        }
         self.streams.present.write(true);
         x.write_varint(&mut self.streams.data);
         self.streams.secondary_scale.write(self.scale as i64);
+        if let Some(bloom_filter) = &mut self.bloom_filter {
+            bloom_filter.add_decimal(x, self.scale);
+        }
         self.row_group_stats.update(x);
         self.check_row_group();
     }
 
+    /// Whether `x` has at most `precision` digits. Otherwise, it's handled according to the
+    /// policy, and must not be written.
+    fn check_precision(&mut self, x: i128) -> bool {
+        if 10u128.checked_pow(self.precision).map_or(true, |bound| x.unsigned_abs() < bound) {
+            return true;
+        }
+        match self.config.out_of_range_policy {
+            OutOfRangePolicy::Error => {
+                let precision = self.precision;
+                record_out_of_range(&self.config, self.column_id, &mut self.error,
+                    || format!("unscaled value {} has more than {} digits", x, precision));
+            }
+            OutOfRangePolicy::Truncate | OutOfRangePolicy::Null => self.write_null(),
+        }
+        false
+    }
+
     pub fn precision(&self) -> u32 { self.precision }
 
     pub fn scale(&self) -> u32 { self.scale }
@@ -137,18 +181,29 @@ impl GenericData for DecimalData {
 impl BaseData for DecimalData {
     fn column_id(&self) -> u32 { self.column_id }
 
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        self.streams.data.compress_remaining();
+        self.streams.secondary_scale.finish_encoding();
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         self.finish_row_group();
         let mut row_index_entries: Vec<orc_proto::RowIndexEntry> = Vec::new();
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
             row_index_entry.set_positions(positions);
             row_index_entry.set_statistics(Statistics::Decimal(entry.stats).to_proto());
             row_index_entries.push(row_index_entry);
         }
         write_index(row_index_entries, self.column_id(), &self.config.compression, out, stream_infos_out)?;
+        write_bloom_filters(self.row_index_entries.iter().filter_map(|e| e.bloom_filter.as_ref()), self.column_id,
+            &self.config.compression, out, stream_infos_out)?;
         Ok(())
     }
 
@@ -187,7 +242,10 @@ impl BaseData for DecimalData {
 
     fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>) {
         let mut encoding = orc_proto::ColumnEncoding::new();
-        encoding.set_kind(orc_proto::ColumnEncoding_Kind::DIRECT);
+        encoding.set_kind(self.config.version.direct_encoding());
+        if self.bloom_filter.is_some() {
+            encoding.set_bloomEncoding(BLOOM_ENCODING_UTF8_UTC);
+        }
         out.push(encoding);
     }
 
@@ -200,11 +258,12 @@ impl BaseData for DecimalData {
             + self.streams.secondary_scale.estimated_size()
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
-        let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type Decimal), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
         }
+        let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
+        Ok(())
     }
 }
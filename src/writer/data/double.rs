@@ -1,6 +1,8 @@
 use std::io::{Write, Result};
 use byteorder::{LittleEndian, WriteBytesExt};
 
+use crate::bloom_filter::{BloomFilter, BLOOM_ENCODING_UTF8_UTC};
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::writer::Config;
 use crate::writer::count_write::CountWrite;
@@ -8,7 +10,7 @@ use crate::writer::compression::{CompressionStream, CompressionStreamPosition};
 use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, DoubleStatistics};
-use crate::writer::data::common::{GenericData, BaseData, write_index};
+use crate::writer::data::common::{GenericData, BaseData, write_index, write_bloom_filters, check_row_count};
 
 
 pub struct DoubleData {
@@ -18,7 +20,9 @@ pub struct DoubleData {
     row_group_stats: DoubleStatistics,
     row_group_position: DoubleDataPosition,
     row_index_entries: Vec<DoubleRowIndexEntry>,
-    config: Config,
+    // Bloom filter of the current row group, if the column has bloom filters
+    bloom_filter: Option<BloomFilter>,
+    pub(crate) config: Config,
 }
 
 struct DoubleDataStreams {
@@ -35,14 +39,15 @@ struct DoubleDataPosition {
 struct DoubleRowIndexEntry {
     position: DoubleDataPosition,
     stats: DoubleStatistics,
+    bloom_filter: Option<BloomFilter>,
 }
 
 impl DoubleDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
+    pub fn record(&self, streams: &DoubleDataStreams, include_present: bool, out: &mut Vec<u64>) {
         if include_present {
-            self.present.record(out);
+            self.present.record(streams.present.sink(), out);
         }
-        self.data.record(out);
+        self.data.record(&streams.data, out);
     }
 }
 
@@ -69,6 +74,7 @@ impl DoubleData {
             row_group_stats: DoubleStatistics::new(),
             row_group_position: streams.position(),
             row_index_entries: Vec::new(),
+            bloom_filter: config.bloom_filter(cid),
             config: config.clone(),
             streams,
         }
@@ -83,9 +89,11 @@ impl DoubleData {
     fn finish_row_group(&mut self) {
         if self.row_group_stats.num_values > 0 {
             self.stripe_stats.merge(&self.row_group_stats);
+            let bloom_filter = std::mem::replace(&mut self.bloom_filter, self.config.bloom_filter(self.column_id));
             self.row_index_entries.push(DoubleRowIndexEntry {
                 position: self.row_group_position,
                 stats: self.row_group_stats,
+                bloom_filter,
             });
             self.row_group_position = self.streams.position();
             self.row_group_stats = DoubleStatistics::new();
@@ -95,6 +103,9 @@ impl DoubleData {
     pub fn write(&mut self, x: f64) {
         self.streams.present.write(true);
         self.streams.data.write_f64::<LittleEndian>(x).unwrap();
+        if let Some(bloom_filter) = &mut self.bloom_filter {
+            bloom_filter.add_double(x);
+        }
         self.row_group_stats.update(x);
         self.check_row_group();
     }
@@ -111,18 +122,28 @@ impl GenericData for DoubleData {
 impl BaseData for DoubleData {
     fn column_id(&self) -> u32 { self.column_id }
 
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        self.streams.data.compress_remaining();
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         self.finish_row_group();
         let mut row_index_entries: Vec<orc_proto::RowIndexEntry> = Vec::new();
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
             row_index_entry.set_positions(positions);
             row_index_entry.set_statistics(Statistics::Double(entry.stats).to_proto());
             row_index_entries.push(row_index_entry);
         }
         write_index(row_index_entries, self.column_id(), &self.config.compression, out, stream_infos_out)?;
+        write_bloom_filters(self.row_index_entries.iter().filter_map(|e| e.bloom_filter.as_ref()), self.column_id,
+            &self.config.compression, out, stream_infos_out)?;
         Ok(())
     }
 
@@ -153,6 +174,9 @@ impl BaseData for DoubleData {
     fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>) {
         let mut encoding = orc_proto::ColumnEncoding::new();
         encoding.set_kind(orc_proto::ColumnEncoding_Kind::DIRECT);
+        if self.bloom_filter.is_some() {
+            encoding.set_bloomEncoding(BLOOM_ENCODING_UTF8_UTC);
+        }
         out.push(encoding);
     }
 
@@ -164,11 +188,9 @@ impl BaseData for DoubleData {
         self.streams.present.estimated_size() + self.streams.data.estimated_size()
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
         let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type Double), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
-        }
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
+        Ok(())
     }
 }
@@ -1,6 +1,7 @@
 use std::io::{Write, Result};
 use byteorder::{LittleEndian, WriteBytesExt};
 
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::writer::Config;
 use crate::writer::count_write::CountWrite;
@@ -8,7 +9,7 @@ use crate::writer::compression::{CompressionStream, CompressionStreamPosition};
 use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, DoubleStatistics};
-use crate::writer::data::common::{GenericData, BaseData, write_index};
+use crate::writer::data::common::{GenericData, BaseData, write_index, check_row_count};
 
 
 pub struct FloatData {
@@ -18,7 +19,7 @@ pub struct FloatData {
     row_group_stats: DoubleStatistics,
     row_group_position: FloatDataPosition,
     row_index_entries: Vec<FloatRowIndexEntry>,
-    config: Config,
+    pub(crate) config: Config,
 }
 
 struct FloatDataStreams {
@@ -38,11 +39,11 @@ struct FloatRowIndexEntry {
 }
 
 impl FloatDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
+    pub fn record(&self, streams: &FloatDataStreams, include_present: bool, out: &mut Vec<u64>) {
         if include_present {
-            self.present.record(out);
+            self.present.record(streams.present.sink(), out);
         }
-        self.data.record(out);
+        self.data.record(&streams.data, out);
     }
 }
 
@@ -111,13 +112,21 @@ impl GenericData for FloatData {
 impl BaseData for FloatData {
     fn column_id(&self) -> u32 { self.column_id }
 
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        self.streams.data.compress_remaining();
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         self.finish_row_group();
         let mut row_index_entries: Vec<orc_proto::RowIndexEntry> = Vec::new();
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
             row_index_entry.set_positions(positions);
             row_index_entry.set_statistics(Statistics::Double(entry.stats).to_proto());
             row_index_entries.push(row_index_entry);
@@ -164,11 +173,9 @@ impl BaseData for FloatData {
         self.streams.present.estimated_size() + self.streams.data.estimated_size()
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
         let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type Double), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
-        }
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
+        Ok(())
     }
 }
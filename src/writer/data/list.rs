@@ -1,13 +1,14 @@
 use std::io::{Write, Result};
 
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::schema::Schema;
 use crate::writer::Config;
 use crate::writer::count_write::CountWrite;
-use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, UnsignedIntRLEv1, IntRLEv1Position};
+use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, UnsignedIntRLE, IntRLEPosition};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, GenericStatistics};
-use crate::writer::data::common::{GenericData, BaseData, write_index};
+use crate::writer::data::common::{GenericData, BaseData, write_index, check_row_count};
 use crate::writer::data::Data;
 
 pub struct ListData {
@@ -19,18 +20,18 @@ pub struct ListData {
     row_group_stats: GenericStatistics,
     row_group_position: ListDataPosition,
     row_index_entries: Vec<ListRowIndexEntry>,
-    config: Config,
+    pub(crate) config: Config,
 }
 
 struct ListDataStreams {
     present: BooleanRLE,
-    lengths: UnsignedIntRLEv1,
+    lengths: UnsignedIntRLE,
 }
 
 #[derive(Copy, Clone)]
 struct ListDataPosition {
     present: BooleanRLEPosition,
-    lengths: IntRLEv1Position,
+    lengths: IntRLEPosition,
 }
 
 struct ListRowIndexEntry {
@@ -39,11 +40,11 @@ struct ListRowIndexEntry {
 }
 
 impl ListDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
+    pub fn record(&self, streams: &ListDataStreams, include_present: bool, out: &mut Vec<u64>) {
         if include_present {
-            self.present.record(out);
+            self.present.record(streams.present.sink(), out);
         }
-        self.lengths.record(out);
+        self.lengths.record(streams.lengths.sink(), out);
     }
 }
 
@@ -63,7 +64,7 @@ impl ListData {
         if let Schema::List(child_schema) = schema {
             let streams = ListDataStreams {
                 present: BooleanRLE::new(&config.compression),
-                lengths: UnsignedIntRLEv1::new(&config.compression),                
+                lengths: UnsignedIntRLE::new(&config.compression, config.version),                
             };
             Self {
                 column_id: cid,
@@ -123,6 +124,15 @@ impl GenericData for ListData {
 impl BaseData for ListData {
     fn column_id(&self) -> u32 { self.column_id }
 
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        self.streams.lengths.finish_encoding();
+        self.child.finish_encoding();
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         self.finish_row_group();
 
@@ -130,7 +140,7 @@ impl BaseData for ListData {
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
             row_index_entry.set_positions(positions);
             row_index_entry.set_statistics(Statistics::Generic(entry.stats).to_proto());
             row_index_entries.push(row_index_entry);
@@ -170,7 +180,7 @@ impl BaseData for ListData {
     fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>) {
         assert_eq!(out.len(), self.column_id as usize);
         let mut encoding = orc_proto::ColumnEncoding::new();
-        encoding.set_kind(orc_proto::ColumnEncoding_Kind::DIRECT);
+        encoding.set_kind(self.config.version.direct_encoding());
         out.push(encoding);
         self.child.column_encodings(out);
     }
@@ -181,13 +191,11 @@ impl BaseData for ListData {
         self.child.statistics(out);
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
         let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type List), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
-        }
-        self.child.verify_row_count(self.num_child_values);
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
+        self.child.verify_row_count(self.num_child_values)?;
+        Ok(())
     }
 
     fn estimated_size(&self) -> usize {
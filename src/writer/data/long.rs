@@ -1,13 +1,15 @@
 use std::io::{Write, Result};
 
+use crate::bloom_filter::{BloomFilter, BLOOM_ENCODING_UTF8_UTC};
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::schema::Schema;
-use crate::writer::Config;
+use crate::writer::{Config, OutOfRangePolicy};
 use crate::writer::count_write::CountWrite;
-use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, SignedIntRLEv1, IntRLEv1Position};
+use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, SignedIntRLE, IntRLEPosition};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, LongStatistics};
-use crate::writer::data::common::{GenericData, BaseData, write_index};
+use crate::writer::data::common::{GenericData, BaseData, write_index, write_bloom_filters, check_row_count, record_out_of_range};
 
 
 pub struct LongData {
@@ -17,32 +19,37 @@ pub struct LongData {
     row_group_stats: LongStatistics,
     row_group_position: LongDataPosition,
     row_index_entries: Vec<LongRowIndexEntry>,
+    // Bloom filter of the current row group, if the column has bloom filters
+    bloom_filter: Option<BloomFilter>,
     schema: Schema,
-    config: Config,
+    // First error in writing a value, reported by the next `verify_row_count`
+    error: Option<OrcError>,
+    pub(crate) config: Config,
 }
 
 struct LongDataStreams {
     present: BooleanRLE,
-    data: SignedIntRLEv1,
+    data: SignedIntRLE,
 }
 
 #[derive(Copy, Clone)]
 struct LongDataPosition {
     present: BooleanRLEPosition,
-    data: IntRLEv1Position,
+    data: IntRLEPosition,
 }
 
 struct LongRowIndexEntry {
     position: LongDataPosition,
     stats: LongStatistics,
+    bloom_filter: Option<BloomFilter>,
 }
 
 impl LongDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
+    pub fn record(&self, streams: &LongDataStreams, include_present: bool, out: &mut Vec<u64>) {
         if include_present {
-            self.present.record(out);
+            self.present.record(streams.present.sink(), out);
         }
-        self.data.record(out);
+        self.data.record(streams.data.sink(), out);
     }
 }
 
@@ -61,7 +68,7 @@ impl LongData {
         *column_id += 1;
         let streams = LongDataStreams {
             present: BooleanRLE::new(&config.compression),
-            data: SignedIntRLEv1::new(&config.compression),
+            data: SignedIntRLE::new(&config.compression, config.version),
         };
         Self {
             column_id: cid,
@@ -69,7 +76,9 @@ impl LongData {
             row_group_stats: LongStatistics::new(),
             row_group_position: streams.position(),
             row_index_entries: Vec::new(),
+            bloom_filter: config.bloom_filter(cid),
             schema: schema.clone(),
+            error: None,
             config: config.clone(),
             streams,
         }
@@ -84,22 +93,50 @@ impl LongData {
     fn finish_row_group(&mut self) {
         if self.row_group_stats.num_values > 0 {
             self.stripe_stats.merge(&self.row_group_stats);
+            let bloom_filter = std::mem::replace(&mut self.bloom_filter, self.config.bloom_filter(self.column_id));
             self.row_index_entries.push(LongRowIndexEntry {
                 position: self.row_group_position,
                 stats: self.row_group_stats,
+                bloom_filter,
             });
             self.row_group_position = self.streams.position();
             self.row_group_stats = LongStatistics::new();
         }
     }
 
+    /// Writes a value, which must fit the type of the column (such as `i16` for Short columns),
+    /// or is otherwise handled according to `Config::with_out_of_range_policy`.
     pub fn write(&mut self, x: i64) {
+        let (min, max) = match self.schema {
+            Schema::Short => (i16::MIN as i64, i16::MAX as i64),
+            Schema::Int => (i32::MIN as i64, i32::MAX as i64),
+            _ => (i64::MIN, i64::MAX),
+        };
+        if x < min || x > max {
+            match self.config.out_of_range_policy {
+                OutOfRangePolicy::Error => {
+                    let schema = &self.schema;
+                    record_out_of_range(&self.config, self.column_id, &mut self.error,
+                        || format!("{} does not fit in {:?}", x, schema));
+                }
+                OutOfRangePolicy::Truncate | OutOfRangePolicy::Null => self.write_null(),
+            }
+            return;
+        }
         self.streams.present.write(true);
         self.streams.data.write(x);
+        if let Some(bloom_filter) = &mut self.bloom_filter {
+            bloom_filter.add_long(x);
+        }
         self.row_group_stats.update(x);
         self.check_row_group();
     }
 
+    /// Date columns have date statistics rather than integer statistics.
+    fn statistics_of(&self, stats: LongStatistics) -> Statistics {
+        if let Schema::Date = self.schema { Statistics::Date(stats) } else { Statistics::Long(stats) }
+    }
+
     pub fn schema(&self) -> &Schema {
         &self.schema
     }
@@ -117,18 +154,28 @@ impl GenericData for LongData {
 impl BaseData for LongData {
     fn column_id(&self) -> u32 { self.column_id }
 
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        self.streams.data.finish_encoding();
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         self.finish_row_group();
         let mut row_index_entries: Vec<orc_proto::RowIndexEntry> = Vec::new();
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
             row_index_entry.set_positions(positions);
-            row_index_entry.set_statistics(Statistics::Long(entry.stats).to_proto());
+            row_index_entry.set_statistics(self.statistics_of(entry.stats).to_proto());
             row_index_entries.push(row_index_entry);
         }
         write_index(row_index_entries, self.column_id(), &self.config.compression, out, stream_infos_out)?;
+        write_bloom_filters(self.row_index_entries.iter().filter_map(|e| e.bloom_filter.as_ref()), self.column_id,
+            &self.config.compression, out, stream_infos_out)?;
         Ok(())
     }
 
@@ -158,23 +205,27 @@ impl BaseData for LongData {
 
     fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>) {
         let mut encoding = orc_proto::ColumnEncoding::new();
-        encoding.set_kind(orc_proto::ColumnEncoding_Kind::DIRECT);
+        encoding.set_kind(self.config.version.direct_encoding());
+        if self.bloom_filter.is_some() {
+            encoding.set_bloomEncoding(BLOOM_ENCODING_UTF8_UTC);
+        }
         out.push(encoding);
     }
 
     fn statistics(&self, out: &mut Vec<Statistics>) {
-        out.push(Statistics::Long(self.stripe_stats));
+        out.push(self.statistics_of(self.stripe_stats));
     }
 
     fn estimated_size(&self) -> usize {
         self.streams.present.estimated_size() + self.streams.data.estimated_size()
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
-        let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type Long), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
         }
+        let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
+        Ok(())
     }
 }
@@ -1,13 +1,14 @@
 use std::io::{Write, Result};
 
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::schema::Schema;
 use crate::writer::Config;
 use crate::writer::count_write::CountWrite;
-use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, UnsignedIntRLEv1, IntRLEv1Position};
+use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, UnsignedIntRLE, IntRLEPosition};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, GenericStatistics};
-use crate::writer::data::common::{BaseData, write_index};
+use crate::writer::data::common::{BaseData, write_index, check_row_count};
 use crate::writer::data::{GenericData, Data};
 
 pub struct MapData {
@@ -20,18 +21,18 @@ pub struct MapData {
     row_group_stats: GenericStatistics,
     row_group_position: MapDataPosition,
     row_index_entries: Vec<MapRowIndexEntry>,
-    config: Config,
+    pub(crate) config: Config,
 }
 
 struct MapDataStreams {
     present: BooleanRLE,
-    lengths: UnsignedIntRLEv1,
+    lengths: UnsignedIntRLE,
 }
 
 #[derive(Copy, Clone)]
 struct MapDataPosition {
     present: BooleanRLEPosition,
-    lengths: IntRLEv1Position,
+    lengths: IntRLEPosition,
 }
 
 struct MapRowIndexEntry {
@@ -40,11 +41,11 @@ struct MapRowIndexEntry {
 }
 
 impl MapDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
+    pub fn record(&self, streams: &MapDataStreams, include_present: bool, out: &mut Vec<u64>) {
         if include_present {
-            self.present.record(out);
+            self.present.record(streams.present.sink(), out);
         }
-        self.lengths.record(out);
+        self.lengths.record(streams.lengths.sink(), out);
     }
 }
 
@@ -65,7 +66,7 @@ impl MapData {
         if let Schema::Map(key_schema, value_schema) = schema {        
             let streams = MapDataStreams {
                 present: BooleanRLE::new(&config.compression),
-                lengths: UnsignedIntRLEv1::new(&config.compression),                
+                lengths: UnsignedIntRLE::new(&config.compression, config.version),                
             };
             Self {
                 column_id: cid,
@@ -134,6 +135,16 @@ impl GenericData for MapData {
 impl BaseData for MapData {
     fn column_id(&self) -> u32 { self.column_id }
 
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        self.streams.lengths.finish_encoding();
+        self.keys.finish_encoding();
+        self.values.finish_encoding();
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         self.finish_row_group();
 
@@ -141,7 +152,7 @@ impl BaseData for MapData {
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
             row_index_entry.set_positions(positions);
             row_index_entry.set_statistics(Statistics::Generic(entry.stats).to_proto());
             row_index_entries.push(row_index_entry);
@@ -183,7 +194,7 @@ impl BaseData for MapData {
     fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>) {
         assert_eq!(out.len(), self.column_id as usize);
         let mut encoding = orc_proto::ColumnEncoding::new();
-        encoding.set_kind(orc_proto::ColumnEncoding_Kind::DIRECT);
+        encoding.set_kind(self.config.version.direct_encoding());
         out.push(encoding);
         self.keys.column_encodings(out);
         self.values.column_encodings(out);
@@ -196,14 +207,12 @@ impl BaseData for MapData {
         self.values.statistics(out);
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
         let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type Map), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
-        }
-        self.keys.verify_row_count(self.num_child_values);
-        self.values.verify_row_count(self.num_child_values);
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
+        self.keys.verify_row_count(self.num_child_values)?;
+        self.values.verify_row_count(self.num_child_values)?;
+        Ok(())
     }
 
     fn estimated_size(&self) -> usize {
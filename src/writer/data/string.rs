@@ -1,60 +1,110 @@
+use std::collections::HashMap;
 use std::io::{Write, Result};
 
+use crate::bloom_filter::{BloomFilter, BLOOM_ENCODING_UTF8_UTC};
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::schema::Schema;
-use crate::writer::Config;
+use crate::writer::{Config, OutOfRangePolicy};
 use crate::writer::count_write::CountWrite;
 use crate::writer::compression::{CompressionStream, CompressionStreamPosition};
-use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, UnsignedIntRLEv1, IntRLEv1Position};
+use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, UnsignedIntRLE, IntRLEPosition};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, StringStatistics};
-use crate::writer::data::common::{GenericData, BaseData, write_index};
+use crate::writer::data::common::{GenericData, BaseData, write_index, write_bloom_filters, check_row_count, record_out_of_range};
 
+/// Values of a string column are buffered in a dictionary until the end of the first row group of
+/// the stripe, when the column is either kept dictionary encoded or switched to direct encoding,
+/// depending on its number of distinct values (see `Config::with_dictionary_key_size_threshold`).
+/// Stripes shorter than a row group decide at the end of the stripe.
 pub struct StringData {
     pub(crate) column_id: u32,
-    streams: StringDataStreams,
+    present: BooleanRLE,
+    // Distinct values of the stripe, with ids in order of first occurrence
+    dictionary: HashMap<String, u32>,
+    dictionary_bytes: usize,
+    // The dictionary id of each non-null value of the stripe
+    rows: Vec<u32>,
+    // Set once the encoding is chosen, when the dictionary is kept until the end of the stripe
+    dictionary_checked: bool,
+    // Set on switching to direct encoding, or when the stripe is finished
+    streams: Option<StringDataStreams>,
     schema: Schema,
     stripe_stats: StringStatistics,
     row_group_stats: StringStatistics,
-    row_group_position: StringDataPosition,
+    row_group_start: StringRowGroupStart,
     row_index_entries: Vec<StringRowIndexEntry>,
-    config: Config,
+    // Bloom filter of the current row group, if the column has bloom filters
+    bloom_filter: Option<BloomFilter>,
+    // First error in writing a value, reported by the next `verify_row_count`
+    error: Option<OrcError>,
+    pub(crate) config: Config,
 }
 
-struct StringDataStreams {
-    present: BooleanRLE,
-    data: CompressionStream,
-    lengths: UnsignedIntRLEv1,
+enum StringDataStreams {
+    Direct {
+        data: CompressionStream,
+        lengths: UnsignedIntRLE,
+    },
+    Dictionary {
+        data: UnsignedIntRLE,
+        dictionary_data: CompressionStream,
+        lengths: UnsignedIntRLE,
+        dictionary_size: u32,
+    },
+}
+
+#[derive(Copy, Clone)]
+enum StringDataPosition {
+    Direct {
+        data: CompressionStreamPosition,
+        lengths: IntRLEPosition,
+    },
+    Dictionary {
+        data: IntRLEPosition,
+    },
 }
 
 #[derive(Copy, Clone)]
-struct StringDataPosition {
+struct StringRowGroupStart {
     present: BooleanRLEPosition,
-    data: CompressionStreamPosition,
-    lengths: IntRLEv1Position,
+    // Index in `rows` of the first value of the row group, while the values are buffered
+    value_index: usize,
+    // Position in `streams`, once the values are encoded
+    data: Option<StringDataPosition>,
 }
 
 struct StringRowIndexEntry {
-    position: StringDataPosition,
+    start: StringRowGroupStart,
     stats: StringStatistics,
+    bloom_filter: Option<BloomFilter>,
 }
 
 impl StringDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
-        if include_present {
-            self.present.record(out);
+    pub fn record(&self, streams: &StringDataStreams, out: &mut Vec<u64>) {
+        match (self, streams) {
+            (StringDataPosition::Direct { data, lengths }, StringDataStreams::Direct { data: data_stream, lengths: lengths_stream }) => {
+                data.record(data_stream, out);
+                lengths.record(lengths_stream.sink(), out);
+            }
+            (StringDataPosition::Dictionary { data }, StringDataStreams::Dictionary { data: data_stream, .. }) => {
+                data.record(data_stream.sink(), out);
+            }
+            _ => unreachable!("position of the other encoding"),
         }
-        self.data.record(out);
-        self.lengths.record(out);
     }
 }
 
 impl StringDataStreams {
     pub fn position(&self) -> StringDataPosition {
-        StringDataPosition {
-            present: self.present.position(),
-            data: self.data.position(),
-            lengths: self.lengths.position(),
+        match self {
+            StringDataStreams::Direct { data, lengths } => StringDataPosition::Direct {
+                data: data.position(),
+                lengths: lengths.position(),
+            },
+            StringDataStreams::Dictionary { data, .. } => StringDataPosition::Dictionary {
+                data: data.position(),
+            },
         }
     }
 }
@@ -63,27 +113,82 @@ impl StringData {
     pub(crate) fn new(schema: &Schema, config: &Config, column_id: &mut u32) -> Self {
         let cid = *column_id;
         *column_id += 1;
-        let streams = StringDataStreams {
-            present: BooleanRLE::new(&config.compression),
-            data: CompressionStream::new(&config.compression),
-            lengths: UnsignedIntRLEv1::new(&config.compression),
-        };
+        let present = BooleanRLE::new(&config.compression);
         Self {
             column_id: cid,
+            row_group_start: StringRowGroupStart {
+                present: present.position(),
+                value_index: 0,
+                data: None,
+            },
+            present,
+            dictionary: HashMap::new(),
+            dictionary_bytes: 0,
+            rows: Vec::new(),
+            dictionary_checked: false,
+            streams: None,
             schema: schema.clone(),
             stripe_stats: StringStatistics::new(),
             row_group_stats: StringStatistics::new(),
-            row_group_position: streams.position(),
             row_index_entries: Vec::new(),
+            bloom_filter: config.bloom_filter(cid),
+            error: None,
             config: config.clone(),
-            streams,
         }
     }
 
+    /// Writes a value. Char values are padded with spaces to the length of the column, and Char and
+    /// VarChar values longer than it (in characters) are handled according to
+    /// `Config::with_out_of_range_policy`.
     pub fn write(&mut self, x: &str) {
-        self.streams.present.write(true);
-        self.streams.data.write_bytes(x.as_bytes());
-        self.streams.lengths.write(x.len() as u64);
+        let length = match self.schema {
+            Schema::Char(n) | Schema::VarChar(n) => n as usize,
+            _ => return self.write_value(x),
+        };
+        let x = match x.char_indices().nth(length) {
+            None => x,
+            Some((end, _)) => match self.config.out_of_range_policy {
+                OutOfRangePolicy::Truncate => &x[..end],
+                OutOfRangePolicy::Null => return self.write_null(),
+                OutOfRangePolicy::Error => {
+                    let schema = &self.schema;
+                    record_out_of_range(&self.config, self.column_id, &mut self.error,
+                        || format!("value of {} characters is longer than {:?}", x.chars().count(), schema));
+                    return;
+                }
+            },
+        };
+        if let Schema::Char(_) = self.schema {
+            if x.chars().count() < length {
+                return self.write_value(&format!("{:<1$}", x, length));
+            }
+        }
+        self.write_value(x)
+    }
+
+    fn write_value(&mut self, x: &str) {
+        self.present.write(true);
+        match &mut self.streams {
+            Some(StringDataStreams::Direct { data, lengths }) => {
+                data.write_bytes(x.as_bytes());
+                lengths.write(x.len() as u64);
+            }
+            _ => {
+                let id = match self.dictionary.get(x) {
+                    Some(&id) => id,
+                    None => {
+                        let id = self.dictionary.len() as u32;
+                        self.dictionary.insert(x.to_owned(), id);
+                        self.dictionary_bytes += x.len();
+                        id
+                    }
+                };
+                self.rows.push(id);
+            }
+        }
+        if let Some(bloom_filter) = &mut self.bloom_filter {
+            bloom_filter.add_bytes(x.as_bytes());
+        }
         self.row_group_stats.update(x);
         self.check_row_group();
     }
@@ -97,82 +202,213 @@ impl StringData {
     fn finish_row_group(&mut self) {
         if self.row_group_stats.num_values > 0 {
             self.stripe_stats.merge(&self.row_group_stats);
+            let bloom_filter = std::mem::replace(&mut self.bloom_filter, self.config.bloom_filter(self.column_id));
             self.row_index_entries.push(StringRowIndexEntry {
-                position: self.row_group_position,
+                start: self.row_group_start,
                 stats: self.row_group_stats.clone(),
+                bloom_filter,
             });
-            self.row_group_position = self.streams.position();
+            if !self.dictionary_checked {
+                self.check_dictionary();
+            }
+            self.row_group_start = StringRowGroupStart {
+                present: self.present.position(),
+                value_index: self.rows.len(),
+                data: self.streams.as_ref().map(|s| s.position()),
+            };
             self.row_group_stats = StringStatistics::new();
         }
     }
 
-    pub fn schema(&self) -> &Schema { 
+    pub fn schema(&self) -> &Schema {
         &self.schema
     }
+
+    fn use_dictionary(&self) -> bool {
+        !self.rows.is_empty()
+            && self.dictionary.len() as f64 / self.rows.len() as f64 <= self.config.dictionary_key_size_threshold
+    }
+
+    /// Chooses the encoding from the values buffered so far, unless they are all null. Switching to
+    /// direct encoding frees the dictionary, and the following values are written to `streams`.
+    fn check_dictionary(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
+        self.dictionary_checked = true;
+        if !self.use_dictionary() {
+            self.encode(false);
+            self.dictionary = HashMap::new();
+            self.dictionary_bytes = 0;
+            self.rows = Vec::new();
+        }
+    }
+
+    /// Encodes the buffered values into `streams`, with the position of the start of each row group.
+    fn encode(&mut self, use_dictionary: bool) {
+        let compression = &self.config.compression;
+        let version = self.config.version;
+        let mut entries: Vec<&str> = vec![""; self.dictionary.len()];
+        for (x, &id) in &self.dictionary {
+            entries[id as usize] = x;
+        }
+
+        let mut streams = if use_dictionary {
+            // The dictionary is written in sorted order, so ids are remapped accordingly
+            let mut order: Vec<u32> = (0..entries.len() as u32).collect();
+            order.sort_unstable_by_key(|&id| entries[id as usize]);
+            let mut dictionary_data = CompressionStream::new(compression);
+            let mut lengths = UnsignedIntRLE::new(compression, version);
+            for &id in &order {
+                dictionary_data.write_bytes(entries[id as usize].as_bytes());
+                lengths.write(entries[id as usize].len() as u64);
+            }
+            let mut sorted_ids: Vec<u32> = vec![0; order.len()];
+            for (sorted_id, &id) in order.iter().enumerate() {
+                sorted_ids[id as usize] = sorted_id as u32;
+            }
+            for id in self.rows.iter_mut() {
+                *id = sorted_ids[*id as usize];
+            }
+            StringDataStreams::Dictionary {
+                data: UnsignedIntRLE::new(compression, version),
+                dictionary_data,
+                lengths,
+                dictionary_size: order.len() as u32,
+            }
+        } else {
+            StringDataStreams::Direct {
+                data: CompressionStream::new(compression),
+                lengths: UnsignedIntRLE::new(compression, version),
+            }
+        };
+
+        let mut row_group_starts = self.row_index_entries.iter_mut().map(|e| &mut e.start).peekable();
+        for (i, &id) in self.rows.iter().enumerate() {
+            while let Some(start) = row_group_starts.next_if(|s| s.value_index == i) {
+                start.data = Some(streams.position());
+            }
+            match &mut streams {
+                StringDataStreams::Direct { data, lengths } => {
+                    let x = entries[id as usize];
+                    data.write_bytes(x.as_bytes());
+                    lengths.write(x.len() as u64);
+                }
+                StringDataStreams::Dictionary { data, .. } => {
+                    data.write(id as u64);
+                }
+            }
+        }
+        // Row groups that start after the last value
+        for start in row_group_starts {
+            start.data = Some(streams.position());
+        }
+        self.streams = Some(streams);
+    }
 }
 
 impl GenericData for StringData {
     fn write_null(&mut self) {
-        self.streams.present.write(false);
+        self.present.write(false);
         self.row_group_stats.update_null();
         self.check_row_group();
     }
 }
 
+fn write_stream<W: Write, F>(out: &mut CountWrite<W>, kind: orc_proto::Stream_Kind, column_id: u32,
+        stream_infos_out: &mut Vec<StreamInfo>, finish: F) -> Result<()>
+        where F: FnOnce(&mut CountWrite<W>) -> Result<()> {
+    let start_pos = out.pos();
+    finish(out)?;
+    stream_infos_out.push(StreamInfo {
+        kind,
+        column_id,
+        length: (out.pos() - start_pos) as u64,
+    });
+    Ok(())
+}
+
 impl BaseData for StringData {
     fn column_id(&self) -> u32 { self.column_id }
 
-    fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
+    fn finish_encoding(&mut self) {
         self.finish_row_group();
+        if self.streams.is_none() {
+            let use_dictionary = self.dictionary_checked || self.use_dictionary();
+            self.encode(use_dictionary);
+        }
+        if self.stripe_stats.has_null() {
+            self.present.finish_encoding();
+        }
+        match self.streams.as_mut().unwrap() {
+            StringDataStreams::Direct { data, lengths } => {
+                data.compress_remaining();
+                lengths.finish_encoding();
+            }
+            StringDataStreams::Dictionary { data, dictionary_data, lengths, .. } => {
+                data.finish_encoding();
+                dictionary_data.compress_remaining();
+                lengths.finish_encoding();
+            }
+        }
+    }
+
+    fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
+        let streams = self.streams.as_ref().expect("string column is encoded by finish_encoding");
         let mut row_index_entries: Vec<orc_proto::RowIndexEntry> = Vec::new();
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            if self.stripe_stats.has_null() {
+                entry.start.present.record(self.present.sink(), &mut positions);
+            }
+            entry.start.data.expect("string values are encoded by finish_encoding").record(streams, &mut positions);
             row_index_entry.set_positions(positions);
             row_index_entry.set_statistics(Statistics::String(entry.stats.clone()).to_proto());
             row_index_entries.push(row_index_entry);
         }
         write_index(row_index_entries, self.column_id(), &self.config.compression, out, stream_infos_out)?;
+        write_bloom_filters(self.row_index_entries.iter().filter_map(|e| e.bloom_filter.as_ref()), self.column_id,
+            &self.config.compression, out, stream_infos_out)?;
         Ok(())
     }
 
     fn write_data_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
+        let column_id = self.column_id;
         if self.stripe_stats.has_null() {
-            let present_start_pos = out.pos();
-            self.streams.present.finish(out)?;
-            let present_len = (out.pos() - present_start_pos) as u64;
-            stream_infos_out.push(StreamInfo {
-                kind: orc_proto::Stream_Kind::PRESENT,
-                column_id: self.column_id,
-                length: present_len as u64,
-            });
+            let present = &mut self.present;
+            write_stream(out, orc_proto::Stream_Kind::PRESENT, column_id, stream_infos_out, |out| present.finish(out))?;
+        }
+
+        match self.streams.as_mut().expect("string column is encoded by finish_encoding") {
+            StringDataStreams::Direct { data, lengths } => {
+                write_stream(out, orc_proto::Stream_Kind::LENGTH, column_id, stream_infos_out, |out| lengths.finish(out))?;
+                write_stream(out, orc_proto::Stream_Kind::DATA, column_id, stream_infos_out, |out| data.finish(out))?;
+            }
+            StringDataStreams::Dictionary { data, dictionary_data, lengths, .. } => {
+                write_stream(out, orc_proto::Stream_Kind::DATA, column_id, stream_infos_out, |out| data.finish(out))?;
+                write_stream(out, orc_proto::Stream_Kind::LENGTH, column_id, stream_infos_out, |out| lengths.finish(out))?;
+                write_stream(out, orc_proto::Stream_Kind::DICTIONARY_DATA, column_id, stream_infos_out, |out| dictionary_data.finish(out))?;
+            }
         }
-        
-        let lengths_start_pos = out.pos();
-        self.streams.lengths.finish(out)?;
-        let lengths_len = (out.pos() - lengths_start_pos) as u64;
-        stream_infos_out.push(StreamInfo {
-            kind: orc_proto::Stream_Kind::LENGTH,
-            column_id: self.column_id,
-            length: lengths_len,
-        });
-
-        let data_start_pos = out.pos();
-        self.streams.data.finish(out)?;
-        let data_len = (out.pos() - data_start_pos) as u64;
-        stream_infos_out.push(StreamInfo {
-            kind: orc_proto::Stream_Kind::DATA,
-            column_id: self.column_id,
-            length: data_len,
-        });
 
         Ok(())
     }
 
     fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>) {
         let mut encoding = orc_proto::ColumnEncoding::new();
-        encoding.set_kind(orc_proto::ColumnEncoding_Kind::DIRECT);
+        match &self.streams {
+            Some(StringDataStreams::Dictionary { dictionary_size, .. }) => {
+                encoding.set_kind(self.config.version.dictionary_encoding());
+                encoding.set_dictionarySize(*dictionary_size);
+            }
+            _ => {
+                encoding.set_kind(self.config.version.direct_encoding());
+            }
+        }
+        if self.bloom_filter.is_some() {
+            encoding.set_bloomEncoding(BLOOM_ENCODING_UTF8_UTC);
+        }
         out.push(encoding);
     }
 
@@ -180,16 +416,128 @@ impl BaseData for StringData {
         out.push(Statistics::String(self.stripe_stats.clone()));
     }
 
+    /// While the values are buffered, this is their uncompressed size in the dictionary, with 4
+    /// bytes for the dictionary id of each value, rather than the size of the encoded streams; it
+    /// may overestimate a column that stays dictionary encoded, and so flush the stripe early.
     fn estimated_size(&self) -> usize {
-        self.streams.present.estimated_size() + self.streams.data.estimated_size() + 
-            self.streams.lengths.estimated_size()
+        let streams_size = match &self.streams {
+            Some(StringDataStreams::Direct { data, lengths }) => data.estimated_size() + lengths.estimated_size(),
+            _ => 0,
+        };
+        self.present.estimated_size() + self.dictionary_bytes + 4 * self.rows.len() + streams_size
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
         let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type String), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Seek, SeekFrom};
+    use protobuf::Message;
+    use crate::protos::orc_proto;
+    use crate::reader::OrcFile;
+    use crate::schema::Schema;
+    use crate::writer::{Config, Version, Writer};
+    use crate::writer::data::GenericData;
+
+    fn write_strings(config: Config, values: &[Option<&str>]) -> Vec<u8> {
+        let mut writer = Writer::new(Vec::new(), &Schema::String, config).unwrap();
+        for x in values {
+            match x {
+                Some(x) => writer.data().unwrap_string().write(x),
+                None => writer.data().unwrap_string().write_null(),
+            }
+        }
+        writer.write_batch(values.len() as u64).unwrap();
+        writer.finish().unwrap()
+    }
+
+    fn stripe_footer(bytes: Vec<u8>) -> orc_proto::StripeFooter {
+        let file = OrcFile::open(Cursor::new(bytes)).unwrap();
+        let stripe = file.stripes()[0];
+        let mut inner = file.into_inner();
+        inner.seek(SeekFrom::Start(stripe.offset + stripe.index_length + stripe.data_length)).unwrap();
+        let mut buf = vec![0; stripe.footer_length as usize];
+        inner.read_exact(&mut buf).unwrap();
+        orc_proto::StripeFooter::parse_from_bytes(&buf).unwrap()
+    }
+
+    #[test]
+    fn test_dictionary_encoding() {
+        let codes = ["DE", "FR", "NL", "US", "GB"];
+        let values: Vec<Option<&str>> = (0..10000).map(|i| if i % 11 == 0 { None } else { Some(codes[i % 5]) }).collect();
+        for &(version, kind) in &[
+            (Version::V0_11, orc_proto::ColumnEncoding_Kind::DICTIONARY),
+            (Version::V0_12, orc_proto::ColumnEncoding_Kind::DICTIONARY_V2),
+        ] {
+            let dictionary = write_strings(Config::new().with_version(version), &values);
+            let direct = write_strings(Config::new().with_version(version).with_dictionary_key_size_threshold(0.0), &values);
+            assert!(dictionary.len() < direct.len());
+
+            let footer = stripe_footer(dictionary);
+            assert_eq!(footer.get_columns()[0].get_kind(), kind);
+            assert_eq!(footer.get_columns()[0].get_dictionarySize(), 5);
+            let stream_kinds: Vec<_> = footer.get_streams().iter().map(|s| s.get_kind()).collect();
+            assert_eq!(stream_kinds, vec![
+                orc_proto::Stream_Kind::ROW_INDEX,
+                orc_proto::Stream_Kind::PRESENT,
+                orc_proto::Stream_Kind::DATA,
+                orc_proto::Stream_Kind::LENGTH,
+                orc_proto::Stream_Kind::DICTIONARY_DATA,
+            ]);
+
+            let footer = stripe_footer(direct);
+            assert_eq!(footer.get_columns()[0].get_kind(), version.direct_encoding());
+        }
+    }
+
+    #[test]
+    fn test_dictionary_fallback() {
+        let values: Vec<String> = (0..1000).map(|i| format!("value {}", i % 900)).collect();
+        let values: Vec<Option<&str>> = values.iter().map(|x| Some(x.as_str())).collect();
+        let footer = stripe_footer(write_strings(Config::new(), &values));
+        assert_eq!(footer.get_columns()[0].get_kind(), orc_proto::ColumnEncoding_Kind::DIRECT_V2);
+        let footer = stripe_footer(write_strings(Config::new().with_dictionary_key_size_threshold(0.95), &values));
+        assert_eq!(footer.get_columns()[0].get_kind(), orc_proto::ColumnEncoding_Kind::DICTIONARY_V2);
+        assert_eq!(footer.get_columns()[0].get_dictionarySize(), 900);
+    }
+
+    #[test]
+    fn test_dictionary_check_after_first_row_group() {
+        let distinct: Vec<String> = (0..1000).map(|i| format!("value {}", i)).collect();
+        let repeated = ["DE", "FR", "NL"];
+        // Distinct values in the first row group only, and in all but the first row group
+        let first: Vec<&str> = (0..10000).map(|i| if i < 1000 { distinct[i].as_str() } else { repeated[i % 3] }).collect();
+        let rest: Vec<&str> = (0..10000).map(|i| if i < 1000 { repeated[i % 3] } else { distinct[i % 1000].as_str() }).collect();
+        for &(values, kind) in &[
+            (&first, orc_proto::ColumnEncoding_Kind::DIRECT_V2),
+            (&rest, orc_proto::ColumnEncoding_Kind::DICTIONARY_V2),
+        ] {
+            let mut writer = Writer::new(Vec::new(), &Schema::String, Config::new().with_row_index_stride(1000)).unwrap();
+            for (i, x) in values.iter().enumerate() {
+                writer.data().unwrap_string().write(x);
+                if i == 999 && kind == orc_proto::ColumnEncoding_Kind::DIRECT_V2 {
+                    // The dictionary is freed on switching to direct encoding
+                    assert!(writer.data().unwrap_string().dictionary.is_empty());
+                }
+            }
+            writer.write_batch(values.len() as u64).unwrap();
+            let bytes = writer.finish().unwrap();
+            assert_eq!(stripe_footer(bytes.clone()).get_columns()[0].get_kind(), kind);
+
+            let mut file = OrcFile::open(Cursor::new(bytes)).unwrap();
+            let mut reader = file.row_reader().unwrap();
+            reader.seek_to_row(4321).unwrap();
+            reader.read_batch(10).unwrap();
+            let read: Vec<&str> = reader.data().unwrap_string().values().collect();
+            assert_eq!(read, &values[4321..4331]);
         }
     }
 }
@@ -1,5 +1,6 @@
 use std::io::{Write, Result};
 
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::schema::Schema;
 use crate::writer::Config;
@@ -7,7 +8,7 @@ use crate::writer::count_write::CountWrite;
 use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, GenericStatistics};
-use crate::writer::data::common::{GenericData, BaseData, write_index};
+use crate::writer::data::common::{GenericData, BaseData, write_index, finish_children_encoding, check_row_count};
 use crate::writer::data::Data;
 
 pub struct StructData {
@@ -19,7 +20,7 @@ pub struct StructData {
     row_group_stats: GenericStatistics,
     row_group_position: StructDataPosition,
     row_index_entries: Vec<StructRowIndexEntry>,
-    config: Config,
+    pub(crate) config: Config,
 }
 
 
@@ -38,9 +39,9 @@ struct StructRowIndexEntry {
 }
 
 impl StructDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
+    pub fn record(&self, streams: &StructDataStreams, include_present: bool, out: &mut Vec<u64>) {
         if include_present {
-            self.present.record(out);
+            self.present.record(streams.present.sink(), out);
         }
     }
 }
@@ -134,13 +135,21 @@ impl GenericData for StructData {
 impl BaseData for StructData {
     fn column_id(&self) -> u32 { self.column_id }
 
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        finish_children_encoding(&mut self.children);
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         self.finish_row_group();
         let mut row_index_entries: Vec<orc_proto::RowIndexEntry> = Vec::new();
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
             row_index_entry.set_positions(positions);
             row_index_entry.set_statistics(Statistics::Generic(entry.stats).to_proto());
             row_index_entries.push(row_index_entry);
@@ -190,16 +199,15 @@ impl BaseData for StructData {
         }
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
         let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type Struct), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
-        }
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
 
-        for child in &self.children {
-            child.verify_row_count(self.stripe_stats.num_present() + self.row_group_stats.num_present());
+        let num_present = self.stripe_stats.num_present() + self.row_group_stats.num_present();
+        for child in &mut self.children {
+            child.verify_row_count(num_present)?;
         }
+        Ok(())
     }
 
     fn estimated_size(&self) -> usize {
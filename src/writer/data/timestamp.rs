@@ -1,12 +1,14 @@
 use std::io::{Write, Result};
 
+use crate::bloom_filter::{BloomFilter, BLOOM_ENCODING_UTF8_UTC};
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::writer::Config;
 use crate::writer::count_write::CountWrite;
-use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, SignedIntRLEv1, UnsignedIntRLEv1, IntRLEv1Position};
+use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, SignedIntRLE, UnsignedIntRLE, IntRLEPosition};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, TimestampStatistics};
-use crate::writer::data::common::{GenericData, BaseData, write_index};
+use crate::writer::data::common::{GenericData, BaseData, write_index, write_bloom_filters, check_row_count};
 
 
 pub struct TimestampData {
@@ -16,34 +18,37 @@ pub struct TimestampData {
     row_group_stats: TimestampStatistics,
     row_group_position: TimestampDataPosition,
     row_index_entries: Vec<TimestampRowIndexEntry>,
-    config: Config,
+    // Bloom filter of the current row group, if the column has bloom filters
+    bloom_filter: Option<BloomFilter>,
+    pub(crate) config: Config,
 }
 
 struct TimestampDataStreams {
     present: BooleanRLE,
-    seconds: SignedIntRLEv1,
-    nanos: UnsignedIntRLEv1,
+    seconds: SignedIntRLE,
+    nanos: UnsignedIntRLE,
 }
 
 #[derive(Copy, Clone)]
 struct TimestampDataPosition {
     present: BooleanRLEPosition,
-    seconds: IntRLEv1Position,
-    nanos: IntRLEv1Position,
+    seconds: IntRLEPosition,
+    nanos: IntRLEPosition,
 }
 
 struct TimestampRowIndexEntry {
     position: TimestampDataPosition,
     stats: TimestampStatistics,
+    bloom_filter: Option<BloomFilter>,
 }
 
 impl TimestampDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
+    pub fn record(&self, streams: &TimestampDataStreams, include_present: bool, out: &mut Vec<u64>) {
         if include_present {
-            self.present.record(out);
+            self.present.record(streams.present.sink(), out);
         }
-        self.seconds.record(out);
-        self.nanos.record(out);
+        self.seconds.record(streams.seconds.sink(), out);
+        self.nanos.record(streams.nanos.sink(), out);
     }
 }
 
@@ -67,8 +72,8 @@ impl TimestampData {
         *column_id += 1;
         let streams = TimestampDataStreams {
             present: BooleanRLE::new(&config.compression),
-            seconds: SignedIntRLEv1::new(&config.compression),
-            nanos: UnsignedIntRLEv1::new(&config.compression),
+            seconds: SignedIntRLE::new(&config.compression, config.version),
+            nanos: UnsignedIntRLE::new(&config.compression, config.version),
         };
         Self {
             column_id: cid,
@@ -76,6 +81,7 @@ impl TimestampData {
             row_group_stats: TimestampStatistics::new(),
             row_group_position: streams.position(),
             row_index_entries: Vec::new(),
+            bloom_filter: config.bloom_filter(cid),
             config: config.clone(),
             streams,
         }
@@ -90,9 +96,11 @@ impl TimestampData {
     fn finish_row_group(&mut self) {
         if self.row_group_stats.num_values > 0 {
             self.stripe_stats.merge(&self.row_group_stats);
+            let bloom_filter = std::mem::replace(&mut self.bloom_filter, self.config.bloom_filter(self.column_id));
             self.row_index_entries.push(TimestampRowIndexEntry {
                 position: self.row_group_position,
                 stats: self.row_group_stats,
+                bloom_filter,
             });
             self.row_group_position = self.streams.position();
             self.row_group_stats = TimestampStatistics::new();
@@ -127,8 +135,14 @@ impl TimestampData {
 
         self.streams.present.write(true);
         self.streams.seconds.write(sec);
-        self.streams.nanos.write((nanos_val << 3 | trailing_zeros) as u64);
-        self.row_group_stats.update(sec * 1000 + (nanos / 1000000) as i64);
+        self.streams.nanos.write(((nanos_val as u64) << 3) | trailing_zeros as u64);
+        // Milliseconds since the UNIX epoch, as in the Java implementation. `sec` is rounded down
+        // and `nanos` is non-negative, so this rounds down too, including before 1970.
+        let epoch_millis = (sec - Self::EPOCH_SECONDS) * 1000 + (nanos / 1000000) as i64;
+        if let Some(bloom_filter) = &mut self.bloom_filter {
+            bloom_filter.add_long(epoch_millis);
+        }
+        self.row_group_stats.update(epoch_millis);
         self.check_row_group();
     }
 }
@@ -144,18 +158,29 @@ impl GenericData for TimestampData {
 impl BaseData for TimestampData {
     fn column_id(&self) -> u32 { self.column_id }
 
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        self.streams.seconds.finish_encoding();
+        self.streams.nanos.finish_encoding();
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         self.finish_row_group();
         let mut row_index_entries: Vec<orc_proto::RowIndexEntry> = Vec::new();
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
             row_index_entry.set_positions(positions);
             row_index_entry.set_statistics(Statistics::Timestamp(entry.stats).to_proto());
             row_index_entries.push(row_index_entry);
         }
         write_index(row_index_entries, self.column_id(), &self.config.compression, out, stream_infos_out)?;
+        write_bloom_filters(self.row_index_entries.iter().filter_map(|e| e.bloom_filter.as_ref()), self.column_id,
+            &self.config.compression, out, stream_infos_out)?;
         Ok(())
     }
 
@@ -194,7 +219,10 @@ impl BaseData for TimestampData {
 
     fn column_encodings(&self, out: &mut Vec<orc_proto::ColumnEncoding>) {
         let mut encoding = orc_proto::ColumnEncoding::new();
-        encoding.set_kind(orc_proto::ColumnEncoding_Kind::DIRECT);
+        encoding.set_kind(self.config.version.direct_encoding());
+        if self.bloom_filter.is_some() {
+            encoding.set_bloomEncoding(BLOOM_ENCODING_UTF8_UTC);
+        }
         out.push(encoding);
     }
 
@@ -208,11 +236,9 @@ impl BaseData for TimestampData {
             + self.streams.nanos.estimated_size()
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
         let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type Timestamp), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
-        }
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
+        Ok(())
     }
 }
@@ -1,5 +1,6 @@
 use std::io::{Write, Result};
 
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::schema::Schema;
 use crate::writer::Config;
@@ -7,7 +8,7 @@ use crate::writer::count_write::CountWrite;
 use crate::writer::encoder::{BooleanRLE, BooleanRLEPosition, ByteRLE, ByteRLEPosition};
 use crate::writer::stripe::StreamInfo;
 use crate::writer::statistics::{Statistics, BaseStatistics, GenericStatistics};
-use crate::writer::data::common::{BaseData, write_index};
+use crate::writer::data::common::{BaseData, write_index, finish_children_encoding, check_row_count, record_out_of_range};
 use crate::writer::data::{GenericData, Data};
 
 pub struct UnionData {
@@ -19,7 +20,9 @@ pub struct UnionData {
     row_group_stats: GenericStatistics,
     row_group_position: UnionDataPosition,
     row_index_entries: Vec<UnionRowIndexEntry>,
-    config: Config,
+    // First error in writing a value, reported by the next `verify_row_count`
+    error: Option<OrcError>,
+    pub(crate) config: Config,
 }
 
 struct UnionDataStreams {
@@ -39,11 +42,11 @@ struct UnionRowIndexEntry {
 }
 
 impl UnionDataPosition {
-    pub fn record(&self, include_present: bool, out: &mut Vec<u64>) {
+    pub fn record(&self, streams: &UnionDataStreams, include_present: bool, out: &mut Vec<u64>) {
         if include_present {
-            self.present.record(out);
+            self.present.record(streams.present.sink(), out);
         }
-        self.tags.record(out);
+        self.tags.record(streams.tags.sink(), out);
     }
 }
 
@@ -63,9 +66,8 @@ impl UnionData {
         *column_id += 1;
 
         if let Schema::Union(fields) = schema {
-            if fields.len() > 256 {
-                panic!("Unions are limited to at most 256 variants");
-            }
+            // Larger unions are rejected by `validate_schema` when the writer is created
+            debug_assert!(fields.len() <= 256);
             for field in fields {
                 children.push(Data::new(field, config, column_id));
             }
@@ -82,6 +84,7 @@ impl UnionData {
                 row_group_stats: GenericStatistics::new(),
                 row_group_position: streams.position(),
                 row_index_entries: Vec::new(),
+                error: None,
                 config: config.clone(),
                 streams,
             }
@@ -114,9 +117,14 @@ impl UnionData {
         }
     }
 
+    /// Writes a value of the variant `tag`, whose value must then be written to `child(tag)`.
+    /// An invalid tag is reported by the next `Writer::write_batch`.
     pub fn write(&mut self, tag: usize) {
-        if tag > self.child_counts.len() {
-            panic!("tag ({}) out of range", tag);
+        if tag >= self.child_counts.len() {
+            let num_variants = self.child_counts.len();
+            record_out_of_range(&self.config, self.column_id, &mut self.error,
+                || format!("tag {} is out of range for a union of {} variants", tag, num_variants));
+            return;
         }
         self.streams.present.write(true);
         self.streams.tags.write(tag as u8);
@@ -139,13 +147,22 @@ impl GenericData for UnionData {
 impl BaseData for UnionData {
     fn column_id(&self) -> u32 { self.column_id }
 
+    fn finish_encoding(&mut self) {
+        self.finish_row_group();
+        if self.stripe_stats.has_null() {
+            self.streams.present.finish_encoding();
+        }
+        self.streams.tags.finish_encoding();
+        finish_children_encoding(&mut self.children);
+    }
+
     fn write_index_streams<W: Write>(&mut self, out: &mut CountWrite<W>, stream_infos_out: &mut Vec<StreamInfo>) -> Result<()> {
         self.finish_row_group();
         let mut row_index_entries: Vec<orc_proto::RowIndexEntry> = Vec::new();
         for entry in &self.row_index_entries {
             let mut row_index_entry = orc_proto::RowIndexEntry::new();
             let mut positions: Vec<u64> = Vec::new();
-            entry.position.record(self.stripe_stats.has_null(), &mut positions);
+            entry.position.record(&self.streams, self.stripe_stats.has_null(), &mut positions);
             row_index_entry.set_positions(positions);
             row_index_entry.set_statistics(Statistics::Generic(entry.stats).to_proto());
             row_index_entries.push(row_index_entry);
@@ -204,16 +221,17 @@ impl BaseData for UnionData {
         }
     }
 
-    fn verify_row_count(&self, expected_row_count: u64) {
-        let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
-        if rows_written != expected_row_count {
-            panic!("In column {} (type Union), the number of values written ({}) does not match the expected number ({})", 
-                self.column_id, rows_written, expected_row_count);
+    fn verify_row_count(&mut self, expected_row_count: u64) -> std::result::Result<(), OrcError> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
         }
+        let rows_written = self.stripe_stats.num_values() + self.row_group_stats.num_values();
+        check_row_count(&self.config, self.column_id, rows_written, expected_row_count)?;
 
-        for (i, child) in self.children.iter().enumerate() {
-            child.verify_row_count(self.child_counts[i]);
+        for (child, &child_count) in self.children.iter_mut().zip(&self.child_counts) {
+            child.verify_row_count(child_count)?;
         }
+        Ok(())
     }
 
     fn estimated_size(&self) -> usize {
@@ -1,9 +1,11 @@
 pub(crate) use boolean_rle::{BooleanRLE, BooleanRLEPosition};
 pub(crate) use byte_rle::{ByteRLE, ByteRLEPosition};
-pub(crate) use int_rle_v1::{SignedIntRLEv1, UnsignedIntRLEv1, IntRLEv1Position};
+pub(crate) use int_rle::{SignedIntRLE, UnsignedIntRLE, IntRLEPosition};
 pub(crate) use varint::VarInt;
 
 mod boolean_rle;
 mod byte_rle;
+mod int_rle;
 mod int_rle_v1;
+mod int_rle_v2;
 mod varint;
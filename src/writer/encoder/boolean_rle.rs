@@ -1,4 +1,4 @@
-use crate::writer::compression::Compression;
+use crate::writer::compression::{Compression, CompressionStream};
 use std::io::{Write, Result};
 
 use crate::writer::encoder::byte_rle::{ByteRLE, ByteRLEPosition};
@@ -19,8 +19,8 @@ pub struct BooleanRLEPosition {
 }
 
 impl BooleanRLEPosition {
-    pub fn record(&self, out: &mut Vec<u64>) {
-        self.inner.record(out);
+    pub fn record(&self, sink: &CompressionStream, out: &mut Vec<u64>) {
+        self.inner.record(sink, out);
         out.push(self.bits as u64);
     }
 }
@@ -41,6 +41,11 @@ impl BooleanRLE {
         }
     }
 
+    /// The stream the encoded values are written to.
+    pub fn sink(&self) -> &CompressionStream {
+        self.byte_rle.sink()
+    }
+
     #[inline(always)]
     pub fn write(&mut self, x: bool) {
         self.buf = self.buf << 1 | (x as u8);
@@ -52,11 +57,17 @@ impl BooleanRLE {
         }
     }
 
-    pub fn finish<W: Write>(&mut self, out: &mut W) -> Result<()> {
+    /// Encodes the buffered values and compresses the remaining data of the stream.
+    pub fn finish_encoding(&mut self) {
         if self.cnt > 0 {
             self.byte_rle.write(self.buf << (8 - self.cnt));
             self.cnt = 0;
         }
+        self.byte_rle.finish_encoding();
+    }
+
+    pub fn finish<W: Write>(&mut self, out: &mut W) -> Result<()> {
+        self.finish_encoding();
         self.byte_rle.finish(out)
     }
 
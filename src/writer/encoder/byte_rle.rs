@@ -16,8 +16,8 @@ pub struct ByteRLEPosition {
 }
 
 impl ByteRLEPosition {
-    pub fn record(&self, out: &mut Vec<u64>) {
-        self.inner.record(out);
+    pub fn record(&self, sink: &CompressionStream, out: &mut Vec<u64>) {
+        self.inner.record(sink, out);
         out.push(self.rle_offset);
     }
 }
@@ -43,6 +43,11 @@ impl ByteRLE {
         }
     }
 
+    /// The stream the encoded values are written to.
+    pub fn sink(&self) -> &CompressionStream {
+        &self.sink
+    }
+
     #[inline(always)]
     pub fn write(&mut self, x: u8) {
         if self.buf_len == 128 || self.run_len == 130 {
@@ -87,8 +92,14 @@ impl ByteRLE {
         }
     }
 
-    pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
+    /// Encodes the buffered values and compresses the remaining data of the stream.
+    pub fn finish_encoding(&mut self) {
         self.finish_group();
+        self.sink.compress_remaining();
+    }
+
+    pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
+        self.finish_encoding();
         self.sink.finish(w)
     }
 
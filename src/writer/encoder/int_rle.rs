@@ -0,0 +1,137 @@
+use std::io::{Write, Result};
+use crate::writer::Version;
+use crate::writer::compression::{Compression, CompressionStream};
+use super::int_rle_v1::{SignedIntRLEv1, UnsignedIntRLEv1, IntRLEv1Position};
+use super::int_rle_v2::{SignedIntRLEv2, UnsignedIntRLEv2, IntRLEv2Position};
+
+
+/// Run-length encoder for signed integer streams, in the version selected by `Config::with_version`.
+pub enum SignedIntRLE {
+    V1(SignedIntRLEv1),
+    V2(SignedIntRLEv2),
+}
+
+/// Run-length encoder for unsigned integer streams, in the version selected by `Config::with_version`.
+pub enum UnsignedIntRLE {
+    V1(UnsignedIntRLEv1),
+    V2(UnsignedIntRLEv2),
+}
+
+#[derive(Copy, Clone)]
+pub enum IntRLEPosition {
+    V1(IntRLEv1Position),
+    V2(IntRLEv2Position),
+}
+
+impl IntRLEPosition {
+    pub fn record(&self, sink: &CompressionStream, out: &mut Vec<u64>) {
+        match self {
+            IntRLEPosition::V1(p) => p.record(sink, out),
+            IntRLEPosition::V2(p) => p.record(sink, out),
+        }
+    }
+}
+
+impl SignedIntRLE {
+    pub fn new(compression: &Compression, version: Version) -> Self {
+        match version {
+            Version::V0_11 => SignedIntRLE::V1(SignedIntRLEv1::new(compression)),
+            Version::V0_12 => SignedIntRLE::V2(SignedIntRLEv2::new(compression)),
+        }
+    }
+
+    #[inline(always)]
+    pub fn write(&mut self, x: i64) {
+        match self {
+            SignedIntRLE::V1(e) => e.write(x),
+            SignedIntRLE::V2(e) => e.write(x),
+        }
+    }
+
+    pub fn position(&self) -> IntRLEPosition {
+        match self {
+            SignedIntRLE::V1(e) => IntRLEPosition::V1(e.position()),
+            SignedIntRLE::V2(e) => IntRLEPosition::V2(e.position()),
+        }
+    }
+
+    pub fn sink(&self) -> &CompressionStream {
+        match self {
+            SignedIntRLE::V1(e) => e.sink(),
+            SignedIntRLE::V2(e) => e.sink(),
+        }
+    }
+
+    pub fn finish_encoding(&mut self) {
+        match self {
+            SignedIntRLE::V1(e) => e.finish_encoding(),
+            SignedIntRLE::V2(e) => e.finish_encoding(),
+        }
+    }
+
+    pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
+        match self {
+            SignedIntRLE::V1(e) => e.finish(w),
+            SignedIntRLE::V2(e) => e.finish(w),
+        }
+    }
+
+    pub fn estimated_size(&self) -> usize {
+        match self {
+            SignedIntRLE::V1(e) => e.estimated_size(),
+            SignedIntRLE::V2(e) => e.estimated_size(),
+        }
+    }
+}
+
+impl UnsignedIntRLE {
+    pub fn new(compression: &Compression, version: Version) -> Self {
+        match version {
+            Version::V0_11 => UnsignedIntRLE::V1(UnsignedIntRLEv1::new(compression)),
+            Version::V0_12 => UnsignedIntRLE::V2(UnsignedIntRLEv2::new(compression)),
+        }
+    }
+
+    #[inline(always)]
+    pub fn write(&mut self, x: u64) {
+        match self {
+            UnsignedIntRLE::V1(e) => e.write(x),
+            UnsignedIntRLE::V2(e) => e.write(x),
+        }
+    }
+
+    pub fn position(&self) -> IntRLEPosition {
+        match self {
+            UnsignedIntRLE::V1(e) => IntRLEPosition::V1(e.position()),
+            UnsignedIntRLE::V2(e) => IntRLEPosition::V2(e.position()),
+        }
+    }
+
+    pub fn sink(&self) -> &CompressionStream {
+        match self {
+            UnsignedIntRLE::V1(e) => e.sink(),
+            UnsignedIntRLE::V2(e) => e.sink(),
+        }
+    }
+
+    pub fn finish_encoding(&mut self) {
+        match self {
+            UnsignedIntRLE::V1(e) => e.finish_encoding(),
+            UnsignedIntRLE::V2(e) => e.finish_encoding(),
+        }
+    }
+
+    pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
+        match self {
+            UnsignedIntRLE::V1(e) => e.finish(w),
+            UnsignedIntRLE::V2(e) => e.finish(w),
+        }
+    }
+
+    pub fn estimated_size(&self) -> usize {
+        match self {
+            UnsignedIntRLE::V1(e) => e.estimated_size(),
+            UnsignedIntRLE::V2(e) => e.estimated_size(),
+        }
+    }
+}
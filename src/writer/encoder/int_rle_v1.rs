@@ -17,8 +17,8 @@ pub struct IntRLEv1Position {
 }
 
 impl IntRLEv1Position {
-    pub fn record(&self, out: &mut Vec<u64>) {
-        self.inner.record(out);
+    pub fn record(&self, sink: &CompressionStream, out: &mut Vec<u64>) {
+        self.inner.record(sink, out);
         out.push(self.rle_offset);
     }
 }
@@ -45,6 +45,11 @@ impl<T: VarInt> IntRLEv1<T> {
         }
     }
 
+    /// The stream the encoded values are written to.
+    pub fn sink(&self) -> &CompressionStream {
+        &self.sink
+    }
+
     #[inline(always)]
     pub fn write(&mut self, x: T) {
         let len = self.buf.len();
@@ -96,8 +101,14 @@ impl<T: VarInt> IntRLEv1<T> {
         }
     }
 
-    pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
+    /// Encodes the buffered values and compresses the remaining data of the stream.
+    pub fn finish_encoding(&mut self) {
         self.finish_group();
+        self.sink.compress_remaining();
+    }
+
+    pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
+        self.finish_encoding();
         self.sink.finish(w)
     }
 
@@ -119,6 +130,10 @@ impl SignedIntRLEv1 {
         self.0.write(x);
     }
 
+    pub fn finish_encoding(&mut self) {
+        self.0.finish_encoding();
+    }
+
     pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
         self.0.finish(w)
     }
@@ -127,6 +142,10 @@ impl SignedIntRLEv1 {
         self.0.position()
     }
 
+    pub fn sink(&self) -> &CompressionStream {
+        self.0.sink()
+    }
+
     pub fn estimated_size(&self) -> usize {
         self.0.estimated_size()
     }
@@ -148,6 +167,14 @@ impl UnsignedIntRLEv1 {
         self.0.position()
     }
 
+    pub fn sink(&self) -> &CompressionStream {
+        self.0.sink()
+    }
+
+    pub fn finish_encoding(&mut self) {
+        self.0.finish_encoding();
+    }
+
     pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
         self.0.finish(w)
     }
@@ -0,0 +1,563 @@
+use std::io::{Write, Result};
+use crate::writer::compression::{Compression, CompressionStream, CompressionStreamPosition};
+use super::varint::VarInt;
+
+const MAX_SCOPE: usize = 512;
+const MIN_REPEAT: usize = 3;
+const MAX_SHORT_REPEAT_LENGTH: usize = 10;
+// Bases of PATCHED_BASE runs are stored in at most 8 bytes, including a sign bit.
+const BASE_VALUE_LIMIT: i64 = 1 << 56;
+
+const SHORT_REPEAT: u8 = 0;
+const DIRECT: u8 = 1;
+const PATCHED_BASE: u8 = 2;
+const DELTA: u8 = 3;
+
+/// Rounds a bit width up to the nearest width that has a 5-bit code.
+fn closest_fixed_bits(n: u32) -> u32 {
+    match n {
+        0 => 1,
+        1..=24 => n,
+        25..=26 => 26,
+        27..=28 => 28,
+        29..=30 => 30,
+        31..=32 => 32,
+        33..=40 => 40,
+        41..=48 => 48,
+        49..=56 => 56,
+        _ => 64,
+    }
+}
+
+/// The 5-bit code of a bit width, as stored in run headers.
+fn encode_bit_width(n: u32) -> u8 {
+    match closest_fixed_bits(n) {
+        n @ 1..=24 => (n - 1) as u8,
+        26 => 24,
+        28 => 25,
+        30 => 26,
+        32 => 27,
+        40 => 28,
+        48 => 29,
+        56 => 30,
+        _ => 31,
+    }
+}
+
+#[inline(always)]
+fn num_bits(x: u64) -> u32 {
+    closest_fixed_bits(64 - x.leading_zeros())
+}
+
+/// The number of bits needed to bit pack the smallest `p` fraction of `values`.
+fn percentile_bits(values: &[u64], p: f64) -> u32 {
+    let mut histogram = [0usize; 65];
+    for &x in values {
+        histogram[num_bits(x) as usize] += 1;
+    }
+    let mut remaining = (values.len() as f64 * (1.0 - p)) as i64;
+    for width in (0..histogram.len()).rev() {
+        remaining -= histogram[width] as i64;
+        if remaining < 0 {
+            return width as u32;
+        }
+    }
+    0
+}
+
+#[inline(always)]
+fn zigzag(x: i64) -> u64 {
+    ((x << 1) ^ (x >> 63)) as u64
+}
+
+/// Packs `values` using `width` bits each, most significant bit first. The last byte is padded
+/// with zeroes.
+fn write_packed(sink: &mut CompressionStream, values: &[u64], width: u32) {
+    let mut current: u8 = 0;
+    let mut bits_left: u32 = 8;
+    for &x in values {
+        let mut x = x;
+        let mut bits_to_write = width;
+        while bits_to_write > bits_left {
+            current |= (x >> (bits_to_write - bits_left)) as u8;
+            bits_to_write -= bits_left;
+            x &= (1 << bits_to_write) - 1;
+            sink.write_u8(current);
+            current = 0;
+            bits_left = 8;
+        }
+        bits_left -= bits_to_write;
+        current |= (x << bits_left) as u8;
+        if bits_left == 0 {
+            sink.write_u8(current);
+            current = 0;
+            bits_left = 8;
+        }
+    }
+    if bits_left != 8 {
+        sink.write_u8(current);
+    }
+}
+
+/// Integer run-length encoding version 2. The choice between the SHORT_REPEAT, DIRECT,
+/// PATCHED_BASE and DELTA sub-encodings follows the Java writer (`RunLengthIntegerWriterV2`).
+struct IntRLEv2 {
+    sink: CompressionStream,
+    signed: bool,
+    literals: Vec<i64>,
+    prev_delta: i64,
+    fixed_run_len: usize,
+    variable_run_len: usize,
+}
+
+#[derive(Copy, Clone)]
+pub struct IntRLEv2Position {
+    inner: CompressionStreamPosition,
+    rle_offset: u64,
+}
+
+impl IntRLEv2Position {
+    pub fn record(&self, sink: &CompressionStream, out: &mut Vec<u64>) {
+        self.inner.record(sink, out);
+        out.push(self.rle_offset);
+    }
+}
+
+impl IntRLEv2 {
+    pub fn new(compression: &Compression, signed: bool) -> Self {
+        IntRLEv2 {
+            sink: CompressionStream::new(compression),
+            signed,
+            literals: Vec::with_capacity(MAX_SCOPE),
+            prev_delta: 0,
+            fixed_run_len: 0,
+            variable_run_len: 0,
+        }
+    }
+
+    pub fn position(&self) -> IntRLEv2Position {
+        IntRLEv2Position {
+            inner: self.sink.position(),
+            rle_offset: self.literals.len() as u64,
+        }
+    }
+
+    /// The stream the encoded values are written to.
+    pub fn sink(&self) -> &CompressionStream {
+        &self.sink
+    }
+
+    fn init_literals(&mut self, x: i64) {
+        self.literals.push(x);
+        self.fixed_run_len = 1;
+        self.variable_run_len = 1;
+    }
+
+    #[inline(always)]
+    pub fn write(&mut self, x: i64) {
+        let len = self.literals.len();
+        if len == 0 {
+            self.init_literals(x);
+            return;
+        }
+        if len == 1 {
+            self.prev_delta = x.wrapping_sub(self.literals[0]);
+            self.literals.push(x);
+            if x == self.literals[0] {
+                self.fixed_run_len = 2;
+                self.variable_run_len = 0;
+            } else {
+                self.fixed_run_len = 0;
+                self.variable_run_len = 2;
+            }
+            return;
+        }
+
+        let delta = x.wrapping_sub(self.literals[len - 1]);
+        if self.prev_delta == 0 && delta == 0 {
+            self.literals.push(x);
+            // Repeated values at the end of a variable run start a fixed run
+            if self.variable_run_len > 0 {
+                self.fixed_run_len = 2;
+            }
+            self.fixed_run_len += 1;
+            if self.fixed_run_len >= MIN_REPEAT && self.variable_run_len > 0 {
+                // Flush the variable run, keeping its repeated tail as the start of the fixed run
+                let tail = self.literals.split_off(self.literals.len() - MIN_REPEAT);
+                self.write_variable_run();
+                self.literals = tail;
+                self.fixed_run_len = MIN_REPEAT;
+                self.variable_run_len = 0;
+            }
+            if self.fixed_run_len == MAX_SCOPE {
+                self.write_fixed_delta(0);
+            }
+        } else {
+            if self.fixed_run_len >= MIN_REPEAT {
+                if self.fixed_run_len <= MAX_SHORT_REPEAT_LENGTH {
+                    self.write_short_repeat();
+                } else {
+                    self.write_fixed_delta(0);
+                }
+            }
+            if self.fixed_run_len > 0 && self.fixed_run_len < MIN_REPEAT && x != self.literals[len - 1] {
+                self.variable_run_len = self.fixed_run_len;
+                self.fixed_run_len = 0;
+            }
+            if self.literals.is_empty() {
+                self.init_literals(x);
+            } else {
+                self.prev_delta = x.wrapping_sub(*self.literals.last().unwrap());
+                self.literals.push(x);
+                self.variable_run_len += 1;
+                if self.literals.len() == MAX_SCOPE {
+                    self.write_variable_run();
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self) {
+        self.literals.clear();
+        self.prev_delta = 0;
+        self.fixed_run_len = 0;
+        self.variable_run_len = 0;
+    }
+
+    fn write_header(&mut self, kind: u8, width_code: u8, len: usize) {
+        // The length is stored minus one, in 9 bits
+        let len = len - 1;
+        self.sink.write_u8(kind << 6 | width_code << 1 | ((len >> 8) & 1) as u8);
+        self.sink.write_u8(len as u8);
+    }
+
+    fn write_first_value(&mut self) {
+        if self.signed {
+            self.literals[0].write_varint(&mut self.sink);
+        } else {
+            (self.literals[0] as u64).write_varint(&mut self.sink);
+        }
+    }
+
+    fn write_short_repeat(&mut self) {
+        let x = if self.signed { zigzag(self.literals[0]) } else { self.literals[0] as u64 };
+        let num_bytes = (num_bits(x) + 7) / 8;
+        let len = self.literals.len();
+        self.sink.write_u8(SHORT_REPEAT << 6 | ((num_bytes - 1) << 3) as u8 | (len - MIN_REPEAT) as u8);
+        for i in (0..num_bytes).rev() {
+            self.sink.write_u8((x >> (i * 8)) as u8);
+        }
+        self.clear();
+    }
+
+    fn write_fixed_delta(&mut self, delta: i64) {
+        self.write_header(DELTA, 0, self.literals.len());
+        self.write_first_value();
+        delta.write_varint(&mut self.sink);
+        self.clear();
+    }
+
+    fn write_delta(&mut self, delta_base: i64, deltas: &[u64], width: u32) {
+        // A width code of 0 denotes a fixed delta, so 1-bit deltas are stored in 2 bits
+        let width = if width == 1 { 2 } else { width };
+        self.write_header(DELTA, encode_bit_width(width), self.literals.len());
+        self.write_first_value();
+        delta_base.write_varint(&mut self.sink);
+        write_packed(&mut self.sink, deltas, width);
+        self.clear();
+    }
+
+    fn write_direct(&mut self, values: &[u64], width: u32) {
+        self.write_header(DIRECT, encode_bit_width(width), values.len());
+        write_packed(&mut self.sink, values, width);
+        self.clear();
+    }
+
+    fn write_patched_base(&mut self, base: i64, mut base_reduced: Vec<u64>, bits_95p: u32, bits_100p: u32) {
+        let mut width = bits_95p;
+        let mut patch_width = closest_fixed_bits(bits_100p - bits_95p);
+        // Gaps and patches are packed together in 64 bits
+        if patch_width == 64 {
+            patch_width = 56;
+            width = 8;
+        }
+        let mask = (1u64 << width) - 1;
+
+        let mut patches: Vec<(usize, u64)> = Vec::new();
+        let mut prev = 0;
+        let mut max_gap = 0;
+        for (i, x) in base_reduced.iter_mut().enumerate() {
+            if *x > mask {
+                max_gap = max_gap.max(i - prev);
+                patches.push((i - prev, *x >> width));
+                prev = i;
+                *x &= mask;
+            }
+        }
+        // Gaps wider than 8 bits are split into entries with a gap of 255 and no patch
+        let patch_gap_width = num_bits(max_gap as u64).min(8);
+        let mut patch_list: Vec<u64> = Vec::new();
+        for (mut gap, patch) in patches {
+            while gap > 255 {
+                patch_list.push(255 << patch_width);
+                gap -= 255;
+            }
+            patch_list.push((gap as u64) << patch_width | patch);
+        }
+
+        // The base is stored in sign-magnitude form, in as few bytes as possible
+        let mut base_bits = base.unsigned_abs();
+        let base_bytes = (num_bits(base_bits) + 1 + 7) / 8;
+        if base < 0 {
+            base_bits |= 1 << (base_bytes * 8 - 1);
+        }
+
+        self.write_header(PATCHED_BASE, encode_bit_width(width), base_reduced.len());
+        self.sink.write_u8(((base_bytes - 1) << 5) as u8 | encode_bit_width(patch_width));
+        self.sink.write_u8(((patch_gap_width - 1) << 5) as u8 | patch_list.len() as u8);
+        for i in (0..base_bytes).rev() {
+            self.sink.write_u8((base_bits >> (i * 8)) as u8);
+        }
+        write_packed(&mut self.sink, &base_reduced, width);
+        write_packed(&mut self.sink, &patch_list, closest_fixed_bits(patch_gap_width + patch_width));
+        self.clear();
+    }
+
+    /// Picks the sub-encoding for the values buffered in `literals`, and writes them.
+    fn write_variable_run(&mut self) {
+        let len = self.literals.len();
+        let zigzag_literals: Vec<u64> = if self.signed {
+            self.literals.iter().map(|&x| zigzag(x)).collect()
+        } else {
+            self.literals.iter().map(|&x| x as u64).collect()
+        };
+        let zigzag_bits_100p = percentile_bits(&zigzag_literals, 1.0);
+        if len <= MIN_REPEAT {
+            self.write_direct(&zigzag_literals, zigzag_bits_100p);
+            return;
+        }
+
+        let mut is_increasing = true;
+        let mut is_decreasing = true;
+        let mut is_fixed_delta = true;
+        let mut min = self.literals[0];
+        let mut max = self.literals[0];
+        let initial_delta = self.literals[1].wrapping_sub(self.literals[0]);
+        let mut deltas: Vec<u64> = Vec::with_capacity(len - 2);
+        for i in 1..len {
+            let (x0, x1) = (self.literals[i - 1], self.literals[i]);
+            let delta = x1.wrapping_sub(x0);
+            min = min.min(x1);
+            max = max.max(x1);
+            is_increasing &= x0 <= x1;
+            is_decreasing &= x0 >= x1;
+            is_fixed_delta &= delta == initial_delta;
+            if i > 1 {
+                deltas.push(delta.unsigned_abs());
+            }
+        }
+
+        // Deltas may overflow, in which case neither DELTA nor PATCHED_BASE can be used
+        if max.checked_sub(min).is_none() {
+            self.write_direct(&zigzag_literals, zigzag_bits_100p);
+            return;
+        }
+        if is_fixed_delta {
+            self.write_fixed_delta(initial_delta);
+            return;
+        }
+        // The sign of the deltas is given by the first one, so it must not be zero
+        if initial_delta != 0 && (is_increasing || is_decreasing) {
+            let width = num_bits(deltas.iter().copied().max().unwrap_or(0));
+            self.write_delta(initial_delta, &deltas, width);
+            return;
+        }
+
+        // Patching pays off if a few values need many more bits than the rest
+        let zigzag_bits_90p = percentile_bits(&zigzag_literals, 0.9);
+        if zigzag_bits_100p - zigzag_bits_90p > 1 && min > -BASE_VALUE_LIMIT && min < BASE_VALUE_LIMIT {
+            let base_reduced: Vec<u64> = self.literals.iter().map(|&x| x.wrapping_sub(min) as u64).collect();
+            let bits_95p = percentile_bits(&base_reduced, 0.95);
+            let bits_100p = percentile_bits(&base_reduced, 1.0);
+            if bits_100p != bits_95p {
+                self.write_patched_base(min, base_reduced, bits_95p, bits_100p);
+                return;
+            }
+        }
+        self.write_direct(&zigzag_literals, zigzag_bits_100p);
+    }
+
+    fn flush(&mut self) {
+        if self.literals.is_empty() {
+            return;
+        }
+        if self.variable_run_len > 0 || self.fixed_run_len < MIN_REPEAT {
+            self.write_variable_run();
+        } else if self.fixed_run_len <= MAX_SHORT_REPEAT_LENGTH {
+            self.write_short_repeat();
+        } else {
+            self.write_fixed_delta(0);
+        }
+    }
+
+    /// Encodes the buffered values and compresses the remaining data of the stream.
+    pub fn finish_encoding(&mut self) {
+        self.flush();
+        self.sink.compress_remaining();
+    }
+
+    pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
+        self.finish_encoding();
+        self.sink.finish(w)
+    }
+
+    pub fn estimated_size(&self) -> usize {
+        self.sink.estimated_size()
+    }
+}
+
+pub struct SignedIntRLEv2(IntRLEv2);
+
+impl SignedIntRLEv2 {
+    pub fn new(compression: &Compression) -> Self {
+        SignedIntRLEv2(IntRLEv2::new(compression, true))
+    }
+
+    #[inline(always)]
+    pub fn write(&mut self, x: i64) {
+        self.0.write(x);
+    }
+
+    pub fn position(&self) -> IntRLEv2Position {
+        self.0.position()
+    }
+
+    pub fn sink(&self) -> &CompressionStream {
+        self.0.sink()
+    }
+
+    pub fn finish_encoding(&mut self) {
+        self.0.finish_encoding();
+    }
+
+    pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
+        self.0.finish(w)
+    }
+
+    pub fn estimated_size(&self) -> usize {
+        self.0.estimated_size()
+    }
+}
+
+pub struct UnsignedIntRLEv2(IntRLEv2);
+
+impl UnsignedIntRLEv2 {
+    pub fn new(compression: &Compression) -> Self {
+        UnsignedIntRLEv2(IntRLEv2::new(compression, false))
+    }
+
+    #[inline(always)]
+    pub fn write(&mut self, x: u64) {
+        self.0.write(x as i64);
+    }
+
+    pub fn position(&self) -> IntRLEv2Position {
+        self.0.position()
+    }
+
+    pub fn sink(&self) -> &CompressionStream {
+        self.0.sink()
+    }
+
+    pub fn finish_encoding(&mut self) {
+        self.0.finish_encoding();
+    }
+
+    pub fn finish<W: Write>(&mut self, w: &mut W) -> Result<()> {
+        self.0.finish(w)
+    }
+
+    pub fn estimated_size(&self) -> usize {
+        self.0.estimated_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::compression::NoCompression;
+
+    fn encode_unsigned(input: &[u64]) -> Vec<u8> {
+        let mut rle = UnsignedIntRLEv2::new(&NoCompression::new().build());
+        for &x in input {
+            rle.write(x);
+        }
+        let mut out: Vec<u8> = Vec::new();
+        rle.finish(&mut out).unwrap();
+        out
+    }
+
+    fn encode_signed(input: &[i64]) -> Vec<u8> {
+        let mut rle = SignedIntRLEv2::new(&NoCompression::new().build());
+        for &x in input {
+            rle.write(x);
+        }
+        let mut out: Vec<u8> = Vec::new();
+        rle.finish(&mut out).unwrap();
+        out
+    }
+
+    // Examples from the ORC specification, except for DELTA, where the specification uses
+    // byte-aligned bit widths
+    #[test]
+    fn test_unsigned_int_rle_v2() {
+        let cases: Vec<(Vec<u64>, Vec<u8>)> = vec![
+            (vec![], vec![]),
+            (vec![10000; 5], vec![0x0a, 0x27, 0x10]),
+            (vec![23713, 43806, 57005, 48879], vec![0x5e, 0x03, 0x5c, 0xa1, 0xab, 0x1e, 0xde, 0xad, 0xbe, 0xef]),
+            (
+                vec![
+                    2030, 2000, 2020, 1000000, 2040, 2050, 2060, 2070, 2080, 2090, 2100, 2110, 2120,
+                    2130, 2140, 2150, 2160, 2170, 2180, 2190,
+                ],
+                vec![
+                    0x8e, 0x13, 0x2b, 0x21, 0x07, 0xd0, 0x1e, 0x00, 0x14, 0x70, 0x28, 0x32, 0x3c, 0x46,
+                    0x50, 0x5a, 0x64, 0x6e, 0x78, 0x82, 0x8c, 0x96, 0xa0, 0xaa, 0xb4, 0xbe, 0xfc, 0xe8,
+                ],
+            ),
+            (vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29], vec![0xc4, 0x09, 0x02, 0x02, 0x4a, 0x28, 0xa6]),
+            (vec![7; 20], vec![0xc0, 0x13, 0x07, 0x00]),
+        ];
+        for (input, expected_output) in cases {
+            assert_eq!(encode_unsigned(&input), expected_output);
+        }
+    }
+
+    #[test]
+    fn test_signed_int_rle_v2() {
+        let cases: Vec<(Vec<i64>, Vec<u8>)> = vec![
+            (vec![-10000; 5], vec![0x0a, 0x4e, 0x1f]),
+            (vec![10, 8, 6, 4], vec![0xc0, 0x03, 20, 3]),
+            (vec![0, -1, 1], vec![0x42, 0x02, 0b00011000]),
+            // A variable run followed by a fixed run
+            (vec![1, 5, 5, 5, 5], vec![0x42, 0x00, 0b1000_0000, 0x01, 0x0a]),
+        ];
+        for (input, expected_output) in cases {
+            assert_eq!(encode_signed(&input), expected_output);
+        }
+    }
+
+    #[test]
+    fn test_bit_widths() {
+        assert_eq!(encode_bit_width(0), 0);
+        assert_eq!(encode_bit_width(24), 23);
+        assert_eq!(encode_bit_width(25), 24);
+        assert_eq!(encode_bit_width(33), 28);
+        assert_eq!(encode_bit_width(64), 31);
+        assert_eq!(percentile_bits(&[1, 2, 3, 1000], 1.0), 10);
+        assert_eq!(percentile_bits(&[0; 20], 0.9), 1);
+        let mut values = vec![3; 19];
+        values.push(1 << 20);
+        assert_eq!(percentile_bits(&values, 0.9), 2);
+    }
+}
@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use crate::protos::orc_proto;
 
 pub use common::BaseStatistics;
@@ -24,6 +26,8 @@ mod binary;
 pub enum Statistics {
     Boolean(BooleanStatistics),
     Long(LongStatistics),
+    /// Statistics of a Date column, in days since the UNIX epoch.
+    Date(LongStatistics),
     Double(DoubleStatistics),
     Decimal(DecimalStatistics),
     Timestamp(TimestampStatistics),
@@ -41,6 +45,10 @@ impl Statistics {
         if let Statistics::Long(x) = self { x } else { panic!("invalid argument to unwrap_long"); }
     }
 
+    pub fn unwrap_date(&self) -> &LongStatistics {
+        if let Statistics::Date(x) = self { x } else { panic!("invalid argument to unwrap_date"); }
+    }
+
     pub fn unwrap_decimal(&self) -> &DecimalStatistics { 
         if let Statistics::Decimal(x) = self { x } else { panic!("invalid argument to unwrap_decimal"); }
     }
@@ -82,6 +90,13 @@ impl Statistics {
                 if let Some(x) = long_statistics.sum { int_stat.set_sum(x); }
                 stat.set_intStatistics(int_stat);
             }
+            Statistics::Date(date_statistics) => {
+                // Dates which don't fit the 32-bit statistics are left out, as unknown bounds
+                let mut date_stat = orc_proto::DateStatistics::new();
+                if let Some(Ok(x)) = date_statistics.min.map(i32::try_from) { date_stat.set_minimum(x); }
+                if let Some(Ok(x)) = date_statistics.max.map(i32::try_from) { date_stat.set_maximum(x); }
+                stat.set_dateStatistics(date_stat);
+            }
             Statistics::Decimal(d) => {
                 let mut dec_stat = orc_proto::DecimalStatistics::new();
                 if let Some(x) = d.min { dec_stat.set_minimum(d.format(x)); }
@@ -90,9 +105,17 @@ impl Statistics {
                 stat.set_decimalStatistics(dec_stat);
             }
             Statistics::Timestamp(d) => {
+                // Timestamps are written without a time zone, so the bounds in the writer's time zone
+                // (read by readers predating ORC-135) are the same as the UTC bounds.
                 let mut ts_stat = orc_proto::TimestampStatistics::new();
-                if let Some(x) = d.min_epoch_millis { ts_stat.set_minimum(x); }
-                if let Some(x) = d.max_epoch_millis { ts_stat.set_maximum(x); }
+                if let Some(x) = d.min_epoch_millis {
+                    ts_stat.set_minimum(x);
+                    ts_stat.set_minimumUtc(x);
+                }
+                if let Some(x) = d.max_epoch_millis {
+                    ts_stat.set_maximum(x);
+                    ts_stat.set_maximumUtc(x);
+                }
                 stat.set_timestampStatistics(ts_stat);
             }
             Statistics::Double(double_statistics) => {
@@ -125,6 +148,7 @@ impl BaseStatistics for Statistics {
         match self {
             Statistics::Boolean(x) => x.update_null(),
             Statistics::Long(x) => x.update_null(),
+            Statistics::Date(x) => x.update_null(),
             Statistics::Double(x) => x.update_null(),
             Statistics::Decimal(x) => x.update_null(),
             Statistics::Timestamp(x) => x.update_null(),
@@ -138,6 +162,7 @@ impl BaseStatistics for Statistics {
         match self {
             Statistics::Boolean(x) => x.num_values(),
             Statistics::Long(x) => x.num_values(),
+            Statistics::Date(x) => x.num_values(),
             Statistics::Double(x) => x.num_values(),
             Statistics::Decimal(x) => x.num_values(),
             Statistics::Timestamp(x) => x.num_values(),
@@ -151,6 +176,7 @@ impl BaseStatistics for Statistics {
         match self {
             Statistics::Boolean(x) => x.num_present(),
             Statistics::Long(x) => x.num_present(),
+            Statistics::Date(x) => x.num_present(),
             Statistics::Double(x) => x.num_present(),
             Statistics::Decimal(x) => x.num_present(),
             Statistics::Timestamp(x) => x.num_present(),
@@ -164,6 +190,7 @@ impl BaseStatistics for Statistics {
         match self {
             Statistics::Boolean(x) => x.merge(rhs.unwrap_boolean()),
             Statistics::Long(x) => x.merge(rhs.unwrap_long()),
+            Statistics::Date(x) => x.merge(rhs.unwrap_date()),
             Statistics::Double(x) => x.merge(rhs.unwrap_double()),
             Statistics::Decimal(x) => x.merge(rhs.unwrap_decimal()),
             Statistics::Timestamp(x) => x.merge(rhs.unwrap_timestamp()),
@@ -1,6 +1,7 @@
 use std::io::{Write, Result};
 use protobuf::{CodedOutputStream, RepeatedField, Message};
 
+use crate::error::OrcError;
 use crate::protos::orc_proto;
 use crate::schema::Schema;
 
@@ -46,10 +47,9 @@ impl Stripe {
         }
     }
 
-    pub fn write_batch(&mut self, num_rows: u64) -> Result<()> {
+    pub fn write_batch(&mut self, num_rows: u64) -> std::result::Result<(), OrcError> {
         self.num_rows += num_rows;
-        self.data.verify_row_count(self.num_rows);
-        Ok(())
+        self.data.verify_row_count(self.num_rows)
     }
 
     fn write_footer<W: Write>(&mut self, out: &mut W, stream_infos: &[StreamInfo]) -> Result<()> {
@@ -80,6 +80,8 @@ impl Stripe {
     pub fn finish<W: Write>(&mut self, out: &mut CountWrite<W>, stripe_infos_out: &mut Vec<StripeInfo>) -> Result<()> {
         if self.num_rows == 0 { return Ok(()) }
         let mut stream_infos: Vec<StreamInfo> = Vec::new();
+        // Finishing the encoding merges the last row group into the statistics of the stripe
+        self.data.finish_encoding();
         let mut statistics: Vec<Statistics> = Vec::new();
         self.data.statistics(&mut statistics);
 
@@ -0,0 +1,165 @@
+"""Generates lz4_liblz4.orc, a small LZ4-compressed ORC file for the reader tests.
+
+No ORC library (C++, Java or pyarrow) is available to the build, so the file is assembled here,
+following the layout of files written by the C++ library with the options
+
+    compression LZ4, compression block size 4096, file version 0.11, row index stride 0
+
+and its compression blocks are compressed by the system liblz4, as in the C++ library, with
+LZ4_compress_default. A block whose compressed form is not smaller is stored as original. The
+writer field of the footer is left unset, as the file is not written by a registered writer.
+
+The file has 3000 rows of struct<x:bigint,s:string>, where row i has x = (i * i) % 1000 - 500 and
+s = "value-%04d" % (i % 50).
+
+Run with: python3 testdata/make_lz4_liblz4.py
+"""
+
+import ctypes
+import ctypes.util
+import os
+
+BLOCK_SIZE = 4096
+NUM_ROWS = 3000
+
+lz4 = ctypes.CDLL(ctypes.util.find_library("lz4") or "liblz4.so.1")
+
+
+def lz4_compress(data):
+    bound = lz4.LZ4_compressBound(len(data))
+    out = ctypes.create_string_buffer(bound)
+    n = lz4.LZ4_compress_default(data, out, len(data), bound)
+    assert n > 0
+    return out.raw[:n]
+
+
+def compress(data):
+    """Splits `data` in compression blocks, each with its 3 byte header."""
+    out = b""
+    for i in range(0, len(data), BLOCK_SIZE):
+        block = data[i:i + BLOCK_SIZE]
+        compressed = lz4_compress(block)
+        if len(compressed) < len(block):
+            header = len(compressed) << 1
+        else:
+            compressed, header = block, (len(block) << 1) | 1
+        out += header.to_bytes(3, "little") + compressed
+    return out
+
+
+# Protobuf encoding
+
+def varint(x):
+    out = b""
+    while True:
+        if x < 0x80:
+            return out + bytes([x])
+        out += bytes([(x & 0x7f) | 0x80])
+        x >>= 7
+
+
+def zigzag(x):
+    return (x << 1) ^ (x >> 63)
+
+
+def field_varint(number, x):
+    return varint(number << 3) + varint(x)
+
+
+def field_bytes(number, data):
+    return varint((number << 3) | 2) + varint(len(data)) + data
+
+
+def field_packed(number, values):
+    return field_bytes(number, b"".join(varint(x) for x in values))
+
+
+# Run length encoding version 1
+
+def rle_v1(values, signed):
+    encode = (lambda x: varint(zigzag(x))) if signed else varint
+    out = b""
+    literals = []
+
+    def flush_literals():
+        nonlocal out, literals
+        if literals:
+            out += bytes([256 - len(literals)]) + b"".join(encode(x) for x in literals)
+            literals = []
+
+    i = 0
+    while i < len(values):
+        run = 1
+        if i + 1 < len(values):
+            delta = values[i + 1] - values[i]
+            if -128 <= delta <= 127:
+                run = 2
+                while run < 130 and i + run < len(values) and values[i + run] - values[i + run - 1] == delta:
+                    run += 1
+        if run >= 3:
+            flush_literals()
+            out += bytes([run - 3, delta & 0xff]) + encode(values[i])
+            i += run
+        else:
+            literals.append(values[i])
+            if len(literals) == 128:
+                flush_literals()
+            i += 1
+    flush_literals()
+    return out
+
+
+def main():
+    xs = [(i * i) % 1000 - 500 for i in range(NUM_ROWS)]
+    ss = [("value-%04d" % (i % 50)).encode() for i in range(NUM_ROWS)]
+
+    # Stripe data, in the order of the stripe footer
+    streams = [
+        (1, 1, compress(rle_v1(xs, True))),                    # x DATA
+        (2, 1, compress(b"".join(ss))),                        # s DATA
+        (2, 2, compress(rle_v1([len(s) for s in ss], False))), # s LENGTH
+    ]
+    data = b"".join(s[2] for s in streams)
+    stripe_footer = b"".join(
+        field_bytes(1, field_varint(1, kind) + field_varint(2, column) + field_varint(3, len(stream)))
+        for column, kind, stream in streams)
+    stripe_footer += b"".join(field_bytes(2, field_varint(1, 0)) for _ in range(3))  # DIRECT
+    stripe_footer += field_bytes(3, b"GMT")
+    stripe_footer = compress(stripe_footer)
+
+    def statistics():
+        int_stats = field_varint(1, zigzag(min(xs))) + field_varint(2, zigzag(max(xs))) + field_varint(3, zigzag(sum(xs)))
+        string_stats = field_bytes(1, min(ss)) + field_bytes(2, max(ss)) + field_varint(3, zigzag(sum(len(s) for s in ss)))
+        return [
+            field_varint(1, NUM_ROWS) + field_varint(10, 0),
+            field_varint(1, NUM_ROWS) + field_bytes(2, int_stats) + field_varint(10, 0),
+            field_varint(1, NUM_ROWS) + field_bytes(4, string_stats) + field_varint(10, 0),
+        ]
+
+    header = b"ORC"
+    metadata = compress(field_bytes(1, b"".join(field_bytes(1, s) for s in statistics())))
+
+    stripe_info = (field_varint(1, len(header)) + field_varint(2, 0) + field_varint(3, len(data))
+                   + field_varint(4, len(stripe_footer)) + field_varint(5, NUM_ROWS))
+    types = [
+        field_varint(1, 12) + field_packed(2, [1, 2]) + field_bytes(3, b"x") + field_bytes(3, b"s"),
+        field_varint(1, 4),
+        field_varint(1, 7),
+    ]
+    footer = (field_varint(1, len(header)) + field_varint(2, len(data) + len(stripe_footer))
+              + field_bytes(3, stripe_info) + b"".join(field_bytes(4, t) for t in types)
+              + field_varint(6, NUM_ROWS) + b"".join(field_bytes(7, s) for s in statistics())
+              + field_varint(8, 0))
+    footer = compress(footer)
+
+    postscript = (field_varint(1, len(footer)) + field_varint(2, 4) + field_varint(3, BLOCK_SIZE)
+                  + field_packed(4, [0, 11]) + field_varint(5, len(metadata)) + field_bytes(8000, b"ORC"))
+    contents = header + data + stripe_footer + metadata + footer + postscript + bytes([len(postscript)])
+
+    path = os.path.join(os.path.dirname(os.path.abspath(__file__)), "lz4_liblz4.orc")
+    with open(path, "wb") as f:
+        f.write(contents)
+
+
+if __name__ == "__main__":
+    main()